@@ -0,0 +1,46 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct ScreenEffects {
+    vignette: f32,
+    aberration: f32,
+    radial_blur: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var screen_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var screen_sampler: sampler;
+
+@group(0) @binding(2)
+var<uniform> effects: ScreenEffects;
+
+const BLUR_SAMPLES: i32 = 8;
+
+// Red and blue are pulled apart along the direction from the screen center
+fn sample_split(uv: vec2<f32>, from_center: vec2<f32>) -> vec3<f32> {
+    let shift = from_center * effects.aberration;
+    return vec3<f32>(
+        textureSample(screen_texture, screen_sampler, uv + shift).r,
+        textureSample(screen_texture, screen_sampler, uv).g,
+        textureSample(screen_texture, screen_sampler, uv - shift).b,
+    );
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let from_center = in.uv - vec2<f32>(0.5);
+
+    // Radial blur averages samples along the line toward the center
+    var color = vec3<f32>(0.0);
+    for (var i = 0; i < BLUR_SAMPLES; i += 1) {
+        let t = f32(i) / f32(BLUR_SAMPLES) * effects.radial_blur;
+        color += sample_split(in.uv - from_center * t, from_center);
+    }
+    color /= f32(BLUR_SAMPLES);
+
+    let edge = smoothstep(0.35, 0.85, length(from_center) * 1.414);
+    color = mix(color, vec3<f32>(0.5, 0.0, 0.0), edge * effects.vignette);
+    return vec4<f32>(color, 1.0);
+}
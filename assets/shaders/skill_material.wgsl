@@ -1,20 +1,64 @@
 #import bevy_pbr::forward_io::VertexOutput
 
 struct FrameData {
+    // xy is the frame's UV offset, zw its UV size
     frame: vec4<f32>,
+    // x is the inset in texels kept between sampling and the frame edge
+    inset: vec4<f32>,
+    // xy is the share of the quad the frame covers, zw where it starts
+    fit: vec4<f32>,
+    // `frame` and `fit` of the frame shown before a clip switch
+    previous_frame: vec4<f32>,
+    previous_fit: vec4<f32>,
+    // x is how much of the previous frame is still blended in, 0 for none
+    blend: vec4<f32>,
 }
 
-@group(1) @binding(0)
+@group(2) @binding(0)
 var<uniform> frame_data: FrameData;
 
-@group(1) @binding(1)
+@group(2) @binding(1)
 var skill_texture: texture_2d<f32>;
 
-@group(1) @binding(2)
+@group(2) @binding(2)
 var skill_sampler: sampler;
 
+@group(2) @binding(3)
+var<uniform> tint: vec4<f32>;
+
+struct FrameSample {
+    color: vec4<f32>,
+    covered: bool,
+}
+
+fn sample_frame(uv: vec2<f32>, frame: vec4<f32>, fit: vec4<f32>) -> FrameSample {
+    // Frames smaller than the quad leave the rest of it empty. Sampled either
+    // way, as texture samples have to stay in uniform control flow.
+    let local = (uv - fit.zw) / fit.xy;
+    let covered = all(local >= vec2<f32>(0.0)) && all(local <= vec2<f32>(1.0));
+    // Clamp inside the frame so filtering never picks up its neighbours
+    let texel = frame_data.inset.x / vec2<f32>(textureDimensions(skill_texture));
+    let frame_min = frame.xy + texel;
+    let frame_max = max(frame.xy + frame.zw - texel, frame_min);
+    let frame_uv = clamp(local * frame.zw + frame.xy, frame_min, frame_max);
+    let color = textureSample(skill_texture, skill_sampler, frame_uv);
+    return FrameSample(select(vec4<f32>(0.0), color, covered), covered);
+}
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let uv = in.uv * frame_data.frame.zw + frame_data.frame.xy;
-    return textureSample(skill_texture, skill_sampler, uv);
+    let current = sample_frame(in.uv, frame_data.frame, frame_data.fit);
+    let weight = frame_data.blend.x;
+    if weight <= 0.0 {
+        if !current.covered {
+            discard;
+        }
+        return current.color * tint;
+    }
+    // Crossfading from the previous clip's last frame
+    let previous = sample_frame(in.uv, frame_data.previous_frame, frame_data.previous_fit);
+    if !current.covered && !previous.covered {
+        discard;
+    }
+    return mix(current.color, previous.color, weight) * tint;
 }
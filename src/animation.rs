@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+use std::collections::VecDeque;
+
+use crate::time_scale::TimeScale;
+
+// Inclusive range of atlas frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Reflect)]
+pub struct FrameRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+// Frames a clip plays, written either as a range, `(start: 1, end: 8)`, or
+// as a list for sheets with gaps or out of order cells, `[0, 2, 3, 7]`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ClipFrames {
+    Range(FrameRange),
+    List(Vec<usize>),
+}
+
+impl ClipFrames {
+    // Frames in play order, leaving out any past `last`. Never empty, a clip
+    // with nothing valid shows `last`.
+    pub fn resolve(&self, last: usize) -> Vec<usize> {
+        let frames: Vec<usize> = match self {
+            ClipFrames::Range(range) => (range.start..=range.end).collect(),
+            ClipFrames::List(frames) => frames.clone(),
+        };
+        let frames: Vec<usize> = frames.into_iter().filter(|frame| *frame <= last).collect();
+        if frames.is_empty() {
+            vec![last]
+        } else {
+            frames
+        }
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct AnimationClip {
+    // Atlas indices in play order
+    pub frames: Vec<usize>,
+    pub frame_duration: f32,
+    pub repeat: bool,
+}
+
+impl AnimationClip {
+    pub fn once(frames: Vec<usize>, frame_duration: f32) -> Self {
+        Self {
+            frames,
+            frame_duration,
+            repeat: false,
+        }
+    }
+
+    pub fn looping(frames: Vec<usize>, frame_duration: f32) -> Self {
+        Self {
+            frames,
+            frame_duration,
+            repeat: true,
+        }
+    }
+}
+
+// Plays named frame clips into the entity's `TextureAtlas`, which works the
+// same for skill quads, billboarded sprites and UI images
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Animator {
+    clips: HashMap<String, AnimationClip>,
+    current: Option<String>,
+    queued: VecDeque<String>,
+    // Position in the current clip's frames, and the atlas index it shows
+    step: usize,
+    frame: usize,
+    timer: Timer,
+    // Frame shown before the last clip switch, until taken
+    switched_from: Option<usize>,
+}
+
+// Sent when a clip that doesn't repeat shows its last frame through, or when
+// a repeating clip wraps while something is queued after it
+#[derive(Event, Debug, Clone)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub clip: String,
+}
+
+impl Animator {
+    pub fn with_clip(mut self, name: impl Into<String>, clip: AnimationClip) -> Self {
+        self.clips.insert(name.into(), clip);
+        self
+    }
+
+    pub fn has_clip(&self, name: &str) -> bool {
+        self.clips.contains_key(name)
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    // Switches to `name` right away and drops anything queued
+    pub fn play(&mut self, name: &str) -> bool {
+        let Some(clip) = self.clips.get(name) else {
+            return false;
+        };
+        if self.current.is_some() {
+            self.switched_from = Some(self.frame);
+        }
+        self.step = 0;
+        self.frame = clip.frames.first().copied().unwrap_or_default();
+        self.timer = Timer::from_seconds(clip.frame_duration, TimerMode::Repeating);
+        self.current = Some(name.to_string());
+        self.queued.clear();
+        true
+    }
+
+    // Plays `name` once the current clip finishes, or right away when idle
+    pub fn queue(&mut self, name: &str) {
+        if self.current.is_none() {
+            self.play(name);
+        } else if self.clips.contains_key(name) {
+            self.queued.push_back(name.to_string());
+        }
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    // Frame shown right before the last `play`, kept until cleared so
+    // effects like crossfades can pick it up
+    pub fn switched_from(&self) -> Option<usize> {
+        self.switched_from
+    }
+
+    pub fn clear_switched_from(&mut self) {
+        self.switched_from = None;
+    }
+
+    // Returns the name of a clip that finished this tick
+    fn tick(&mut self, delta: std::time::Duration) -> Option<String> {
+        let clip = self.clips.get(self.current.as_deref()?)?.clone();
+        self.timer.tick(delta);
+        let mut finished = None;
+        for _ in 0..self.timer.times_finished_this_tick() {
+            if self.step + 1 < clip.frames.len() {
+                self.step += 1;
+                self.frame = clip.frames[self.step];
+                continue;
+            }
+            if clip.repeat && self.queued.is_empty() {
+                self.step = 0;
+                self.frame = clip.frames.first().copied().unwrap_or_default();
+                continue;
+            }
+            finished = self.current.clone();
+            let queued = self.queued.pop_front();
+            match queued {
+                Some(next) => {
+                    let rest = std::mem::take(&mut self.queued);
+                    self.play(&next);
+                    self.queued = rest;
+                }
+                None => self.timer.pause(),
+            }
+            break;
+        }
+        finished
+    }
+}
+
+// Multiplies how fast the entity's animator plays, on top of its clips' frame
+// durations, so haste, slows and level of detail can change the pace without
+// touching the clips. Stacks with the entity's `TimeScale`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AnimationSpeed(pub f32);
+
+impl Default for AnimationSpeed {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationSystem;
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Animator>()
+            .register_type::<AnimationSpeed>()
+            .add_event::<AnimationFinished>()
+            .add_systems(Update, advance_animators.in_set(AnimationSystem));
+    }
+}
+
+fn advance_animators(
+    time: Res<Time>,
+    mut finished: EventWriter<AnimationFinished>,
+    mut animators: Query<(
+        Entity,
+        &mut Animator,
+        Option<&mut TextureAtlas>,
+        Option<&AnimationSpeed>,
+        Option<&TimeScale>,
+    )>,
+) {
+    let _span = info_span!("advance_animators", animators = animators.iter().len()).entered();
+    for (entity, mut animator, atlas, speed, time_scale) in animators.iter_mut() {
+        // Bounded like a time scale, the product can still overflow or be NaN
+        let speed =
+            TimeScale::clamp(speed.map_or(1.0, |speed| speed.0) * TimeScale::of(time_scale));
+        let delta = time.delta().mul_f32(speed);
+        if let Some(clip) = animator.tick(delta) {
+            finished.send(AnimationFinished { entity, clip });
+        }
+        if let Some(mut atlas) = atlas {
+            if atlas.index != animator.frame {
+                atlas.index = animator.frame;
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::EnemyKilled;
+use crate::hud_layout::SafeAreaAnchor;
+use crate::locale::{Locale, Localized};
+use crate::pause::GameState;
+use crate::prefab::PrefabSpawner;
+use crate::respawn::PlayerDied;
+use crate::save::{load_ron, save_ron};
+use crate::schedule::GameSet;
+use crate::settings::GameplaySettings;
+use crate::state_cleanup::DespawnOnExit;
+use crate::toast::ToastEvent;
+use crate::{players_center, Enemy, Player};
+
+pub const LEADERBOARD_PATH: &str = "leaderboard.ron";
+pub const RESTART_KEY: KeyCode = KeyCode::Enter;
+const LEADERBOARD_SIZE: usize = 10;
+
+// Survival mode: waves of enemies for a fixed time, scored by kills and wave
+// clears. The demo level skips its own enemies while this resource exists.
+#[derive(Resource, Clone)]
+pub struct Arena {
+    pub round_seconds: f32,
+    pub first_wave: u32,
+    // Extra enemies added with every wave
+    pub wave_growth: u32,
+    pub spawn_radius: f32,
+    pub points_per_kill: u32,
+    // Multiplied by the wave number
+    pub wave_clear_bonus: u32,
+    // Taken off the score every time the player dies
+    pub death_penalty: u32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self {
+            round_seconds: 180.0,
+            first_wave: 3,
+            wave_growth: 2,
+            spawn_radius: 7.0,
+            points_per_kill: 100,
+            wave_clear_bonus: 250,
+            death_penalty: 500,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ArenaRound {
+    pub timer: Timer,
+    pub wave: u32,
+    pub score: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub over: bool,
+}
+
+impl ArenaRound {
+    fn new(arena: &Arena) -> Self {
+        Self {
+            timer: Timer::from_seconds(arena.round_seconds, TimerMode::Once),
+            wave: 0,
+            score: 0,
+            kills: 0,
+            deaths: 0,
+            over: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub score: u32,
+    pub waves: u32,
+    pub kills: u32,
+}
+
+// Best arena rounds, persisted to `LEADERBOARD_PATH`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    // A missing or unreadable file starts an empty leaderboard
+    pub fn load() -> Self {
+        load_ron(LEADERBOARD_PATH)
+    }
+
+    pub fn save(&self) {
+        save_ron(LEADERBOARD_PATH, self);
+    }
+
+    // Returns the entry's place, if it made the board
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let place = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+        if place >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.entries.insert(place, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        Some(place)
+    }
+}
+
+#[derive(Component)]
+struct ArenaHud;
+
+#[derive(Component)]
+struct ArenaResults;
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Startup,
+            (start_arena, spawn_arena_hud).run_if(resource_exists::<Arena>),
+        )
+        .add_systems(
+            Update,
+            (
+                restart_round.in_set(GameSet::Input),
+                (score_kills, penalize_deaths, spawn_waves, end_round)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+                update_arena_hud.in_set(GameSet::Presentation),
+            )
+                .run_if(resource_exists::<ArenaRound>),
+        );
+    }
+}
+
+fn start_arena(mut commands: Commands, arena: Res<Arena>) {
+    commands.insert_resource(ArenaRound::new(&arena));
+}
+
+fn score_kills(
+    arena: Res<Arena>,
+    mut round: ResMut<ArenaRound>,
+    mut kills: EventReader<EnemyKilled>,
+) {
+    for _ in kills.read() {
+        if round.over {
+            continue;
+        }
+        round.kills += 1;
+        round.score += arena.points_per_kill;
+    }
+}
+
+fn penalize_deaths(
+    arena: Res<Arena>,
+    mut round: ResMut<ArenaRound>,
+    mut deaths: EventReader<PlayerDied>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for _ in deaths.read() {
+        if round.over {
+            continue;
+        }
+        round.deaths += 1;
+        round.score = round.score.saturating_sub(arena.death_penalty);
+        toasts.send(ToastEvent::new(
+            locale.format("arena.death_penalty", &[("points", &arena.death_penalty)]),
+        ));
+    }
+}
+
+// The next wave starts as soon as the last enemy of the current one is dead
+fn spawn_waves(
+    arena: Res<Arena>,
+    mut round: ResMut<ArenaRound>,
+    enemies: Query<(), With<Enemy>>,
+    players: Query<&Transform, With<Player>>,
+    mut prefabs: PrefabSpawner,
+    gameplay: Res<GameplaySettings>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if round.over || !enemies.is_empty() {
+        return;
+    }
+    // Waves ring the players' midpoint so nobody is left out of the fight
+    let Some(center) = players_center(players.iter().map(|player| player.translation)) else {
+        return;
+    };
+    if round.wave > 0 {
+        round.score += arena.wave_clear_bonus * round.wave;
+        toasts.send(ToastEvent::new(
+            locale.format("arena.wave_cleared", &[("wave", &round.wave)]),
+        ));
+    }
+    round.wave += 1;
+    let count = gameplay
+        .multipliers()
+        .scale_count(arena.first_wave + arena.wave_growth * (round.wave - 1));
+    for i in 0..count {
+        // Tougher enemies mix in as the waves go on
+        let prefab = match i % 4 {
+            1 if round.wave >= 2 => "bomber",
+            3 if round.wave >= 3 => "splitter",
+            _ => "enemy",
+        };
+        let angle = std::f32::consts::TAU * i as f32 / count as f32;
+        let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * arena.spawn_radius;
+        let transform = Transform::from_translation(center + offset);
+        if let Err(error) = prefabs.spawn(prefab, transform) {
+            warn!("{}", error);
+        }
+    }
+}
+
+fn end_round(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut round: ResMut<ArenaRound>,
+    enemies: Query<Entity, With<Enemy>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if round.over || !round.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    round.over = true;
+    next_state.set(GameState::GameOver);
+    for enemy in enemies.iter() {
+        commands.entity(enemy).despawn_recursive();
+    }
+
+    let mut leaderboard = Leaderboard::load();
+    let place = leaderboard.submit(LeaderboardEntry {
+        score: round.score,
+        waves: round.wave,
+        kills: round.kills,
+    });
+    leaderboard.save();
+    spawn_results(&mut commands, &round, &leaderboard, place);
+}
+
+fn spawn_results(
+    commands: &mut Commands,
+    round: &ArenaRound,
+    leaderboard: &Leaderboard,
+    place: Option<usize>,
+) {
+    let text = |localized: Localized, font_size: f32| {
+        (
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size,
+                    ..default()
+                },
+            ),
+            localized,
+        )
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+            ArenaResults,
+            DespawnOnExit(GameState::GameOver),
+        ))
+        .with_children(|results| {
+            results.spawn(text(Localized::new("arena.times_up"), 48.0));
+            results.spawn(text(
+                Localized::new("arena.summary")
+                    .with_arg("score", round.score)
+                    .with_arg("kills", round.kills)
+                    .with_arg("wave", round.wave),
+                24.0,
+            ));
+            if let Some(place) = place {
+                results.spawn(text(
+                    Localized::new("arena.new_place").with_arg("place", place + 1),
+                    20.0,
+                ));
+            }
+            for (i, entry) in leaderboard.entries.iter().enumerate() {
+                results.spawn(text(
+                    Localized::new("arena.leaderboard_row")
+                        .with_arg("place", format!("{:>2}", i + 1))
+                        .with_arg("score", format!("{:>6}", entry.score))
+                        .with_arg("wave", format!("{:>2}", entry.waves))
+                        .with_arg("kills", format!("{:>3}", entry.kills)),
+                    18.0,
+                ));
+            }
+            results.spawn(text(Localized::new("arena.restart"), 20.0));
+        });
+}
+
+fn restart_round(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    arena: Res<Arena>,
+    mut round: ResMut<ArenaRound>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !round.over || !keyboard_input.just_pressed(RESTART_KEY) {
+        return;
+    }
+    *round = ArenaRound::new(&arena);
+    // Leaving game over takes the results down; the last round's skills
+    // already ended with it
+    next_state.set(GameState::Playing);
+}
+
+fn spawn_arena_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 22.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+        ArenaHud,
+        SafeAreaAnchor::default(),
+    ));
+}
+
+fn update_arena_hud(
+    round: Res<ArenaRound>,
+    locale: Res<Locale>,
+    mut huds: Query<&mut Text, With<ArenaHud>>,
+) {
+    let remaining = round.timer.remaining_secs().ceil() as u32;
+    let time = format!("{}:{:02}", remaining / 60, remaining % 60);
+    for mut text in huds.iter_mut() {
+        text.sections[0].value = locale.format(
+            "arena.hud",
+            &[
+                ("time", &time),
+                ("score", &round.score),
+                ("wave", &round.wave),
+            ],
+        );
+    }
+}
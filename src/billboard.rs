@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use crate::camera::CameraMode;
+use crate::MainCamera;
+
+// Rotates the entity every frame so its quad faces the main camera, or lies
+// flat on the ground in the top-down camera mode
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Billboard;
+
+pub struct BillboardPlugin;
+
+impl Plugin for BillboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Billboard>().add_systems(
+            PostUpdate,
+            face_camera.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+// The camera's world rotation. `GlobalTransform` is only propagated after
+// this runs, so the camera's own rotation is combined with its parent's, e.g.
+// the player's in first-person, to not lag a frame behind mouse look.
+fn face_camera(
+    mode: Option<Res<CameraMode>>,
+    camera: Query<(&Transform, Option<&Parent>), (With<MainCamera>, Without<Billboard>)>,
+    parents: Query<&GlobalTransform>,
+    mut billboards: Query<&mut Transform, With<Billboard>>,
+) {
+    let rotation = match mode.as_deref() {
+        Some(CameraMode::TopDown) => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        _ => match camera.get_single() {
+            Ok((camera_transform, parent)) => {
+                let parent_rotation = parent
+                    .and_then(|parent| parents.get(parent.get()).ok())
+                    .map_or(Quat::IDENTITY, |parent| parent.compute_transform().rotation);
+                parent_rotation * camera_transform.rotation
+            }
+            Err(_) => return,
+        },
+    };
+    let _span = info_span!("face_camera", billboards = billboards.iter().len()).entered();
+    for mut transform in billboards.iter_mut() {
+        transform.rotation = rotation;
+    }
+}
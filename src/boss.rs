@@ -0,0 +1,418 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Health, Hitbox, Invulnerable};
+use crate::damage::mitigate;
+use crate::ground::GroundProbe;
+use crate::hud_layout::SafeAreaAnchor;
+use crate::locale::Locale;
+use crate::schedule::GameSet;
+use crate::settings::{AccessibilitySettings, GameplaySettings};
+use crate::skills::{SkillDefinition, SkillSpawner};
+use crate::stats::Stats;
+use crate::threat::ThreatTable;
+use crate::time_scale::TimeScale;
+use crate::toast::ToastEvent;
+use crate::Player;
+
+// Decals start this transparent and darken as the impact nears
+const TELEGRAPH_ALPHA: f32 = 0.25;
+// Keeps decals from flickering against the ground they lie on
+const DECAL_LIFT: f32 = 0.02;
+
+// Where an attack's impacts land
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub enum BossPattern {
+    // A single impact under the boss's target, the player unless something
+    // else drew its threat
+    AtPlayer,
+    // Impacts evenly spaced on a circle around the boss
+    Ring { count: u32, radius: f32 },
+    // Impacts scattered around the target
+    Scatter { count: u32, spread: f32 },
+}
+
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct BossAttack {
+    // Skill played at each impact, purely visual; its hitbox is removed
+    pub skill: String,
+    #[serde(skip)]
+    pub handle: Handle<SkillDefinition>,
+    pub pattern: BossPattern,
+    // Seconds the decal is shown before the impact
+    #[serde(default = "default_telegraph")]
+    pub telegraph: f32,
+    pub radius: f32,
+    pub damage: f32,
+}
+
+fn default_telegraph() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct BossPhase {
+    // Health fraction at or below which this phase starts
+    pub below: f32,
+    // Seconds between attacks, which are used in order
+    pub interval: f32,
+    pub attacks: Vec<BossAttack>,
+}
+
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct BossDefinition {
+    pub name: String,
+    // Ordered from full health down
+    pub phases: Vec<BossPhase>,
+    // Only attacks while the player is this close, for bosses without a
+    // threat table
+    #[serde(default = "default_aggro_range")]
+    pub aggro_range: f32,
+}
+
+fn default_aggro_range() -> f32 {
+    18.0
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Boss {
+    pub definition: BossDefinition,
+    phase: usize,
+    next_attack: usize,
+    timer: Timer,
+}
+
+impl Boss {
+    pub fn new(definition: BossDefinition) -> Self {
+        let interval = definition
+            .phases
+            .first()
+            .map_or(1.0, |phase| phase.interval);
+        Self {
+            definition,
+            phase: 0,
+            next_attack: 0,
+            timer: Timer::from_seconds(interval, TimerMode::Repeating),
+        }
+    }
+
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+}
+
+// Ground decal marking where an attack is about to land
+#[derive(Component)]
+struct Telegraph {
+    timer: Timer,
+    boss: Entity,
+    attack: BossAttack,
+}
+
+#[derive(Resource)]
+struct TelegraphMesh(Handle<Mesh>);
+
+#[derive(Component)]
+struct BossBar;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(Component)]
+struct BossBarLabel;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Boss>()
+            .add_systems(Startup, (create_telegraph_mesh, spawn_boss_bar))
+            .add_systems(
+                Update,
+                (
+                    (advance_boss_phases, start_boss_attacks, resolve_telegraphs)
+                        .chain()
+                        .in_set(GameSet::Simulation),
+                    update_boss_bar.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn create_telegraph_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(TelegraphMesh(meshes.add(Circle::new(1.0))));
+}
+
+fn advance_boss_phases(
+    mut bosses: Query<(&mut Boss, &Health)>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for (mut boss, health) in bosses.iter_mut() {
+        let fraction = health.current / health.max.max(1.0);
+        let phase = boss
+            .definition
+            .phases
+            .iter()
+            .rposition(|phase| fraction <= phase.below)
+            .unwrap_or(0);
+        if phase <= boss.phase {
+            continue;
+        }
+        boss.phase = phase;
+        boss.next_attack = 0;
+        let interval = boss.definition.phases[phase].interval;
+        boss.timer = Timer::from_seconds(interval, TimerMode::Repeating);
+        toasts.send(ToastEvent::new(locale.format(
+            "boss.enters_phase",
+            &[("boss", &boss.definition.name), ("phase", &(phase + 1))],
+        )));
+    }
+}
+
+fn start_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mesh: Res<TelegraphMesh>,
+    accessibility: Res<AccessibilitySettings>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    ground: GroundProbe,
+    mut bosses: Query<(
+        Entity,
+        &mut Boss,
+        &GlobalTransform,
+        Option<&ThreatTable>,
+        Option<&TimeScale>,
+    )>,
+    players: Query<Entity, With<Player>>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (entity, mut boss, transform, threat, time_scale) in bosses.iter_mut() {
+        let origin = transform.translation();
+        // Bosses with a threat table go after their top target, the rest after
+        // the nearest player in range
+        let target = match threat {
+            Some(threat) => threat
+                .target()
+                .and_then(|target| targets.get(target).ok())
+                .map(GlobalTransform::translation),
+            None => players
+                .iter()
+                .filter_map(|player| targets.get(player).ok())
+                .map(GlobalTransform::translation)
+                .filter(|target| origin.distance(*target) <= boss.definition.aggro_range)
+                .min_by(|a, b| origin.distance(*a).total_cmp(&origin.distance(*b))),
+        };
+        let Some(target) = target else {
+            continue;
+        };
+        let delta = time.delta().mul_f32(TimeScale::of(time_scale));
+        if !boss.timer.tick(delta).just_finished() {
+            continue;
+        }
+        let Some(phase) = boss.definition.phases.get(boss.phase) else {
+            continue;
+        };
+        if phase.attacks.is_empty() {
+            continue;
+        }
+        let attack = phase.attacks[boss.next_attack % phase.attacks.len()].clone();
+        boss.next_attack += 1;
+
+        let points: Vec<Vec3> = match attack.pattern {
+            BossPattern::AtPlayer => vec![target],
+            BossPattern::Ring { count, radius } => (0..count)
+                .map(|i| {
+                    let angle = std::f32::consts::TAU * i as f32 / count as f32;
+                    origin + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius
+                })
+                .collect(),
+            BossPattern::Scatter { count, spread } => (0..count)
+                .map(|_| {
+                    let offset = Vec2::new(rand::random(), rand::random()) * 2.0 - 1.0;
+                    target + Vec3::new(offset.x, 0.0, offset.y) * spread
+                })
+                .collect(),
+        };
+        for point in points {
+            let height = ground
+                .height_below(point + Vec3::Y * 2.0)
+                .unwrap_or(point.y - 0.5);
+            commands.spawn((
+                PbrBundle {
+                    mesh: mesh.0.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: accessibility.palette.danger().with_alpha(TELEGRAPH_ALPHA),
+                        alpha_mode: AlphaMode::Blend,
+                        unlit: true,
+                        ..default()
+                    }),
+                    transform: Transform::from_xyz(point.x, height + DECAL_LIFT, point.z)
+                        .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+                        .with_scale(Vec3::splat(attack.radius)),
+                    ..default()
+                },
+                NotShadowCaster,
+                Telegraph {
+                    timer: Timer::from_seconds(attack.telegraph, TimerMode::Once),
+                    boss: entity,
+                    attack: attack.clone(),
+                },
+            ));
+        }
+    }
+}
+
+// Decals darken as the impact nears, then the skill plays and anything still
+// inside takes the hit
+fn resolve_telegraphs(
+    time: Res<Time>,
+    definitions: Res<Assets<SkillDefinition>>,
+    accessibility: Res<AccessibilitySettings>,
+    gameplay: Res<GameplaySettings>,
+    mut telegraphs: Query<(
+        Entity,
+        &mut Telegraph,
+        &GlobalTransform,
+        &Handle<StandardMaterial>,
+    )>,
+    mut players: Query<
+        (&GlobalTransform, &mut Health, Option<&Stats>),
+        (With<Player>, Without<Invulnerable>),
+    >,
+    mut spawner: SkillSpawner,
+) {
+    for (entity, mut telegraph, transform, material) in telegraphs.iter_mut() {
+        telegraph.timer.tick(time.delta());
+        if let Some(material) = spawner.materials.get_mut(material) {
+            let alpha = TELEGRAPH_ALPHA + 0.5 * telegraph.timer.fraction();
+            material.base_color = accessibility.palette.danger().with_alpha(alpha);
+        }
+        if !telegraph.timer.finished() {
+            continue;
+        }
+        spawner.commands.entity(entity).despawn();
+
+        let impact = transform.translation();
+        if let Some(definition) = definitions.get(&telegraph.attack.handle) {
+            match spawner.spawn(
+                &telegraph.attack.handle,
+                definition,
+                telegraph.boss,
+                impact,
+                None,
+            ) {
+                // Skill hitboxes only hurt enemies, which includes the boss itself
+                Ok(skill) => {
+                    spawner.commands.entity(skill).remove::<Hitbox>();
+                }
+                Err(error) => spawner.report(&error),
+            }
+        }
+        for (player, mut health, stats) in players.iter_mut() {
+            let offset = player.translation() - impact;
+            if offset.with_y(0.0).length() > telegraph.attack.radius {
+                continue;
+            }
+            let resistance = stats.map_or(0.0, Stats::resistance);
+            let damage = telegraph.attack.damage * gameplay.multipliers().enemy_damage;
+            let amount = mitigate(damage, resistance);
+            health.current = (health.current - amount).max(0.0);
+            println!("Hit by a boss attack for {:.1}", amount);
+        }
+    }
+}
+
+fn spawn_boss_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(16.0),
+                    left: Val::Percent(20.0),
+                    width: Val::Percent(60.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    display: Display::None,
+                    ..default()
+                },
+                ..default()
+            },
+            BossBar,
+            SafeAreaAnchor::default(),
+        ))
+        .with_children(|bar| {
+            bar.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                ),
+                BossBarLabel,
+            ));
+            bar.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(14.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.0, 0.0, 0.8).into(),
+                ..default()
+            })
+            .with_children(|track| {
+                track.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.8, 0.1, 0.1).into(),
+                        ..default()
+                    },
+                    BossBarFill,
+                ));
+            });
+        });
+}
+
+// Follows the first boss alive, hidden when there is none
+fn update_boss_bar(
+    bosses: Query<(&Boss, &Health)>,
+    mut bars: Query<&mut Style, (With<BossBar>, Without<BossBarFill>)>,
+    mut fills: Query<&mut Style, With<BossBarFill>>,
+    mut labels: Query<&mut Text, With<BossBarLabel>>,
+    locale: Res<Locale>,
+) {
+    let boss = bosses.iter().next();
+    for mut style in bars.iter_mut() {
+        style.display = if boss.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    let Some((boss, health)) = boss else {
+        return;
+    };
+    let fraction = (health.current / health.max.max(1.0)).clamp(0.0, 1.0);
+    for mut style in fills.iter_mut() {
+        style.width = Val::Percent(fraction * 100.0);
+    }
+    for mut text in labels.iter_mut() {
+        text.sections[0].value = locale.format(
+            "boss.phase",
+            &[
+                ("boss", &boss.definition.name),
+                ("phase", &(boss.phase + 1)),
+                ("phases", &boss.definition.phases.len()),
+            ],
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+
+use crate::cursor::CursorGrab;
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+use crate::{players_center, MainCamera, Player};
+
+pub const CAMERA_MODE_KEY: KeyCode = KeyCode::KeyO;
+pub const FIRST_PERSON_KEY: KeyCode = KeyCode::KeyV;
+const TOP_DOWN_HEIGHT: f32 = 20.0;
+const TOP_DOWN_VIEW_HEIGHT: f32 = 14.0;
+const HEAD_HEIGHT: f32 = 0.45;
+const MOUSE_SENSITIVITY: f32 = 0.003;
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraMode {
+    #[default]
+    Perspective,
+    // Orthographic, looking straight down with north up
+    TopDown,
+    // Parented to the player's head with mouse-look
+    FirstPerson,
+}
+
+impl CameraMode {
+    pub fn uses_mouse_look(self) -> bool {
+        self == CameraMode::FirstPerson
+    }
+}
+
+// Perspective transform to return to when leaving the other modes
+#[derive(Resource, Default)]
+struct SavedPerspective(Option<(Transform, Projection)>);
+
+#[derive(Component, Default)]
+struct FirstPersonLook {
+    yaw: f32,
+    pitch: f32,
+}
+
+pub struct CameraModePlugin;
+
+impl Plugin for CameraModePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MainCamera>()
+            .init_resource::<CameraMode>()
+            .init_resource::<SavedPerspective>()
+            .add_systems(
+                Update,
+                (toggle_camera_mode, first_person_look)
+                    .chain()
+                    .run_if(not(in_state(GameState::PhotoMode)))
+                    .in_set(GameSet::Input),
+            );
+    }
+}
+
+fn toggle_camera_mode(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<CameraMode>,
+    mut saved: ResMut<SavedPerspective>,
+    mut players: Query<(Entity, &Transform, &mut Visibility), (With<Player>, Without<MainCamera>)>,
+    mut cameras: Query<(Entity, &mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let requested = if keyboard_input.just_pressed(CAMERA_MODE_KEY) {
+        CameraMode::TopDown
+    } else if keyboard_input.just_pressed(FIRST_PERSON_KEY) {
+        CameraMode::FirstPerson
+    } else {
+        return;
+    };
+    // Pressing a mode's key again goes back to the perspective camera
+    let next = if *mode == requested {
+        CameraMode::Perspective
+    } else {
+        requested
+    };
+    let Ok((camera, mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    // Leave the current mode first so every mode starts from the perspective camera
+    if *mode == CameraMode::FirstPerson {
+        commands
+            .entity(camera)
+            .remove_parent()
+            .remove::<FirstPersonLook>();
+    }
+    for (_, _, mut visibility) in players.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+    if *mode != CameraMode::Perspective {
+        let (saved_transform, saved_projection) = saved
+            .0
+            .take()
+            .unwrap_or((*transform, Projection::Perspective(default())));
+        *transform = saved_transform;
+        *projection = saved_projection;
+    }
+
+    match next {
+        CameraMode::Perspective => {}
+        CameraMode::TopDown => {
+            saved.0 = Some((*transform, projection.clone()));
+            // Centered between the players so all of them stay in view
+            let focus = players_center(players.iter().map(|(_, player, _)| player.translation))
+                .unwrap_or(Vec3::ZERO);
+            *transform = Transform::from_translation(focus + Vec3::Y * TOP_DOWN_HEIGHT)
+                .looking_at(focus, Vec3::NEG_Z);
+            *projection = Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical(TOP_DOWN_VIEW_HEIGHT),
+                ..default()
+            });
+        }
+        CameraMode::FirstPerson => {
+            // Only one view to look through, so the first player spawned gets it
+            let Some((player, player_transform, mut visibility)) =
+                players.iter_mut().min_by_key(|(player, _, _)| *player)
+            else {
+                return;
+            };
+            saved.0 = Some((*transform, projection.clone()));
+            // Keep facing the same way the gameplay camera was. The camera's
+            // rotation is now relative to the player, who may be turned.
+            let (world_yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
+            let (player_yaw, _, _) = player_transform.rotation.to_euler(EulerRot::YXZ);
+            let yaw = world_yaw - player_yaw;
+            *transform = Transform::from_xyz(0.0, HEAD_HEIGHT, 0.0)
+                .with_rotation(Quat::from_rotation_y(yaw));
+            *visibility = Visibility::Hidden;
+            commands
+                .entity(camera)
+                .set_parent(player)
+                .insert(FirstPersonLook { yaw, pitch: 0.0 });
+        }
+    }
+    *mode = next;
+    println!("Camera mode: {:?}", *mode);
+}
+
+fn first_person_look(
+    mut motion: EventReader<MouseMotion>,
+    grab: Option<Res<CursorGrab>>,
+    mut cameras: Query<(&mut Transform, &mut FirstPersonLook), With<MainCamera>>,
+) {
+    let delta: Vec2 = motion.read().map(|event| event.delta).sum();
+    // Without a grabbed cursor the mouse is free for the UI
+    if grab.is_some_and(|grab| !grab.grabbed) {
+        return;
+    }
+    let Ok((mut transform, mut look)) = cameras.get_single_mut() else {
+        return;
+    };
+    look.yaw -= delta.x * MOUSE_SENSITIVITY;
+    look.pitch = (look.pitch - delta.y * MOUSE_SENSITIVITY).clamp(-1.5, 1.5);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, look.yaw, look.pitch, 0.0);
+}
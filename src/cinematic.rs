@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::camera::CameraMode;
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+use crate::MainCamera;
+
+// Scripted camera move played when a skill is cast, written in the skill
+// definition as `camera: Some((keyframes: [...]))`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CameraSequence {
+    pub keyframes: Vec<CameraKeyframe>,
+    // Seconds spent blending back to the gameplay camera after the last keyframe
+    #[serde(default = "default_return_duration")]
+    pub return_duration: f32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct CameraKeyframe {
+    // Seconds from the start of the sequence, in real time
+    pub time: f32,
+    // Camera position relative to the caster
+    pub offset: (f32, f32, f32),
+    // Point the camera looks at, relative to the caster
+    #[serde(default)]
+    pub look_at: (f32, f32, f32),
+    // Game speed while at this keyframe, below 1 for slow-mo
+    #[serde(default = "default_time_scale")]
+    pub time_scale: f32,
+}
+
+fn default_return_duration() -> f32 {
+    0.5
+}
+
+fn default_time_scale() -> f32 {
+    1.0
+}
+
+#[derive(Event)]
+pub struct PlayCameraSequence {
+    pub sequence: CameraSequence,
+    pub focus: Entity,
+}
+
+#[derive(Clone, Copy)]
+struct Pose {
+    time: f32,
+    transform: Transform,
+    time_scale: f32,
+}
+
+struct ActiveSequence {
+    sequence: CameraSequence,
+    focus: Entity,
+    focus_position: Vec3,
+    gameplay: Transform,
+    // Game speed before the sequence, e.g. set from the console, restored after
+    time_scale: f32,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+struct CinematicState(Option<ActiveSequence>);
+
+impl CinematicState {
+    fn is_playing(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+pub struct CinematicPlugin;
+
+impl Plugin for CinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayCameraSequence>()
+            .init_resource::<CinematicState>()
+            .add_systems(
+                Update,
+                (start_camera_sequences, play_camera_sequence)
+                    .chain()
+                    .run_if(not(in_state(GameState::PhotoMode)))
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn start_camera_sequences(
+    mut requests: EventReader<PlayCameraSequence>,
+    mut state: ResMut<CinematicState>,
+    mode: Option<Res<CameraMode>>,
+    virtual_time: Res<Time<Virtual>>,
+    cameras: Query<&Transform, With<MainCamera>>,
+    focuses: Query<&GlobalTransform>,
+) {
+    for request in requests.read() {
+        // Keyframes are authored for the perspective camera
+        let perspective = mode
+            .as_deref()
+            .is_none_or(|mode| *mode == CameraMode::Perspective);
+        if state.is_playing() || !perspective || request.sequence.keyframes.is_empty() {
+            continue;
+        }
+        let (Ok(camera), Ok(focus)) = (cameras.get_single(), focuses.get(request.focus)) else {
+            continue;
+        };
+        state.0 = Some(ActiveSequence {
+            sequence: request.sequence.clone(),
+            focus: request.focus,
+            focus_position: focus.translation(),
+            gameplay: *camera,
+            time_scale: virtual_time.relative_speed(),
+            elapsed: 0.0,
+        });
+    }
+}
+
+fn play_camera_sequence(
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut state: ResMut<CinematicState>,
+    focuses: Query<&GlobalTransform, Without<MainCamera>>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Some(active) = state.0.as_mut() else {
+        return;
+    };
+    let Ok(mut camera) = cameras.get_single_mut() else {
+        virtual_time.set_relative_speed(active.time_scale);
+        state.0 = None;
+        return;
+    };
+    active.elapsed += real_time.delta_seconds();
+    if let Ok(focus) = focuses.get(active.focus) {
+        active.focus_position = focus.translation();
+    }
+
+    // The gameplay camera acts as an implicit first and last keyframe
+    let focus = active.focus_position;
+    let gameplay = Pose {
+        time: 0.0,
+        transform: active.gameplay,
+        time_scale: active.time_scale,
+    };
+    let mut poses = vec![gameplay];
+    poses.extend(active.sequence.keyframes.iter().map(|keyframe| {
+        let offset = Vec3::new(keyframe.offset.0, keyframe.offset.1, keyframe.offset.2);
+        let look_at = Vec3::new(keyframe.look_at.0, keyframe.look_at.1, keyframe.look_at.2);
+        Pose {
+            time: keyframe.time,
+            transform: Transform::from_translation(focus + offset)
+                .looking_at(focus + look_at, Vec3::Y),
+            time_scale: keyframe.time_scale,
+        }
+    }));
+    let end = poses.last().map_or(0.0, |pose| pose.time) + active.sequence.return_duration;
+    poses.push(Pose {
+        time: end,
+        ..gameplay
+    });
+
+    if active.elapsed >= end {
+        *camera = active.gameplay;
+        virtual_time.set_relative_speed(active.time_scale);
+        state.0 = None;
+        return;
+    }
+
+    let next = poses
+        .iter()
+        .position(|pose| pose.time > active.elapsed)
+        .unwrap_or(poses.len() - 1)
+        .max(1);
+    let (from, to) = (poses[next - 1], poses[next]);
+    let span = (to.time - from.time).max(f32::EPSILON);
+    let t = ((active.elapsed - from.time) / span).clamp(0.0, 1.0);
+    // Smoothstep so each segment eases in and out
+    let t = t * t * (3.0 - 2.0 * t);
+    camera.translation = from.transform.translation.lerp(to.transform.translation, t);
+    camera.rotation = from.transform.rotation.slerp(to.transform.rotation, t);
+    virtual_time.set_relative_speed(from.time_scale + (to.time_scale - from.time_scale) * t);
+}
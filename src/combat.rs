@@ -0,0 +1,288 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::console::RegisterConsoleCommand;
+use crate::damage::{compute_damage, DamageDealt};
+use crate::destructible::Destructible;
+use crate::hit_volume::HitShape;
+use crate::schedule::GameSet;
+use crate::skills::SkillOwner;
+use crate::stats::Stats;
+use crate::transition::{start_dying, DeathAnimation};
+use crate::{Enemy, Player};
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+// Spent by skills that cost something to keep up, like channels
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Mana {
+    pub current: f32,
+    pub max: f32,
+    pub regen_per_second: f32,
+}
+
+impl Mana {
+    pub fn new(max: f32, regen_per_second: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_per_second,
+        }
+    }
+
+    // Spends `amount` if there is that much left
+    pub fn try_spend(&mut self, amount: f32) -> bool {
+        if self.current < amount {
+            return false;
+        }
+        self.current -= amount;
+        true
+    }
+}
+
+// Takes no damage until the timer runs out, e.g. just after a teleport
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable(pub Timer);
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+// Damage-dealing volume carried by skills
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Hitbox {
+    pub shape: HitShape,
+    pub damage: f32,
+}
+
+// When a skill last hit each target, so lingering skills deal damage every
+// `interval` seconds instead of every frame they overlap
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ImmunityFrames {
+    pub interval: f32,
+    pub last_hit: HashMap<Entity, f32>,
+}
+
+impl ImmunityFrames {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            interval,
+            last_hit: HashMap::new(),
+        }
+    }
+
+    // Records the hit if `target` isn't still immune to this skill
+    pub fn try_hit(&mut self, target: Entity, now: f32) -> bool {
+        match self.last_hit.get(&target) {
+            Some(&last) if now - last < self.interval => false,
+            _ => {
+                self.last_hit.insert(target, now);
+                true
+            }
+        }
+    }
+}
+
+// Skills with this marker report their own hits instead of using hitbox overlap
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ManualHitDetection;
+
+// Volume that skills can hit
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Hurtbox {
+    pub radius: f32,
+}
+
+// Sent when a skill's hitbox overlaps an enemy's hurtbox, at most once per
+// `ImmunityFrames::interval` for skills that have one
+#[derive(Event)]
+pub struct SkillHitEvent {
+    pub skill: Entity,
+    pub target: Entity,
+}
+
+#[derive(Event)]
+pub struct EnemyKilled {
+    pub enemy: Entity,
+    pub position: Vec3,
+}
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Player>()
+            .register_type::<Enemy>()
+            .register_type::<Health>()
+            .register_type::<Mana>()
+            .register_type::<Hitbox>()
+            .register_type::<ImmunityFrames>()
+            .register_type::<Invulnerable>()
+            .register_type::<ManualHitDetection>()
+            .register_type::<Hurtbox>()
+            .add_event::<SkillHitEvent>()
+            .add_event::<EnemyKilled>()
+            .add_event::<DamageDealt>()
+            .register_console_command("kill", "kill", kill_command)
+            .add_systems(
+                Update,
+                (
+                    (detect_skill_hits, apply_skill_damage, despawn_dead_enemies).chain(),
+                    regenerate_mana,
+                    expire_invulnerability,
+                )
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// Zeroes every enemy's health so they die through the usual path and still
+// award experience
+fn kill_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let mut enemies = world.query_filtered::<&mut Health, With<Enemy>>();
+    let mut count = 0;
+    for mut health in enemies.iter_mut(world) {
+        health.current = 0.0;
+        count += 1;
+    }
+    Ok(format!("Killed {} enemies", count))
+}
+
+fn expire_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+fn regenerate_mana(time: Res<Time>, mut query: Query<&mut Mana>) {
+    for mut mana in query.iter_mut() {
+        mana.current = (mana.current + mana.regen_per_second * time.delta_seconds()).min(mana.max);
+    }
+}
+
+fn detect_skill_hits(
+    time: Res<Time>,
+    mut skills: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Hitbox,
+            Option<&mut ImmunityFrames>,
+        ),
+        Without<ManualHitDetection>,
+    >,
+    targets: Query<(Entity, &GlobalTransform, &Hurtbox), Or<(With<Enemy>, With<Destructible>)>>,
+    mut hits: EventWriter<SkillHitEvent>,
+) {
+    // Every skill against every enemy and prop, the first thing to grow with effect counts
+    let _span = info_span!(
+        "detect_skill_hits",
+        skills = skills.iter().len(),
+        targets = targets.iter().len()
+    )
+    .entered();
+    let now = time.elapsed_seconds();
+    for (skill, skill_transform, hitbox, mut immunity) in skills.iter_mut() {
+        for (target, target_transform, hurtbox) in targets.iter() {
+            if !hitbox.shape.overlaps_sphere(
+                skill_transform,
+                target_transform.translation(),
+                hurtbox.radius,
+            ) {
+                continue;
+            }
+            if immunity
+                .as_mut()
+                .is_none_or(|immunity| immunity.try_hit(target, now))
+            {
+                hits.send(SkillHitEvent { skill, target });
+            }
+        }
+    }
+}
+
+fn apply_skill_damage(
+    mut hits: EventReader<SkillHitEvent>,
+    hitboxes: Query<(&Hitbox, Option<&SkillOwner>)>,
+    attackers: Query<&Stats>,
+    mut targets: Query<(&mut Health, Option<&Stats>), Without<Invulnerable>>,
+    mut damage_dealt: EventWriter<DamageDealt>,
+) {
+    for hit in hits.read() {
+        let (Ok((hitbox, owner)), Ok((mut health, target_stats))) =
+            (hitboxes.get(hit.skill), targets.get_mut(hit.target))
+        else {
+            continue;
+        };
+        let attacker = owner.map(|owner| owner.0);
+        let offense = attacker
+            .and_then(|attacker| attackers.get(attacker).ok())
+            .map(Stats::offense)
+            .unwrap_or_default();
+        let resistance = target_stats.map_or(0.0, Stats::resistance);
+        let roll = compute_damage(hitbox.damage, &offense, resistance, rand::random());
+        if roll.amount <= 0.0 {
+            continue;
+        }
+
+        health.current = (health.current - roll.amount).max(0.0);
+        if roll.is_crit {
+            println!("Critical hit for {:.1}!", roll.amount);
+        }
+        damage_dealt.send(DamageDealt {
+            source: hit.skill,
+            attacker,
+            target: hit.target,
+            amount: roll.amount,
+            is_crit: roll.is_crit,
+        });
+    }
+}
+
+pub fn despawn_dead_enemies(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &Health, &Transform, Option<&DeathAnimation>),
+        (With<Enemy>, Changed<Health>),
+    >,
+    mut kills: EventWriter<EnemyKilled>,
+) {
+    for (entity, health, transform, animation) in query.iter() {
+        if health.current <= 0.0 {
+            match animation {
+                Some(animation) => start_dying(&mut commands.entity(entity), *animation),
+                None => commands.entity(entity).despawn_recursive(),
+            }
+            kills.send(EnemyKilled {
+                enemy: entity,
+                position: transform.translation,
+            });
+            println!("Enemy defeated");
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::{ButtonState, InputSystem};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::schedule::GameSet;
+
+pub const CONSOLE_KEY: KeyCode = KeyCode::Backquote;
+// Lines of output kept on screen
+const HISTORY: usize = 12;
+
+// A console command gets the words typed after its name and returns the line
+// to print, or an error explaining what went wrong. Closures taking the world
+// and the arguments are commands too.
+pub trait ConsoleCommand: Send + Sync + 'static {
+    fn run(&self, world: &mut World, args: &[&str]) -> Result<String, String>;
+}
+
+impl<F> ConsoleCommand for F
+where
+    F: Fn(&mut World, &[&str]) -> Result<String, String> + Send + Sync + 'static,
+{
+    fn run(&self, world: &mut World, args: &[&str]) -> Result<String, String> {
+        self(world, args)
+    }
+}
+
+struct RegisteredCommand {
+    usage: String,
+    command: Box<dyn ConsoleCommand>,
+}
+
+#[derive(Resource, Default)]
+pub struct ConsoleCommands {
+    // Shared so a command can run while still able to read the registry
+    commands: HashMap<String, Arc<RegisteredCommand>>,
+}
+
+impl ConsoleCommands {
+    // `usage` is shown by `help`, e.g. "spawn <prefab> [count]"
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        usage: impl Into<String>,
+        command: impl ConsoleCommand,
+    ) {
+        self.commands.insert(
+            name.into(),
+            Arc::new(RegisteredCommand {
+                usage: usage.into(),
+                command: Box::new(command),
+            }),
+        );
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
+    pub fn usage(&self, name: &str) -> Option<&str> {
+        self.commands
+            .get(name)
+            .map(|command| command.usage.as_str())
+    }
+}
+
+pub trait RegisterConsoleCommand {
+    fn register_console_command(
+        &mut self,
+        name: impl Into<String>,
+        usage: impl Into<String>,
+        command: impl ConsoleCommand,
+    ) -> &mut Self;
+}
+
+impl RegisterConsoleCommand for App {
+    fn register_console_command(
+        &mut self,
+        name: impl Into<String>,
+        usage: impl Into<String>,
+        command: impl ConsoleCommand,
+    ) -> &mut Self {
+        self.init_resource::<ConsoleCommands>();
+        self.world_mut()
+            .resource_mut::<ConsoleCommands>()
+            .register(name, usage, command);
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    // Lines submitted this frame, run by `run_console_commands`
+    submitted: Vec<String>,
+    output: Vec<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        println!("{}", line);
+        self.output.push(line);
+        let overflow = self.output.len().saturating_sub(HISTORY);
+        self.output.drain(..overflow);
+    }
+}
+
+pub fn console_open(console: Res<Console>) -> bool {
+    console.open
+}
+
+#[derive(Component)]
+struct ConsoleText;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .register_console_command("help", "help", help)
+            .add_systems(Startup, spawn_console)
+            // Typing into the console should not also move the player or cast
+            .add_systems(
+                PreUpdate,
+                swallow_keyboard_input
+                    .after(InputSystem)
+                    .run_if(console_open),
+            )
+            .add_systems(
+                Update,
+                (
+                    (read_console_input, run_console_commands)
+                        .chain()
+                        .in_set(GameSet::Input),
+                    update_console_text.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn help(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let commands = world.resource::<ConsoleCommands>();
+    let mut usages: Vec<_> = commands
+        .names()
+        .filter_map(|name| commands.usage(name))
+        .collect();
+    usages.sort();
+    Ok(usages.join(", "))
+}
+
+fn swallow_keyboard_input(mut keyboard_input: ResMut<ButtonInput<KeyCode>>) {
+    keyboard_input.reset_all();
+}
+
+// Reads raw key events rather than `ButtonInput`, which is cleared while the
+// console is open
+fn read_console_input(mut console: ResMut<Console>, mut keys: EventReader<KeyboardInput>) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        if key.key_code == CONSOLE_KEY {
+            console.open = !console.open;
+            continue;
+        }
+        if !console.open {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if !line.trim().is_empty() {
+                    console.submitted.push(line);
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Space => console.input.push(' '),
+            Key::Character(text) => console.input.push_str(text),
+            _ => {}
+        }
+    }
+}
+
+fn run_console_commands(world: &mut World) {
+    let submitted = std::mem::take(&mut world.resource_mut::<Console>().submitted);
+    for line in submitted {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((name, args)) = words.split_first() else {
+            continue;
+        };
+        let registered = world
+            .resource::<ConsoleCommands>()
+            .commands
+            .get(*name)
+            .cloned();
+        let result = match registered {
+            Some(registered) => registered.command.run(world, args),
+            None => Err(format!("unknown command {:?}, try help", name)),
+        };
+        let mut console = world.resource_mut::<Console>();
+        console.print(format!("> {}", line));
+        match result {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => console.print(output),
+            Err(error) => console.print(format!("error: {}", error)),
+        }
+    }
+}
+
+fn spawn_console(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            left: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            display: Display::None,
+            ..default()
+        })
+        .with_background_color(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+        ConsoleText,
+    ));
+}
+
+fn update_console_text(
+    console: Res<Console>,
+    mut texts: Query<(&mut Text, &mut Style), With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    for (mut text, mut style) in texts.iter_mut() {
+        style.display = if console.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let mut lines = console.output.clone();
+        lines.push(format!("> {}_", console.input));
+        text.sections[0].value = lines.join("\n");
+    }
+}
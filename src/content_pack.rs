@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+
+use crate::prefab::{load_prefabs, Prefabs};
+use crate::skills::{SkillDefinition, SkillRegistry};
+
+// Content packs live in `assets/packs/<name>/` with any of
+//   skills/*.skill.ron    extra skill definitions, registered as `<name>/<file>`
+//   prefabs/*.prefab.ron  extra enemies and props, spawned by file name
+// Sprite sheets sit anywhere in the pack and are referenced from the
+// definitions by asset path, e.g. `texture: "packs/<name>/frost.png"`.
+const PACKS_DIR: &str = "packs";
+
+pub struct ContentPack {
+    pub name: String,
+    pub skills: Vec<Handle<SkillDefinition>>,
+    pub prefabs: Vec<String>,
+}
+
+// Packs found at startup, in name order
+#[derive(Resource, Default)]
+pub struct ContentPacks(pub Vec<ContentPack>);
+
+pub struct ContentPackPlugin;
+
+impl Plugin for ContentPackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ContentPacks>()
+            // A pack prefab named like a built-in one replaces it
+            .add_systems(PreStartup, load_content_packs.after(load_prefabs));
+    }
+}
+
+// File stems in `dir` ending in `extension`, sorted so load order is stable
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file| file.strip_suffix(extension).map(str::to_string))
+        .collect();
+    names.sort();
+    names
+}
+
+fn load_content_packs(
+    mut packs: ResMut<ContentPacks>,
+    mut prefabs: ResMut<Prefabs>,
+    mut registry: ResMut<SkillRegistry>,
+    asset_server: Res<AssetServer>,
+) {
+    let root = FileAssetReader::get_base_path()
+        .join("assets")
+        .join(PACKS_DIR);
+    let Ok(entries) = std::fs::read_dir(&root) else {
+        return;
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+
+    for name in names {
+        let dir = root.join(&name);
+        let skills: Vec<Handle<SkillDefinition>> =
+            files_with_extension(&dir.join("skills"), ".skill.ron")
+                .into_iter()
+                .map(|skill| {
+                    let path = format!("{}/{}/skills/{}.skill.ron", PACKS_DIR, name, skill);
+                    registry.load(&asset_server, &path)
+                })
+                .collect();
+        let pack_prefabs = files_with_extension(&dir.join("prefabs"), ".prefab.ron");
+        for prefab in pack_prefabs.iter() {
+            let handle = asset_server.load(format!(
+                "{}/{}/prefabs/{}.prefab.ron",
+                PACKS_DIR, name, prefab
+            ));
+            if prefabs.0.insert(prefab.clone(), handle).is_some() {
+                println!("Content pack {} replaces the {} prefab", name, prefab);
+            }
+        }
+        println!(
+            "Loaded content pack {} ({} skills, {} prefabs)",
+            name,
+            skills.len(),
+            pack_prefabs.len()
+        );
+        packs.0.push(ContentPack {
+            name,
+            skills,
+            prefabs: pack_prefabs,
+        });
+    }
+}
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::camera::CameraMode;
+use crate::schedule::{simulation_running, GameSet};
+use crate::stats::Stats;
+use crate::status::StatusEffects;
+use crate::time_scale::TimeScale;
+use crate::touch::TouchControls;
+use crate::{MainCamera, Player};
+
+// Velocity-based movement toward the direction in `MovementIntent`. Max speed
+// comes from `Stats::speed`, scaled by slows.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct CharacterController {
+    pub acceleration: f32,
+    pub deceleration: f32,
+    // How quickly the character turns to face where it is going
+    pub turn_speed: f32,
+    pub velocity: Vec3,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            acceleration: 20.0,
+            deceleration: 25.0,
+            turn_speed: 12.0,
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+// World-space direction a character wants to move in, at most unit length.
+// Written by player input here, and by AI for other characters.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct MovementIntent(pub Vec3);
+
+pub struct ControllerPlugin;
+
+impl Plugin for ControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CharacterController>()
+            .register_type::<MovementIntent>()
+            .add_systems(
+                Update,
+                (
+                    read_player_input
+                        .run_if(simulation_running)
+                        .in_set(GameSet::Input),
+                    move_characters.in_set(GameSet::Simulation),
+                ),
+            );
+    }
+}
+
+// Turns a screen-aligned input (-Z forward, +X right) into a world direction
+// on the ground plane, so forward always moves away from the camera. Use it
+// for anything steered relative to the view, not only the player.
+pub fn camera_relative(input: Vec3, camera: &GlobalTransform) -> Vec3 {
+    // Looking straight down, the camera's up is what points forward on screen
+    let mut forward = camera.forward().with_y(0.0);
+    if forward.length_squared() < 1e-4 {
+        forward = camera.up().with_y(0.0);
+    }
+    let Some(forward) = forward.try_normalize() else {
+        return input;
+    };
+    let right = forward.cross(Vec3::Y);
+    (right * input.x - forward * input.z).clamp_length_max(1.0)
+}
+
+fn read_player_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    touch: Res<TouchControls>,
+    cameras: Query<&GlobalTransform, With<MainCamera>>,
+    mut players: Query<&mut MovementIntent, With<Player>>,
+) {
+    let mut input = Vec3::ZERO;
+    if keyboard_input.pressed(KeyCode::KeyI) {
+        input.z -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::KeyK) {
+        input.z += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::KeyJ) {
+        input.x -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::KeyL) {
+        input.x += 1.0;
+    }
+    // The touch stick is analog, so only overlong input is shortened
+    input += Vec3::new(touch.stick.x, 0.0, touch.stick.y);
+    let input = input.clamp_length_max(1.0);
+    let direction = match cameras.get_single() {
+        Ok(camera) => camera_relative(input, camera),
+        Err(_) => input,
+    };
+    for mut intent in players.iter_mut() {
+        intent.0 = direction;
+    }
+}
+
+fn move_characters(
+    time: Res<Time>,
+    mode: Option<Res<CameraMode>>,
+    mut characters: Query<(
+        &mut Transform,
+        &mut CharacterController,
+        &MovementIntent,
+        Option<&Stats>,
+        Option<&StatusEffects>,
+        Option<&TimeScale>,
+        Has<Player>,
+    )>,
+) {
+    let first_person = mode.is_some_and(|mode| *mode == CameraMode::FirstPerson);
+    for (mut transform, mut controller, intent, stats, status, time_scale, is_player) in
+        characters.iter_mut()
+    {
+        let dt = time.delta_seconds() * TimeScale::of(time_scale);
+        let max_speed = stats.map_or(3.0, |stats| stats.speed)
+            * status.map_or(1.0, StatusEffects::speed_multiplier);
+        let target = intent.0.clamp_length_max(1.0) * max_speed;
+        let rate = if target.length_squared() > 0.0 {
+            controller.acceleration
+        } else {
+            controller.deceleration
+        };
+        let change = target - controller.velocity;
+        controller.velocity += change.clamp_length_max(rate * dt);
+        transform.translation += controller.velocity * dt;
+
+        // The first-person camera is a child of the player, turning the player
+        // would turn the view with it
+        if is_player && first_person {
+            continue;
+        }
+        let horizontal = Vec3::new(controller.velocity.x, 0.0, controller.velocity.z);
+        if horizontal.length_squared() > 0.01 {
+            let facing = Transform::IDENTITY.looking_to(horizontal, Vec3::Y).rotation;
+            let t = 1.0 - (-controller.turn_speed * dt).exp();
+            transform.rotation = transform.rotation.slerp(facing, t);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::ground::Grounded;
+use crate::schedule::GameSet;
+use crate::settings::GraphicsSettings;
+use crate::transition::{animate_dying, Dying};
+
+// How long the remains of a fight stay before they start to fade
+const REMAINS_LIFETIME: f32 = 45.0;
+// Seconds spent sinking away or shrinking once they do
+const REMAINS_FADE: f32 = 2.0;
+// How far a corpse sinks into the ground while fading
+const CORPSE_SINK: f32 = 1.0;
+// Just above the ground so scorch marks don't flicker through it
+const SCORCH_LIFT: f32 = 0.02;
+
+// What an enemy leaves behind once its death animation has played, listed as
+// `Remains(...)` in its prefab. At most `GraphicsSettings::corpse_limit` are
+// kept, the oldest fading first.
+#[derive(Component, Debug, Clone, Copy, Deserialize, Reflect)]
+#[reflect(Component)]
+pub enum Remains {
+    // The body, lying as the animation left it
+    Corpse,
+    // A dark mark on the ground
+    Scorch { radius: f32 },
+}
+
+// Something left behind by a dead enemy, cleaned up by age and count
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Leftover {
+    pub left_at: f32,
+    // Counts down once it has started fading
+    pub fade: Option<Timer>,
+    pub kind: Remains,
+    // Where it was when it started fading
+    pub fade_from: Transform,
+}
+
+#[derive(Resource)]
+struct ScorchAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub struct CorpsePlugin;
+
+impl Plugin for CorpsePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Remains>()
+            .register_type::<Leftover>()
+            .add_systems(Startup, create_scorch_assets)
+            .add_systems(
+                Update,
+                (
+                    leave_remains.after_ignore_deferred(animate_dying),
+                    clean_up_remains,
+                )
+                    .chain()
+                    .in_set(GameSet::Animation),
+            );
+    }
+}
+
+fn create_scorch_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ScorchAssets {
+        mesh: meshes.add(Circle::new(1.0)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.05, 0.04, 0.03, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 1.0,
+            ..default()
+        }),
+    });
+}
+
+// Runs once the death animation has finished, before its despawn is applied
+// so the dying entity is still around to copy from
+fn leave_remains(
+    mut commands: Commands,
+    time: Res<Time>,
+    graphics: Res<GraphicsSettings>,
+    scorch: Res<ScorchAssets>,
+    dying: Query<(
+        &Dying,
+        &Remains,
+        &GlobalTransform,
+        Option<&Grounded>,
+        Option<&Handle<Mesh>>,
+        Option<&Handle<StandardMaterial>>,
+    )>,
+) {
+    if graphics.corpse_limit == 0 {
+        return;
+    }
+    for (dying, remains, global_transform, grounded, mesh, material) in dying.iter() {
+        if !dying.timer.finished() {
+            continue;
+        }
+        let transform = global_transform.compute_transform();
+        let left = match *remains {
+            Remains::Corpse => {
+                let (Some(mesh), Some(material)) = (mesh, material) else {
+                    continue;
+                };
+                commands.spawn(PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform,
+                    ..default()
+                })
+            }
+            Remains::Scorch { radius } => {
+                let ground =
+                    transform.translation.y - grounded.map_or(0.0, |grounded| grounded.height);
+                commands.spawn(PbrBundle {
+                    mesh: scorch.mesh.clone(),
+                    material: scorch.material.clone(),
+                    transform: Transform::from_translation(
+                        transform.translation.with_y(ground + SCORCH_LIFT),
+                    )
+                    .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+                    .with_scale(Vec3::splat(radius)),
+                    ..default()
+                })
+            }
+        }
+        .id();
+        commands.entity(left).insert(Leftover {
+            left_at: time.elapsed_seconds(),
+            fade: None,
+            kind: *remains,
+            fade_from: Transform::default(),
+        });
+    }
+}
+
+// Remains older than the lifetime, or beyond the limit counting from the
+// newest, fade out and are despawned
+fn clean_up_remains(
+    mut commands: Commands,
+    time: Res<Time>,
+    graphics: Res<GraphicsSettings>,
+    mut leftovers: Query<(Entity, &mut Leftover, &mut Transform)>,
+) {
+    let now = time.elapsed_seconds();
+    let mut lasting: Vec<_> = leftovers
+        .iter()
+        .filter(|(_, leftover, _)| leftover.fade.is_none())
+        .map(|(entity, leftover, _)| (entity, leftover.left_at))
+        .collect();
+    lasting.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let over_limit: Vec<_> = lasting
+        .iter()
+        .skip(graphics.corpse_limit)
+        .map(|(entity, _)| *entity)
+        .collect();
+
+    for (entity, mut leftover, mut transform) in leftovers.iter_mut() {
+        if leftover.fade.is_none()
+            && (now - leftover.left_at > REMAINS_LIFETIME || over_limit.contains(&entity))
+        {
+            leftover.fade_from = *transform;
+            leftover.fade = Some(Timer::from_seconds(REMAINS_FADE, TimerMode::Once));
+        }
+        let kind = leftover.kind;
+        let start = leftover.fade_from;
+        let Some(fade) = leftover.fade.as_mut() else {
+            continue;
+        };
+        fade.tick(time.delta());
+        let t = fade.fraction();
+        match kind {
+            Remains::Corpse => {
+                transform.translation = start.translation - Vec3::Y * CORPSE_SINK * t;
+            }
+            Remains::Scorch { .. } => {
+                transform.scale = start.scale * (1.0 - t).max(0.01);
+            }
+        }
+        if fade.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use bevy::window::{CursorGrabMode, PrimaryWindow, WindowFocused};
+
+use crate::camera::CameraMode;
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+
+pub const CURSOR_GRAB_KEY: KeyCode = KeyCode::KeyG;
+
+// Whether the cursor is locked and hidden for mouse-look
+#[derive(Resource, Debug, Default)]
+pub struct CursorGrab {
+    pub grabbed: bool,
+}
+
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorGrab>()
+            .add_systems(
+                Update,
+                (
+                    toggle_cursor_grab.run_if(in_state(GameState::Playing)),
+                    release_cursor,
+                    apply_cursor_grab,
+                )
+                    .chain()
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(OnEnter(GameState::Paused), release_on_pause);
+    }
+}
+
+fn toggle_cursor_grab(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mode: Res<CameraMode>,
+    mut grab: ResMut<CursorGrab>,
+) {
+    // Grab as soon as a mouse-look mode is entered, and let the key toggle it
+    if mode.is_changed() && mode.uses_mouse_look() {
+        grab.grabbed = true;
+    }
+    if keyboard_input.just_pressed(CURSOR_GRAB_KEY) && mode.uses_mouse_look() {
+        grab.grabbed = !grab.grabbed;
+    }
+}
+
+fn release_cursor(
+    mut focus_events: EventReader<WindowFocused>,
+    mode: Res<CameraMode>,
+    mut grab: ResMut<CursorGrab>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    if (lost_focus || !mode.uses_mouse_look()) && grab.grabbed {
+        grab.grabbed = false;
+    }
+}
+
+fn release_on_pause(mut grab: ResMut<CursorGrab>) {
+    grab.grabbed = false;
+}
+
+fn apply_cursor_grab(grab: Res<CursorGrab>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !grab.is_changed() {
+        return;
+    }
+    for mut window in windows.iter_mut() {
+        window.cursor.grab_mode = if grab.grabbed {
+            CursorGrabMode::Locked
+        } else {
+            CursorGrabMode::None
+        };
+        window.cursor.visible = !grab.grabbed;
+    }
+}
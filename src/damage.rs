@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+// Highest fraction of damage a target can resist
+pub const MAX_RESISTANCE: f32 = 0.9;
+
+// Attacker side of the formula, usually taken from `Stats::offense`
+#[derive(Debug, Clone, Copy)]
+pub struct Offense {
+    // Percent bonus, 50.0 = +50% damage
+    pub attack: f32,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+}
+
+impl Default for Offense {
+    fn default() -> Self {
+        Self {
+            attack: 0.0,
+            crit_chance: 0.05,
+            crit_multiplier: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageRoll {
+    pub amount: f32,
+    pub is_crit: bool,
+}
+
+// Sent for every instance of damage so UI and audio can react, e.g. to crits
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageDealt {
+    pub source: Entity,
+    pub attacker: Option<Entity>,
+    pub target: Entity,
+    pub amount: f32,
+    pub is_crit: bool,
+}
+
+pub fn mitigate(amount: f32, resistance: f32) -> f32 {
+    amount * (1.0 - resistance.clamp(0.0, MAX_RESISTANCE))
+}
+
+// `roll` is a uniform sample in [0, 1); the hit crits when it falls below the
+// attacker's crit chance
+pub fn compute_damage(base: f32, offense: &Offense, resistance: f32, roll: f32) -> DamageRoll {
+    let is_crit = roll < offense.crit_chance;
+    let mut amount = base * (1.0 + offense.attack / 100.0).max(0.0);
+    if is_crit {
+        amount *= offense.crit_multiplier;
+    }
+    DamageRoll {
+        amount: mitigate(amount, resistance).max(0.0),
+        is_crit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offense(attack: f32, crit_chance: f32) -> Offense {
+        Offense {
+            attack,
+            crit_chance,
+            crit_multiplier: 2.0,
+        }
+    }
+
+    #[test]
+    fn base_damage_passes_through_unmodified() {
+        let roll = compute_damage(10.0, &offense(0.0, 0.0), 0.0, 0.5);
+        assert_eq!(
+            roll,
+            DamageRoll {
+                amount: 10.0,
+                is_crit: false
+            }
+        );
+    }
+
+    #[test]
+    fn attack_is_a_percent_bonus() {
+        let roll = compute_damage(10.0, &offense(50.0, 0.0), 0.0, 0.5);
+        assert_eq!(roll.amount, 15.0);
+    }
+
+    #[test]
+    fn crit_applies_multiplier_when_roll_is_below_chance() {
+        let roll = compute_damage(10.0, &offense(0.0, 0.25), 0.0, 0.1);
+        assert!(roll.is_crit);
+        assert_eq!(roll.amount, 20.0);
+
+        let roll = compute_damage(10.0, &offense(0.0, 0.25), 0.0, 0.25);
+        assert!(!roll.is_crit);
+    }
+
+    #[test]
+    fn resistance_reduces_damage_and_is_capped() {
+        assert_eq!(
+            compute_damage(10.0, &offense(0.0, 0.0), 0.5, 0.5).amount,
+            5.0
+        );
+        assert!((compute_damage(10.0, &offense(0.0, 0.0), 2.0, 0.5).amount - 1.0).abs() < 1e-5);
+        assert_eq!(
+            compute_damage(10.0, &offense(0.0, 0.0), -1.0, 0.5).amount,
+            10.0
+        );
+    }
+
+    #[test]
+    fn negative_attack_never_heals() {
+        let roll = compute_damage(10.0, &offense(-300.0, 0.0), 0.0, 0.5);
+        assert_eq!(roll.amount, 0.0);
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::pbr::light_consts;
+use bevy::prelude::*;
+
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+
+pub const EARLIER_KEY: KeyCode = KeyCode::BracketLeft;
+pub const LATER_KEY: KeyCode = KeyCode::BracketRight;
+
+#[derive(Resource, Debug, Clone)]
+pub struct TimeOfDay {
+    // 0..24, 12 is noon
+    pub hour: f32,
+    // Real seconds for a full day, 0 stops the clock
+    pub day_length: f32,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self {
+            hour: 10.0,
+            day_length: 240.0,
+        }
+    }
+}
+
+impl TimeOfDay {
+    // 1 at noon, 0 from sunset to sunrise (18:00 to 06:00)
+    pub fn daylight(&self) -> f32 {
+        let sun_height = ((self.hour - 6.0) / 12.0 * std::f32::consts::PI).sin();
+        sun_height.max(0.0)
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Sun;
+
+// Scene light scaled by the time of day, remembering its authored intensity
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct DayNightLight {
+    pub base_intensity: f32,
+}
+
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Sun>()
+            .register_type::<DayNightLight>()
+            .init_resource::<TimeOfDay>()
+            .add_systems(Startup, spawn_sun)
+            .add_systems(
+                Update,
+                (
+                    advance_time_of_day
+                        .run_if(in_state(GameState::Playing))
+                        .in_set(GameSet::Simulation),
+                    apply_time_of_day.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn spawn_sun(mut commands: Commands) {
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            ..default()
+        },
+        Sun,
+    ));
+}
+
+fn advance_time_of_day(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time_of_day: ResMut<TimeOfDay>,
+) {
+    let mut hour = time_of_day.hour;
+    if time_of_day.day_length > 0.0 {
+        hour += time.delta_seconds() / time_of_day.day_length * 24.0;
+    }
+    if keyboard_input.just_pressed(EARLIER_KEY) {
+        hour -= 1.0;
+    }
+    if keyboard_input.just_pressed(LATER_KEY) {
+        hour += 1.0;
+    }
+    time_of_day.hour = hour.rem_euclid(24.0);
+}
+
+fn sky_color(daylight: f32) -> Color {
+    let night = Color::srgb(0.35, 0.45, 0.8).to_linear();
+    let dawn = Color::srgb(1.0, 0.6, 0.35).to_linear();
+    let noon = Color::srgb(1.0, 0.98, 0.92).to_linear();
+    let color = if daylight < 0.3 {
+        night.mix(&dawn, daylight / 0.3)
+    } else {
+        dawn.mix(&noon, (daylight - 0.3) / 0.7)
+    };
+    color.into()
+}
+
+// Lit skill materials (`lit: true` in the definition) pick this up through the
+// regular PBR lighting; unlit ones keep their authored colors
+fn apply_time_of_day(
+    time_of_day: Res<TimeOfDay>,
+    mut ambient: ResMut<AmbientLight>,
+    mut suns: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+    mut lights: Query<(&mut PointLight, &DayNightLight)>,
+) {
+    if !time_of_day.is_changed() {
+        return;
+    }
+    let daylight = time_of_day.daylight();
+    let color = sky_color(daylight);
+
+    // The sun travels east to west, below the horizon at night
+    let angle = (time_of_day.hour - 6.0) / 12.0 * std::f32::consts::PI;
+    for (mut sun, mut transform) in suns.iter_mut() {
+        sun.color = color;
+        sun.illuminance = light_consts::lux::OVERCAST_DAY * daylight;
+        let position = Vec3::new(angle.cos(), angle.sin(), 0.3);
+        *transform = Transform::from_translation(position).looking_at(Vec3::ZERO, Vec3::Z);
+    }
+    ambient.color = color;
+    ambient.brightness = 20.0 + 180.0 * daylight;
+
+    // Scene lamps matter most at night
+    for (mut light, day_night) in lights.iter_mut() {
+        light.color = Color::srgb(1.0, 0.85, 0.6);
+        light.intensity = day_night.base_intensity * (1.5 - daylight);
+    }
+}
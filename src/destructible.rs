@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Health, Hitbox, Hurtbox};
+use crate::loot::{drop_loot, LootDrop};
+use crate::prefab::PrefabSpawner;
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillSpawner};
+use crate::state_cleanup::LevelScoped;
+
+// How long debris flies and lies around before it is gone
+const DEBRIS_LIFETIME: f32 = 1.5;
+// Seconds at the end of its lifetime spent shrinking away
+const DEBRIS_SHRINK: f32 = 0.4;
+const DEBRIS_SIZE: f32 = 0.12;
+const DEBRIS_GRAVITY: f32 = 12.0;
+// What's left of a broken prop's height
+const BROKEN_HEIGHT: f32 = 0.25;
+
+// A prop that skills can break, listed as `Destructible(...)` in its prefab
+// next to `Health` and `Hurtbox`. Skill hits count against it the same way
+// they do against enemies.
+#[derive(Component, Debug, Clone, Deserialize, Reflect)]
+#[reflect(Component)]
+pub struct Destructible {
+    // Colors it takes as it gets damaged, each one further along, e.g.
+    // cracked then splintered. The last is kept by the broken remains.
+    #[serde(default)]
+    pub stages: Vec<(f32, f32, f32)>,
+    // Skill played where it breaks, only for show
+    #[serde(default)]
+    pub effect: Option<String>,
+    #[serde(skip)]
+    pub effect_handle: Handle<SkillDefinition>,
+    // Chunks thrown out when it breaks
+    #[serde(default = "default_debris")]
+    pub debris: u32,
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+}
+
+fn default_debris() -> u32 {
+    8
+}
+
+impl Destructible {
+    // Which of `stages` fits how damaged it is, none while undamaged
+    fn stage(&self, health: &Health) -> Option<usize> {
+        if self.stages.is_empty() || health.current >= health.max {
+            return None;
+        }
+        let damaged = 1.0 - (health.current / health.max).max(0.0);
+        let stage = (damaged * self.stages.len() as f32) as usize;
+        Some(stage.min(self.stages.len() - 1))
+    }
+}
+
+// What's left once it has broken. It can no longer be hit.
+#[derive(Component)]
+pub struct Broken;
+
+// The stage a destructible is showing, so its material only changes when the
+// stage does
+#[derive(Component)]
+struct DamageStage(usize);
+
+#[derive(Component)]
+struct Debris {
+    velocity: Vec3,
+    timer: Timer,
+    size: f32,
+}
+
+#[derive(Resource)]
+struct DebrisMesh(Handle<Mesh>);
+
+pub struct DestructiblePlugin;
+
+impl Plugin for DestructiblePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Destructible>()
+            .add_systems(Startup, create_debris_mesh)
+            .add_systems(
+                Update,
+                (show_damage_stages, break_destructibles)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            )
+            .add_systems(Update, animate_debris.in_set(GameSet::Animation));
+    }
+}
+
+fn create_debris_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(DebrisMesh(meshes.add(Cuboid::from_length(1.0))));
+}
+
+fn stage_color((r, g, b): (f32, f32, f32)) -> Color {
+    Color::srgb(r, g, b)
+}
+
+// Prefab instances share a material, so each damaged prop gets its own
+fn show_damage_stages(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<
+        (Entity, &Destructible, &Health, Option<&DamageStage>),
+        (Changed<Health>, Without<Broken>),
+    >,
+) {
+    for (entity, destructible, health, shown) in props.iter() {
+        let Some(stage) = destructible.stage(health) else {
+            continue;
+        };
+        if shown.is_some_and(|shown| shown.0 == stage) {
+            continue;
+        }
+        commands.entity(entity).insert((
+            DamageStage(stage),
+            materials.add(stage_color(destructible.stages[stage])),
+        ));
+    }
+}
+
+fn break_destructibles(
+    mut commands: Commands,
+    debris_mesh: Res<DebrisMesh>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<
+        (
+            Entity,
+            &Destructible,
+            &Health,
+            &Transform,
+            &GlobalTransform,
+            Option<&Handle<StandardMaterial>>,
+            Has<LevelScoped>,
+        ),
+        (Changed<Health>, Without<Broken>),
+    >,
+    mut skills: SkillSpawner,
+    mut prefabs: PrefabSpawner,
+) {
+    for (entity, destructible, health, transform, global, material, scoped) in props.iter() {
+        if health.current > 0.0 {
+            continue;
+        }
+        let position = global.translation();
+
+        // Flattened into a heap where it stood, in its most damaged colors.
+        // Props rest on the ground, so their origin is half their height up.
+        let mut broken = *transform;
+        broken.translation.y *= BROKEN_HEIGHT;
+        broken.scale.y *= BROKEN_HEIGHT;
+        let mut entity_commands = commands.entity(entity);
+        entity_commands
+            .remove::<(Hurtbox, DamageStage)>()
+            .insert((Broken, broken));
+        if let Some(color) = destructible.stages.last() {
+            entity_commands.insert(materials.add(stage_color(*color)));
+        }
+
+        if let Some(definition) = definitions.get(&destructible.effect_handle) {
+            match skills.spawn(
+                &destructible.effect_handle,
+                definition,
+                entity,
+                position,
+                None,
+            ) {
+                // Skill hitboxes hurt whatever they touch, this one is only the look
+                Ok(skill) => {
+                    skills.commands.entity(skill).remove::<Hitbox>();
+                }
+                Err(error) => skills.report(&error),
+            }
+        }
+
+        // Debris takes the prop's own color
+        let debris_material = material.cloned().unwrap_or_default();
+        for i in 0..destructible.debris {
+            let angle = std::f32::consts::TAU * (i as f32 + rand::random::<f32>())
+                / destructible.debris as f32;
+            let speed = 2.0 + rand::random::<f32>() * 2.0;
+            let size = DEBRIS_SIZE * (0.6 + rand::random::<f32>() * 0.8);
+            commands.spawn((
+                PbrBundle {
+                    mesh: debris_mesh.0.clone(),
+                    material: debris_material.clone(),
+                    transform: Transform::from_translation(position + Vec3::Y * 0.3)
+                        .with_scale(Vec3::splat(size)),
+                    ..default()
+                },
+                Debris {
+                    velocity: Vec3::new(angle.cos() * speed, 3.0 + speed, angle.sin() * speed),
+                    timer: Timer::from_seconds(DEBRIS_LIFETIME, TimerMode::Once),
+                    size,
+                },
+                LevelScoped,
+            ));
+        }
+
+        drop_loot(&mut prefabs, &destructible.loot, position, scoped);
+        println!("Prop broken");
+    }
+}
+
+fn animate_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris: Query<(Entity, &mut Transform, &mut Debris)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut transform, mut debris) in debris.iter_mut() {
+        debris.timer.tick(time.delta());
+        if debris.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        debris.velocity.y -= DEBRIS_GRAVITY * dt;
+        transform.translation += debris.velocity * dt;
+        // Comes to rest on the ground instead of bouncing
+        let floor = debris.size * 0.5;
+        if transform.translation.y <= floor {
+            transform.translation.y = floor;
+            debris.velocity = Vec3::ZERO;
+        } else {
+            transform.rotate_local_x(8.0 * dt);
+        }
+        let shrink = (debris.timer.remaining_secs() / DEBRIS_SHRINK).min(1.0);
+        transform.scale = Vec3::splat(debris.size * shrink);
+    }
+}
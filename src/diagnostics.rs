@@ -0,0 +1,226 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+    FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+};
+use bevy::prelude::*;
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillRegistry, WaterSkill};
+use crate::threat::ThreatTable;
+
+pub const OVERLAY_KEY: KeyCode = KeyCode::F8;
+// Starts and stops writing every diagnostic to `CSV_PATH`, one row per frame
+pub const CSV_KEY: KeyCode = KeyCode::F9;
+const CSV_PATH: &str = "diagnostics.csv";
+
+pub const ACTIVE_SKILLS: DiagnosticPath = DiagnosticPath::const_new("skills/active");
+// Skills spawned this frame; there is no pooling, so every spawn is fresh
+pub const SKILL_SPAWNS: DiagnosticPath = DiagnosticPath::const_new("skills/spawned");
+pub const MATERIALS: DiagnosticPath = DiagnosticPath::const_new("assets/materials");
+pub const MESHES: DiagnosticPath = DiagnosticPath::const_new("assets/meshes");
+pub const IMAGES: DiagnosticPath = DiagnosticPath::const_new("assets/images");
+pub const ATLAS_LAYOUTS: DiagnosticPath = DiagnosticPath::const_new("assets/atlas_layouts");
+pub const SKILL_DEFINITIONS: DiagnosticPath = DiagnosticPath::const_new("assets/skill_definitions");
+// Registry entries ready to spawn from, and ones let go of for going unused
+pub const PREPARED_SKILLS: DiagnosticPath = DiagnosticPath::const_new("skills/prepared");
+pub const RELEASED_SKILLS: DiagnosticPath = DiagnosticPath::const_new("skills/released");
+
+#[derive(Resource, Default)]
+struct DiagnosticsCsv {
+    writer: Option<BufWriter<File>>,
+    // Column order, fixed when recording starts
+    columns: Vec<DiagnosticPath>,
+}
+
+#[derive(Component)]
+struct DiagnosticsOverlay;
+
+pub struct DiagnosticsOverlayPlugin;
+
+impl Plugin for DiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+        app.register_diagnostic(Diagnostic::new(ACTIVE_SKILLS))
+            .register_diagnostic(Diagnostic::new(SKILL_SPAWNS))
+            .register_diagnostic(Diagnostic::new(MATERIALS))
+            .register_diagnostic(Diagnostic::new(MESHES))
+            .register_diagnostic(Diagnostic::new(IMAGES))
+            .register_diagnostic(Diagnostic::new(ATLAS_LAYOUTS))
+            .register_diagnostic(Diagnostic::new(SKILL_DEFINITIONS))
+            .register_diagnostic(Diagnostic::new(PREPARED_SKILLS))
+            .register_diagnostic(Diagnostic::new(RELEASED_SKILLS))
+            .init_resource::<DiagnosticsCsv>()
+            .add_systems(Startup, spawn_overlay)
+            .add_systems(
+                Update,
+                (
+                    (toggle_overlay, toggle_csv).in_set(GameSet::Input),
+                    (measure_game_counters, update_overlay).in_set(GameSet::Debug),
+                ),
+            )
+            // After the measurements taken in Update have been applied
+            .add_systems(Last, write_csv_row);
+    }
+}
+
+fn measure_game_counters(
+    mut diagnostics: Diagnostics,
+    skills: Query<(), With<WaterSkill>>,
+    spawned: Query<(), Added<WaterSkill>>,
+    materials: Res<Assets<StandardMaterial>>,
+    meshes: Res<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    definitions: Res<Assets<SkillDefinition>>,
+    registry: Res<SkillRegistry>,
+) {
+    diagnostics.add_measurement(&ACTIVE_SKILLS, || skills.iter().count() as f64);
+    diagnostics.add_measurement(&SKILL_SPAWNS, || spawned.iter().count() as f64);
+    diagnostics.add_measurement(&MATERIALS, || materials.len() as f64);
+    diagnostics.add_measurement(&MESHES, || meshes.len() as f64);
+    diagnostics.add_measurement(&IMAGES, || images.len() as f64);
+    diagnostics.add_measurement(&ATLAS_LAYOUTS, || layouts.len() as f64);
+    diagnostics.add_measurement(&SKILL_DEFINITIONS, || definitions.len() as f64);
+    diagnostics.add_measurement(&PREPARED_SKILLS, || registry.prepared_count() as f64);
+    diagnostics.add_measurement(&RELEASED_SKILLS, || registry.released_count() as f64);
+}
+
+fn spawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 14.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            display: Display::None,
+            ..default()
+        })
+        .with_background_color(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        DiagnosticsOverlay,
+        SafeAreaAnchor::default(),
+    ));
+}
+
+fn toggle_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlays: Query<&mut Style, With<DiagnosticsOverlay>>,
+) {
+    if !keyboard_input.just_pressed(OVERLAY_KEY) {
+        return;
+    }
+    for mut style in overlays.iter_mut() {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
+
+fn update_overlay(
+    store: Res<DiagnosticsStore>,
+    csv: Res<DiagnosticsCsv>,
+    threat_tables: Query<(Entity, &ThreatTable)>,
+    mut overlays: Query<(&mut Text, &Style), With<DiagnosticsOverlay>>,
+) {
+    for (mut text, style) in overlays.iter_mut() {
+        if style.display == Display::None {
+            continue;
+        }
+        let mut diagnostics: Vec<_> = store.iter().filter(|d| d.is_enabled).collect();
+        diagnostics.sort_by(|a, b| a.path().as_str().cmp(b.path().as_str()));
+        let mut lines: Vec<String> = diagnostics
+            .into_iter()
+            .filter_map(|diagnostic| {
+                let value = diagnostic.smoothed()?;
+                Some(format!(
+                    "{}: {:.1}{}",
+                    diagnostic.path(),
+                    value,
+                    diagnostic.suffix
+                ))
+            })
+            .collect();
+        for (enemy, table) in threat_tables.iter() {
+            let entries: Vec<String> = table
+                .sorted()
+                .into_iter()
+                .map(|(target, threat)| format!("{}={:.0}", target, threat))
+                .collect();
+            if !entries.is_empty() {
+                lines.push(format!("threat {}: {}", enemy, entries.join(" ")));
+            }
+        }
+        if csv.writer.is_some() {
+            lines.push(format!("recording to {}", CSV_PATH));
+        }
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn toggle_csv(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    store: Res<DiagnosticsStore>,
+    mut csv: ResMut<DiagnosticsCsv>,
+) {
+    if !keyboard_input.just_pressed(CSV_KEY) {
+        return;
+    }
+    if let Some(mut writer) = csv.writer.take() {
+        if let Err(error) = writer.flush() {
+            warn!("Could not finish {}: {}", CSV_PATH, error);
+        }
+        println!("Stopped recording diagnostics");
+        return;
+    }
+    let mut columns: Vec<DiagnosticPath> = store.iter().map(|d| d.path().clone()).collect();
+    columns.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    let header: Vec<&str> = columns.iter().map(DiagnosticPath::as_str).collect();
+    let started = File::create(CSV_PATH).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", header.join(","))?;
+        Ok(writer)
+    });
+    match started {
+        Ok(writer) => {
+            csv.writer = Some(writer);
+            csv.columns = columns;
+            println!("Recording diagnostics to {}", CSV_PATH);
+        }
+        Err(error) => warn!("Could not create {}: {}", CSV_PATH, error),
+    }
+}
+
+fn write_csv_row(store: Res<DiagnosticsStore>, mut csv: ResMut<DiagnosticsCsv>) {
+    let DiagnosticsCsv { writer, columns } = &mut *csv;
+    let Some(file) = writer.as_mut() else {
+        return;
+    };
+    let row: Vec<String> = columns
+        .iter()
+        .map(|path| {
+            store
+                .get_measurement(path)
+                .map_or(String::new(), |measurement| measurement.value.to_string())
+        })
+        .collect();
+    if let Err(error) = writeln!(file, "{}", row.join(",")) {
+        warn!("Stopped recording diagnostics: {}", error);
+        *writer = None;
+    }
+}
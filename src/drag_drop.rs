@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::schedule::GameSet;
+use crate::screen_space::to_ui;
+
+// Pixels the cursor has to move with the button held before a press becomes
+// a drag, so plain clicks stay clicks
+const DRAG_THRESHOLD: f32 = 6.0;
+const GHOST_SIZE: f32 = 48.0;
+
+// A UI node that can be picked up with the mouse. What it carries is up to
+// the other components on it; the label is shown under the cursor.
+#[derive(Component)]
+pub struct Draggable {
+    pub label: String,
+}
+
+// A UI node things can be dropped on
+#[derive(Component)]
+pub struct DropTarget;
+
+// On the drop target under the cursor while something is dragged, for
+// highlighting it
+#[derive(Component)]
+pub struct DropHovered;
+
+// Sent when a dragged node is let go, with the drop target under the cursor
+// if there was one
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DragDropped {
+    pub source: Entity,
+    pub target: Option<Entity>,
+}
+
+enum DragPhase {
+    // Pressed but not moved far enough yet
+    Pressed { source: Entity, from: Vec2 },
+    Dragging { source: Entity, ghost: Entity },
+}
+
+#[derive(Resource, Default)]
+pub struct DragState {
+    phase: Option<DragPhase>,
+}
+
+impl DragState {
+    // What's being dragged, once it has started moving
+    pub fn dragging(&self) -> Option<Entity> {
+        match self.phase {
+            Some(DragPhase::Dragging { source, .. }) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct DragGhost;
+
+pub struct DragDropPlugin;
+
+impl Plugin for DragDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DragState>()
+            .add_event::<DragDropped>()
+            .add_systems(Update, update_drag.in_set(GameSet::Input));
+    }
+}
+
+// UI coordinates of the cursor, which is what node rects are in
+fn cursor_position(window: &Window, ui_scale: &UiScale) -> Option<Vec2> {
+    Some(to_ui(window.cursor_position()?, ui_scale))
+}
+
+fn update_drag(
+    mut commands: Commands,
+    mut state: ResMut<DragState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    ui_scale: Res<UiScale>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    draggables: Query<(Entity, &Interaction, &Draggable)>,
+    targets: Query<(Entity, &Node, &GlobalTransform, &ViewVisibility), With<DropTarget>>,
+    hovered: Query<Entity, With<DropHovered>>,
+    mut ghosts: Query<&mut Style, With<DragGhost>>,
+    mut dropped: EventWriter<DragDropped>,
+) {
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(|window| cursor_position(window, &ui_scale));
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let pressed = draggables
+            .iter()
+            .find(|(_, interaction, _)| **interaction == Interaction::Pressed);
+        if let (Some((source, ..)), Some(from)) = (pressed, cursor) {
+            state.phase = Some(DragPhase::Pressed { source, from });
+        }
+    }
+
+    // A press turns into a drag once the cursor has moved far enough
+    if let (Some(DragPhase::Pressed { source, from }), Some(cursor)) = (&state.phase, cursor) {
+        let source = *source;
+        if cursor.distance(*from) >= DRAG_THRESHOLD {
+            let label = draggables
+                .get(source)
+                .map_or_else(|_| String::new(), |(.., draggable)| draggable.label.clone());
+            let ghost = spawn_ghost(&mut commands, label, cursor);
+            state.phase = Some(DragPhase::Dragging { source, ghost });
+        }
+    }
+
+    let under_cursor = cursor.and_then(|cursor| {
+        targets
+            .iter()
+            .filter(|(.., visibility)| visibility.get())
+            .find(|(_, node, transform, _)| node.logical_rect(transform).contains(cursor))
+            .map(|(target, ..)| target)
+    });
+
+    if let Some(DragPhase::Dragging { ghost, .. }) = &state.phase {
+        if let (Ok(mut style), Some(cursor)) = (ghosts.get_mut(*ghost), cursor) {
+            style.left = Val::Px(cursor.x - GHOST_SIZE / 2.0);
+            style.top = Val::Px(cursor.y - GHOST_SIZE / 2.0);
+        }
+        for target in hovered
+            .iter()
+            .filter(|target| Some(*target) != under_cursor)
+        {
+            commands.entity(target).remove::<DropHovered>();
+        }
+        if let Some(target) = under_cursor {
+            commands.entity(target).insert(DropHovered);
+        }
+    }
+
+    if !mouse.pressed(MouseButton::Left) {
+        if let Some(DragPhase::Dragging { source, ghost }) = state.phase.take() {
+            commands.entity(ghost).despawn_recursive();
+            for target in hovered.iter() {
+                commands.entity(target).remove::<DropHovered>();
+            }
+            dropped.send(DragDropped {
+                source,
+                target: under_cursor,
+            });
+        }
+        state.phase = None;
+    }
+}
+
+fn spawn_ghost(commands: &mut Commands, label: String, cursor: Vec2) -> Entity {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(cursor.x - GHOST_SIZE / 2.0),
+                    top: Val::Px(cursor.y - GHOST_SIZE / 2.0),
+                    width: Val::Px(GHOST_SIZE),
+                    height: Val::Px(GHOST_SIZE),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.3, 0.4, 0.5, 0.7).into(),
+                border_radius: BorderRadius::all(Val::Px(6.0)),
+                // Above everything, and never in the way of what's under it
+                z_index: ZIndex::Global(200),
+                focus_policy: bevy::ui::FocusPolicy::Pass,
+                ..default()
+            },
+            DragGhost,
+        ))
+        .with_children(|ghost| {
+            ghost.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 12.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        })
+        .id()
+}
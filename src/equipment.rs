@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::skills::on_hit::OnHitEffect;
+use crate::stats::{
+    recompute_stats, ModifierKind, ModifierSource, Stat, StatModifier, StatModifiers,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct Item {
+    pub name: String,
+    pub slot: EquipmentSlot,
+    pub modifiers: Vec<(Stat, ModifierKind, f32)>,
+    // Multiplied into the sprite color of every skill the wearer casts
+    pub skill_tint: Option<Color>,
+    // Added to the definition's own on-hit effects
+    pub on_hit: Vec<OnHitEffect>,
+}
+
+impl Item {
+    pub fn new(name: impl Into<String>, slot: EquipmentSlot) -> Self {
+        Self {
+            name: name.into(),
+            slot,
+            modifiers: Vec::new(),
+            skill_tint: None,
+            on_hit: Vec::new(),
+        }
+    }
+
+    pub fn with_modifier(mut self, stat: Stat, kind: ModifierKind, value: f32) -> Self {
+        self.modifiers.push((stat, kind, value));
+        self
+    }
+
+    pub fn with_skill_tint(mut self, tint: Color) -> Self {
+        self.skill_tint = Some(tint);
+        self
+    }
+
+    pub fn with_on_hit(mut self, effect: OnHitEffect) -> Self {
+        self.on_hit.push(effect);
+        self
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Equipment {
+    slots: HashMap<EquipmentSlot, Item>,
+}
+
+impl Equipment {
+    // Returns the item previously in the slot
+    pub fn equip(&mut self, item: Item) -> Option<Item> {
+        self.slots.insert(item.slot, item)
+    }
+
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<Item> {
+        self.slots.remove(&slot)
+    }
+
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&Item> {
+        self.slots.get(&slot)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.slots.values()
+    }
+
+    pub fn skill_tint(&self) -> Option<Color> {
+        self.items()
+            .filter_map(|item| item.skill_tint)
+            .map(|tint| tint.to_linear().to_vec4())
+            .reduce(|a, b| a * b)
+            .map(|tint| LinearRgba::from_vec4(tint).into())
+    }
+
+    pub fn on_hit_effects(&self) -> impl Iterator<Item = &OnHitEffect> {
+        self.items().flat_map(|item| item.on_hit.iter())
+    }
+}
+
+pub struct EquipmentPlugin;
+
+impl Plugin for EquipmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Equipment>()
+            .add_systems(PreUpdate, apply_gear_modifiers.before(recompute_stats));
+    }
+}
+
+// Rebuilds the gear part of the modifier stack whenever equipment changes
+fn apply_gear_modifiers(mut query: Query<(&Equipment, &mut StatModifiers), Changed<Equipment>>) {
+    for (equipment, mut modifiers) in query.iter_mut() {
+        modifiers.remove_source(ModifierSource::Gear);
+        for item in equipment.items() {
+            for &(stat, kind, value) in item.modifiers.iter() {
+                modifiers.add(StatModifier {
+                    stat,
+                    kind,
+                    value,
+                    source: ModifierSource::Gear,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::animation::Animator;
+use crate::render_preview::{PreviewSubject, RenderPreview};
+use crate::schedule::GameSet;
+use crate::screen_space::{to_ui, ScreenProjection};
+use crate::skills::{frame_uv_transform, SkillDefinition};
+use crate::MainCamera;
+
+const COLUMNS: usize = 4;
+const SPACING: f32 = 3.0;
+const MAX_SIZE: f32 = 2.4;
+const CLOSE_UP_SIZE: f32 = 240.0;
+// Where the grid starts, away from the rest of the scene
+const ORIGIN: Vec3 = Vec3::new(0.0, 1.5, -30.0);
+
+// Art review mode laying out every loaded skill definition in a looping grid.
+// Clicking an entry shows its parameters.
+#[derive(Resource, Default)]
+pub struct Gallery {
+    entries: Vec<AssetId<SkillDefinition>>,
+    selected: Option<AssetId<SkillDefinition>>,
+}
+
+#[derive(Component)]
+struct GalleryEntry {
+    definition: Handle<SkillDefinition>,
+    size: f32,
+}
+
+#[derive(Component)]
+struct GalleryLabel(Entity);
+
+#[derive(Component)]
+struct GalleryInspector;
+
+// Close-up of the selected entry
+#[derive(Component)]
+struct GalleryCloseUp;
+
+pub struct GalleryPlugin;
+
+impl Plugin for GalleryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_inspector.run_if(resource_exists::<Gallery>))
+            // After the camera has been spawned
+            .add_systems(
+                PostStartup,
+                frame_gallery_camera.run_if(resource_exists::<Gallery>),
+            )
+            .add_systems(
+                Update,
+                (
+                    add_gallery_entries,
+                    sync_gallery_frames,
+                    place_gallery_labels,
+                    select_gallery_entry,
+                    update_inspector,
+                    frame_close_up,
+                )
+                    .chain()
+                    .in_set(GameSet::Presentation)
+                    .run_if(resource_exists::<Gallery>),
+            );
+    }
+}
+
+fn slot_position(index: usize) -> Vec3 {
+    let column = (index % COLUMNS) as f32 - (COLUMNS - 1) as f32 / 2.0;
+    let row = (index / COLUMNS) as f32;
+    ORIGIN + Vec3::new(column * SPACING, -row * SPACING, 0.0)
+}
+
+fn frame_gallery_camera(mut cameras: Query<&mut Transform, With<MainCamera>>) {
+    for mut transform in cameras.iter_mut() {
+        *transform = Transform::from_translation(ORIGIN + Vec3::new(0.0, -SPACING, 14.0))
+            .looking_at(ORIGIN + Vec3::new(0.0, -SPACING, 0.0), Vec3::Y);
+    }
+}
+
+fn spawn_inspector(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Click a skill to inspect it",
+            TextStyle {
+                font_size: 16.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            max_width: Val::Px(360.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        })
+        .with_background_color(Color::srgba(0.05, 0.05, 0.1, 0.8)),
+        GalleryInspector,
+    ));
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                left: Val::Px(12.0),
+                width: Val::Px(CLOSE_UP_SIZE),
+                height: Val::Px(CLOSE_UP_SIZE),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        RenderPreview::entity(Entity::PLACEHOLDER, UVec2::splat(CLOSE_UP_SIZE as u32))
+            .with_clear_color(Color::srgba(0.05, 0.05, 0.1, 0.8)),
+        GalleryCloseUp,
+    ));
+}
+
+fn add_gallery_entries(
+    mut commands: Commands,
+    mut gallery: ResMut<Gallery>,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (id, definition) in definitions.iter() {
+        if gallery.entries.contains(&id) {
+            continue;
+        }
+        let Some(handle) = asset_server.get_id_handle(id) else {
+            continue;
+        };
+        let position = slot_position(gallery.entries.len());
+        gallery.entries.push(id);
+
+        let size = definition.scale.min(MAX_SIZE);
+        let entry = commands
+            .spawn((
+                PbrBundle {
+                    mesh: meshes.add(Mesh::from(Rectangle::new(1.0, 1.0))),
+                    material: materials.add(StandardMaterial {
+                        base_color_texture: Some(definition.image.clone()),
+                        alpha_mode: definition.alpha_mode(),
+                        unlit: true,
+                        ..default()
+                    }),
+                    transform: Transform::from_translation(position).with_scale(Vec3::splat(size)),
+                    ..default()
+                },
+                TextureAtlas {
+                    layout: definition.atlas_layout.clone(),
+                    index: 0,
+                },
+                // Loops forever since nothing ends an entry's lifetime
+                definition.animator(),
+                GalleryEntry {
+                    definition: handle,
+                    size,
+                },
+            ))
+            .id();
+        commands.spawn((
+            TextBundle::from_section(
+                definition.name.clone(),
+                TextStyle {
+                    font_size: 16.0,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+            GalleryLabel(entry),
+        ));
+    }
+}
+
+fn sync_gallery_frames(
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    entries: Query<(&GalleryEntry, &TextureAtlas, &Handle<StandardMaterial>), Changed<Animator>>,
+) {
+    for (entry, atlas, material) in entries.iter() {
+        let inset = definitions
+            .get(&entry.definition)
+            .map_or(0.0, |definition| definition.frame_inset);
+        let (Some(uv_transform), Some(material)) = (
+            layouts
+                .get(&atlas.layout)
+                .and_then(|layout| frame_uv_transform(layout, atlas.index, inset)),
+            materials.get_mut(material),
+        ) else {
+            continue;
+        };
+        material.uv_transform = uv_transform;
+    }
+}
+
+fn place_gallery_labels(
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    entries: Query<(&GlobalTransform, &GalleryEntry)>,
+    mut labels: Query<(&GalleryLabel, &mut Style)>,
+    ui_scale: Res<UiScale>,
+) {
+    let Some(projection) = cameras
+        .get_single()
+        .ok()
+        .and_then(|(camera, transform)| ScreenProjection::new(camera, transform))
+    else {
+        return;
+    };
+    for (label, mut style) in labels.iter_mut() {
+        let Ok((transform, entry)) = entries.get(label.0) else {
+            continue;
+        };
+        let below = transform.translation() - Vec3::Y * (entry.size / 2.0 + 0.2);
+        if let Some(position) = projection.world_to_screen(below) {
+            let position = to_ui(position, &ui_scale);
+            style.left = Val::Px(position.x - 40.0);
+            style.top = Val::Px(position.y);
+        }
+    }
+}
+
+fn select_gallery_entry(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut gallery: ResMut<Gallery>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    entries: Query<(&GlobalTransform, &GalleryEntry)>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| ScreenProjection::new(camera, camera_transform)?.screen_to_ray(cursor))
+    else {
+        return;
+    };
+    // Entries all face +Z, so test the ray against each quad's square
+    gallery.selected = entries
+        .iter()
+        .filter_map(|(transform, entry)| {
+            let center = transform.translation();
+            let distance = ray.intersect_plane(center, InfinitePlane3d::new(Vec3::Z))?;
+            let offset = ray.get_point(distance) - center;
+            (offset.x.abs() <= entry.size / 2.0 && offset.y.abs() <= entry.size / 2.0)
+                .then_some((entry.definition.id(), distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id);
+}
+
+fn update_inspector(
+    gallery: Res<Gallery>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut inspectors: Query<&mut Text, With<GalleryInspector>>,
+) {
+    if !gallery.is_changed() {
+        return;
+    }
+    let Some(definition) = gallery.selected.and_then(|id| definitions.get(id)) else {
+        return;
+    };
+    let mut lines = vec![
+        definition.name.clone(),
+        format!("texture: {}", definition.texture),
+        format!(
+            "frames: {}x{} of {}px, {}s each",
+            definition.columns, definition.rows, definition.frame_size, definition.frame_duration
+        ),
+        format!("clips: {:?}", definition.clips),
+        format!(
+            "behavior: {}, cast: {:?}",
+            definition.behavior, definition.cast
+        ),
+        format!(
+            "damage: {}, cooldown: {}s, lifetime: {}s",
+            definition.damage, definition.cooldown, definition.lifetime
+        ),
+        format!(
+            "scale: {}, hit shape: {:?} every {}s, offset: {:?}",
+            definition.scale,
+            definition.hit_shape(),
+            definition.hit_interval,
+            definition.spawn_offset
+        ),
+    ];
+    let mut params: Vec<_> = definition.params.iter().collect();
+    params.sort_by(|a, b| a.0.cmp(b.0));
+    lines.extend(
+        params
+            .into_iter()
+            .map(|(name, value)| format!("  {}: {}", name, value)),
+    );
+    if !definition.on_hit.is_empty() {
+        lines.push(format!("on hit: {}", definition.on_hit.len()));
+    }
+    if !definition.ranks.is_empty() {
+        lines.push(format!("ranks: {}", definition.ranks.len()));
+    }
+    for mut text in inspectors.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+// Points the close-up at the selected entry, near enough for it to fill the view
+fn frame_close_up(
+    gallery: Res<Gallery>,
+    entries: Query<(Entity, &GalleryEntry)>,
+    mut close_ups: Query<(&mut RenderPreview, &mut Visibility), With<GalleryCloseUp>>,
+) {
+    if !gallery.is_changed() {
+        return;
+    }
+    let selected = entries
+        .iter()
+        .find(|(_, entry)| Some(entry.definition.id()) == gallery.selected);
+    for (mut preview, mut visibility) in close_ups.iter_mut() {
+        let Some((entity, entry)) = selected else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+        preview.subject = PreviewSubject::Entity {
+            entity,
+            distance: entry.size * 1.6,
+            height: 0.0,
+        };
+    }
+}
@@ -0,0 +1,140 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::primitives::Aabb;
+
+// How far above its current position an entity looks for the surface, so it
+// can walk up slopes and small steps but not onto walls
+const STEP_HEIGHT: f32 = 0.6;
+const PROBE_DEPTH: f32 = 50.0;
+
+// Level geometry that entities stand on. Its mesh is raycast on the CPU, so
+// keep it to terrain and floors rather than detailed props.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Ground;
+
+// Keeps the entity `height` above the ground under it
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Grounded {
+    pub height: f32,
+}
+
+pub struct GroundPlugin;
+
+impl Plugin for GroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Ground>()
+            .register_type::<Grounded>()
+            .add_systems(
+                PostUpdate,
+                snap_to_ground.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[derive(SystemParam)]
+pub struct GroundProbe<'w, 's> {
+    meshes: Res<'w, Assets<Mesh>>,
+    grounds: Query<
+        'w,
+        's,
+        (
+            &'static Handle<Mesh>,
+            &'static GlobalTransform,
+            Option<&'static Aabb>,
+        ),
+        With<Ground>,
+    >,
+}
+
+impl GroundProbe<'_, '_> {
+    // Height of the highest ground surface below `from`, if there is one
+    pub fn height_below(&self, from: Vec3) -> Option<f32> {
+        let mut best: Option<f32> = None;
+        for (mesh, transform, aabb) in self.grounds.iter() {
+            let Some(mesh) = self.meshes.get(mesh) else {
+                continue;
+            };
+            let to_local = transform.affine().inverse();
+            let origin = to_local.transform_point3(from);
+            let direction = to_local.transform_vector3(Vec3::NEG_Y * PROBE_DEPTH);
+            if let Some(aabb) = aabb {
+                let (min, max) = (aabb.min(), aabb.max());
+                let end = origin + direction;
+                let (lo, hi) = (origin.min(end), origin.max(end));
+                if hi.x < min.x || lo.x > max.x || hi.z < min.z || lo.z > max.z {
+                    continue;
+                }
+            }
+            // The local ray spans the probe depth, so `t` scales it back to world units
+            if let Some(t) = raycast_mesh(mesh, origin, direction) {
+                let height = from.y - t * PROBE_DEPTH;
+                best = Some(best.map_or(height, |best| best.max(height)));
+            }
+        }
+        best
+    }
+}
+
+// Closest hit along `origin + t * direction` for t in 0..=1
+fn raycast_mesh(mesh: &Mesh, origin: Vec3, direction: Vec3) -> Option<f32> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|&i| i as usize).collect(),
+        Some(Indices::U32(indices)) => indices.iter().map(|&i| i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+    let mut closest: Option<f32> = None;
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i]]));
+        if let Some(t) = ray_triangle(origin, direction, a, b, c) {
+            if t <= 1.0 && closest.is_none_or(|closest| t < closest) {
+                closest = Some(t);
+            }
+        }
+    }
+    closest
+}
+
+// Möller–Trumbore, hitting either face
+fn ray_triangle(origin: Vec3, direction: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < 1e-8 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv_det;
+    (t >= 0.0).then_some(t)
+}
+
+fn snap_to_ground(probe: GroundProbe, mut grounded: Query<(&mut Transform, &Grounded)>) {
+    for (mut transform, grounded) in grounded.iter_mut() {
+        let feet = transform.translation.y - grounded.height;
+        let from = transform.translation.with_y(feet + STEP_HEIGHT);
+        if let Some(height) = probe.height_below(from) {
+            transform.translation.y = height + grounded.height;
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Health, Hitbox, Invulnerable};
+use crate::damage::{mitigate, DamageDealt};
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillSpawner, WaterSkill};
+use crate::stats::Stats;
+use crate::status::{StatusEffect, StatusEffects, StatusKind};
+use crate::{Enemy, Player};
+
+// Anything this far above a hazard's surface is standing in it
+const HAZARD_HEIGHT: f32 = 1.5;
+// Keeps decals from flickering against the ground they lie on
+const DECAL_LIFT: f32 = 0.02;
+// Overlays sit just above the decal
+const OVERLAY_LIFT: f32 = 0.04;
+
+// Status effects reapplied to everything inside on every tick
+#[derive(Debug, Clone, Copy, Deserialize, Reflect)]
+pub enum HazardEffect {
+    Burn {
+        damage_per_second: f32,
+        duration: f32,
+    },
+    Slow {
+        factor: f32,
+        duration: f32,
+    },
+}
+
+impl HazardEffect {
+    fn status(self, source: Entity) -> StatusEffect {
+        match self {
+            HazardEffect::Burn {
+                damage_per_second,
+                duration,
+            } => StatusEffect::new(
+                StatusKind::Burn { damage_per_second },
+                duration,
+                Some(source),
+            ),
+            HazardEffect::Slow { factor, duration } => {
+                StatusEffect::new(StatusKind::Slow { factor }, duration, Some(source))
+            }
+        }
+    }
+}
+
+// An area of the floor that hurts players and enemies standing in it, e.g.
+// lava or spikes. Covers `size` on the ground, centered on the entity.
+#[derive(Component, Debug, Clone, Deserialize, Reflect)]
+#[reflect(Component)]
+pub struct Hazard {
+    #[serde(default)]
+    pub size: Vec2,
+    // Dealt every `interval` seconds to everything inside
+    #[serde(default)]
+    pub damage: f32,
+    #[serde(default = "default_interval")]
+    pub interval: f32,
+    #[serde(default)]
+    pub effects: Vec<HazardEffect>,
+    // Flat decal covering the area, as `(r, g, b, a)`
+    #[serde(default)]
+    pub color: Option<(f32, f32, f32, f32)>,
+    // Ground skill tiled across the area for an animated surface, e.g.
+    // bubbling lava. Only for show, it never hits anything.
+    #[serde(default)]
+    pub overlay: Option<String>,
+}
+
+fn default_interval() -> f32 {
+    0.5
+}
+
+impl Hazard {
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let offset = point - transform.translation();
+        offset.xz().abs().cmple(self.size / 2.0).all() && (-0.5..=HAZARD_HEIGHT).contains(&offset.y)
+    }
+}
+
+// Added once a hazard is set up: its tick and the overlay instances kept
+// alive for as long as it exists
+#[derive(Component)]
+struct HazardState {
+    timer: Timer,
+    overlay: Option<Handle<SkillDefinition>>,
+    overlays: Vec<(Vec3, Option<Entity>)>,
+}
+
+#[derive(Resource)]
+struct HazardDecalMesh(Handle<Mesh>);
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Hazard>()
+            .add_systems(Startup, create_decal_mesh)
+            .add_systems(
+                Update,
+                (set_up_hazards, keep_hazard_overlays, hurt_in_hazards)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+fn create_decal_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(HazardDecalMesh(meshes.add(Rectangle::new(1.0, 1.0))));
+}
+
+fn set_up_hazards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mesh: Res<HazardDecalMesh>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hazards: Query<(Entity, &Hazard), Without<HazardState>>,
+) {
+    for (entity, hazard) in hazards.iter() {
+        let mut timer = Timer::from_seconds(hazard.interval.max(0.05), TimerMode::Once);
+        // The first tick lands as soon as something steps in
+        timer.set_elapsed(timer.duration());
+        commands.entity(entity).insert(HazardState {
+            timer,
+            overlay: hazard.overlay.as_ref().map(|path| asset_server.load(path)),
+            overlays: Vec::new(),
+        });
+        let Some((r, g, b, a)) = hazard.color else {
+            continue;
+        };
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                PbrBundle {
+                    mesh: mesh.0.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: Color::srgba(r, g, b, a),
+                        alpha_mode: AlphaMode::Blend,
+                        unlit: true,
+                        ..default()
+                    }),
+                    transform: Transform::from_xyz(0.0, DECAL_LIFT, 0.0)
+                        .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+                        .with_scale(hazard.size.extend(1.0)),
+                    ..default()
+                },
+                NotShadowCaster,
+            ));
+        });
+    }
+}
+
+// Tiles the overlay at its own scale so the sprite isn't stretched, and
+// spawns any that ran out their lifetime again
+fn keep_hazard_overlays(
+    definitions: Res<Assets<SkillDefinition>>,
+    mut hazards: Query<(Entity, &GlobalTransform, &Hazard, &mut HazardState)>,
+    skill_instances: Query<(), With<WaterSkill>>,
+    mut spawner: SkillSpawner,
+) {
+    for (entity, transform, hazard, mut state) in hazards.iter_mut() {
+        let Some(handle) = state.overlay.clone() else {
+            continue;
+        };
+        let Some(definition) = definitions.get(&handle) else {
+            continue;
+        };
+        if state.overlays.is_empty() {
+            let tile = definition.scale.max(0.1);
+            let counts = (hazard.size / tile).ceil().max(Vec2::ONE);
+            let step = hazard.size / counts;
+            for x in 0..counts.x as u32 {
+                for z in 0..counts.y as u32 {
+                    let offset = (Vec2::new(x as f32, z as f32) + 0.5) * step - hazard.size / 2.0;
+                    state
+                        .overlays
+                        .push((Vec3::new(offset.x, OVERLAY_LIFT, offset.y), None));
+                }
+            }
+        }
+        let origin = transform.translation() - definition.spawn_offset();
+        for (offset, overlay) in state.overlays.iter_mut() {
+            if overlay.is_some_and(|overlay| skill_instances.contains(overlay)) {
+                continue;
+            }
+            match spawner.spawn(&handle, definition, entity, origin + *offset, None) {
+                Ok(skill) => {
+                    spawner.commands.entity(skill).remove::<Hitbox>();
+                    *overlay = Some(skill);
+                }
+                Err(error) => spawner.report(&error),
+            }
+        }
+    }
+}
+
+fn hurt_in_hazards(
+    time: Res<Time>,
+    mut hazards: Query<(Entity, &GlobalTransform, &Hazard, &mut HazardState)>,
+    mut targets: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Health,
+            Option<&Stats>,
+            Option<&mut StatusEffects>,
+        ),
+        (Or<(With<Player>, With<Enemy>)>, Without<Invulnerable>),
+    >,
+    mut damage_dealt: EventWriter<DamageDealt>,
+) {
+    for (entity, transform, hazard, mut state) in hazards.iter_mut() {
+        state.timer.tick(time.delta());
+        let inside: Vec<_> = targets
+            .iter()
+            .filter(|(_, target, ..)| hazard.contains(transform, target.translation()))
+            .map(|(target, ..)| target)
+            .collect();
+        // Stepping out and back in doesn't get around the interval
+        if inside.is_empty() || !state.timer.finished() {
+            continue;
+        }
+        state.timer.reset();
+        for target in inside {
+            let Ok((_, _, mut health, stats, status)) = targets.get_mut(target) else {
+                continue;
+            };
+            if let Some(mut status) = status {
+                for effect in hazard.effects.iter() {
+                    status.apply(effect.status(entity));
+                }
+            }
+            let resistance = stats.map_or(0.0, Stats::resistance);
+            let amount = mitigate(hazard.damage, resistance);
+            if amount <= 0.0 {
+                continue;
+            }
+            health.current = (health.current - amount).max(0.0);
+            damage_dealt.send(DamageDealt {
+                source: entity,
+                attacker: None,
+                target,
+                amount,
+                is_crit: false,
+            });
+        }
+    }
+}
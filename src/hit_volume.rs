@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Hitbox, Hurtbox};
+use crate::schedule::GameSet;
+
+pub const HIT_VOLUME_DEBUG_KEY: KeyCode = KeyCode::F11;
+const HITBOX_COLOR: Color = Color::srgb(1.0, 0.3, 0.2);
+const HURTBOX_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+// Segments per full circle when drawing arcs
+const ARC_RESOLUTION: usize = 24;
+
+// Shape of a skill's hitbox. Cones, boxes and lines reach out along the
+// skill's facing, see `HitShape::facing`. Written in the definition as e.g.
+// `hit_shape: Some(Cone(range: 3.0, angle: 60.0))`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Reflect)]
+pub enum HitShape {
+    Sphere {
+        radius: f32,
+    },
+    // Wedge of `angle` degrees, covering the full height
+    Cone {
+        range: f32,
+        angle: f32,
+    },
+    // Starting at the skill and `length` long, centered across and vertically
+    Box {
+        length: f32,
+        width: f32,
+        height: f32,
+    },
+    // Capsule from the skill to `length` ahead of it
+    Line {
+        length: f32,
+        radius: f32,
+    },
+}
+
+impl HitShape {
+    // Flattened forward axis of the skill, or its up axis for quads lying on
+    // the ground, whose forward points straight down
+    pub fn facing(transform: &GlobalTransform) -> Vec3 {
+        let flat = |axis: Vec3| axis.with_y(0.0).try_normalize();
+        flat(*transform.forward())
+            .or_else(|| flat(*transform.up()))
+            .unwrap_or(Vec3::X)
+    }
+
+    // Distance from the skill to the farthest point of the shape
+    pub fn reach(&self) -> f32 {
+        match *self {
+            HitShape::Sphere { radius } => radius,
+            HitShape::Cone { range, .. } => range,
+            HitShape::Box {
+                length,
+                width,
+                height,
+            } => Vec3::new(width / 2.0, height / 2.0, length).length(),
+            HitShape::Line { length, radius } => length + radius,
+        }
+    }
+
+    pub fn overlaps_sphere(&self, transform: &GlobalTransform, center: Vec3, radius: f32) -> bool {
+        let origin = transform.translation();
+        let facing = Self::facing(transform);
+        match *self {
+            HitShape::Sphere { radius: reach } => origin.distance(center) <= reach + radius,
+            HitShape::Cone { range, angle } => sphere_in_cone(
+                origin,
+                facing,
+                range,
+                angle.to_radians() / 2.0,
+                center,
+                radius,
+            ),
+            HitShape::Box {
+                length,
+                width,
+                height,
+            } => {
+                let right = Vec3::Y.cross(facing);
+                let offset = center - origin;
+                let local = Vec3::new(
+                    offset.dot(right),
+                    offset.y,
+                    offset.dot(facing) - length / 2.0,
+                );
+                let half = Vec3::new(width, height, length) / 2.0;
+                (local.abs() - half).max(Vec3::ZERO).length() <= radius
+            }
+            HitShape::Line {
+                length,
+                radius: thickness,
+            } => {
+                let closest = closest_point_on_segment(origin, origin + facing * length, center);
+                closest.distance(center) <= thickness + radius
+            }
+        }
+    }
+
+    pub fn draw(&self, gizmos: &mut Gizmos, transform: &GlobalTransform, color: Color) {
+        let origin = transform.translation();
+        let facing = Self::facing(transform);
+        let yaw = Quat::from_rotation_arc(Vec3::NEG_Z, facing);
+        match *self {
+            HitShape::Sphere { radius } => {
+                gizmos.sphere(origin, Quat::IDENTITY, radius, color);
+            }
+            HitShape::Cone { range, angle } => {
+                let half = angle.to_radians() / 2.0;
+                let edge = |side: f32| origin + Quat::from_rotation_y(side * half) * facing * range;
+                gizmos.line(origin, edge(1.0), color);
+                gizmos.line(origin, edge(-1.0), color);
+                let segments = ((ARC_RESOLUTION as f32 * angle / 360.0).ceil() as usize).max(2);
+                // The arc gizmo starts at local +X and turns towards -Z
+                let rotation = yaw * Quat::from_rotation_y(std::f32::consts::FRAC_PI_2 - half);
+                gizmos
+                    .arc_3d(angle.to_radians(), range, origin, rotation, color)
+                    .resolution(segments);
+            }
+            HitShape::Box {
+                length,
+                width,
+                height,
+            } => {
+                let transform = Transform::from_translation(origin + facing * length / 2.0)
+                    .with_rotation(yaw)
+                    .with_scale(Vec3::new(width, height, length));
+                gizmos.cuboid(transform, color);
+            }
+            HitShape::Line { length, radius } => {
+                let end = origin + facing * length;
+                gizmos.line(origin, end, color);
+                gizmos.sphere(origin, Quat::IDENTITY, radius, color);
+                gizmos.sphere(end, Quat::IDENTITY, radius, color);
+            }
+        }
+    }
+}
+
+pub fn closest_point_on_segment(start: Vec3, end: Vec3, point: Vec3) -> Vec3 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared <= f32::EPSILON {
+        return start;
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    start + segment * t
+}
+
+// Distance along the ray to the first hit on the sphere
+pub fn ray_sphere(ray: Ray3d, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = center - ray.origin;
+    let along = to_center.dot(*ray.direction);
+    let closest_squared = to_center.length_squared() - along * along;
+    if along < 0.0 || closest_squared > radius * radius {
+        return None;
+    }
+    Some(along - (radius * radius - closest_squared).sqrt())
+}
+
+// Treats the cone as a flat wedge; the sphere counts as inside when any part
+// of it is within range and the half angle, widened by how much of the view
+// the sphere takes up
+pub fn sphere_in_cone(
+    apex: Vec3,
+    facing: Vec3,
+    range: f32,
+    half_angle: f32,
+    center: Vec3,
+    radius: f32,
+) -> bool {
+    let offset = (center - apex).with_y(0.0);
+    let distance = offset.length();
+    if distance > range + radius {
+        return false;
+    }
+    if distance <= radius {
+        return true;
+    }
+    let spread = (radius / distance).clamp(0.0, 1.0).asin();
+    facing.angle_between(offset) <= half_angle + spread
+}
+
+#[derive(Resource, Default)]
+pub struct HitVolumeDebug(pub bool);
+
+pub struct HitVolumePlugin;
+
+impl Plugin for HitVolumePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HitShape>()
+            .init_resource::<HitVolumeDebug>()
+            .add_systems(
+                Update,
+                (
+                    toggle_hit_volume_debug.in_set(GameSet::Input),
+                    draw_hit_volumes
+                        .run_if(|debug: Res<HitVolumeDebug>| debug.0)
+                        .in_set(GameSet::Debug),
+                ),
+            );
+    }
+}
+
+fn toggle_hit_volume_debug(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut debug: ResMut<HitVolumeDebug>,
+) {
+    if keyboard_input.just_pressed(HIT_VOLUME_DEBUG_KEY) {
+        debug.0 = !debug.0;
+    }
+}
+
+fn draw_hit_volumes(
+    mut gizmos: Gizmos,
+    hitboxes: Query<(&GlobalTransform, &Hitbox)>,
+    hurtboxes: Query<(&GlobalTransform, &Hurtbox)>,
+) {
+    for (transform, hitbox) in hitboxes.iter() {
+        hitbox.shape.draw(&mut gizmos, transform, HITBOX_COLOR);
+    }
+    for (transform, hurtbox) in hurtboxes.iter() {
+        gizmos.sphere(
+            transform.translation(),
+            Quat::IDENTITY,
+            hurtbox.radius,
+            HURTBOX_COLOR,
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+
+use crate::drag_drop::{DragDropped, Draggable, DropHovered, DropTarget};
+use crate::hud_layout::SafeAreaAnchor;
+use crate::save::SaveFile;
+use crate::schedule::GameSet;
+use crate::skills::{SkillBindings, SkillDefinition, SkillRegistry, HOTBAR_KEYS};
+use crate::spellbook::{skill_icon, skill_label, SpellbookEntry};
+
+const SLOT_SIZE: f32 = 56.0;
+const SLOT_COLOR: Color = Color::srgba(0.1, 0.12, 0.15, 0.75);
+const HOVERED_SLOT_COLOR: Color = Color::srgba(0.3, 0.4, 0.5, 0.9);
+
+// Slot `n` of the hotbar, casting with `HOTBAR_KEYS[n]`
+#[derive(Component, Clone, Copy)]
+struct HotbarSlot(usize);
+
+// The skill name shown in a slot
+#[derive(Component)]
+struct HotbarSlotName(usize);
+
+// The bound skill's icon, filling the slot behind its name
+#[derive(Component)]
+struct HotbarSlotIcon(usize);
+
+pub struct HotbarPlugin;
+
+impl Plugin for HotbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_hotbar)
+            .add_systems(
+                Update,
+                (rearrange_hotbar, save_hotbar)
+                    .chain()
+                    .run_if(on_event::<DragDropped>())
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(
+                Update,
+                (name_hotbar_slots, highlight_drop_targets).in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Digit").unwrap_or(&name).to_string()
+}
+
+fn spawn_hotbar(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(12.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                },
+                ..default()
+            },
+            SafeAreaAnchor::default(),
+        ))
+        .with_children(|bar| {
+            for (slot, (key, _)) in HOTBAR_KEYS.iter().enumerate() {
+                bar.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(SLOT_SIZE),
+                            height: Val::Px(SLOT_SIZE),
+                            border: UiRect::all(Val::Px(2.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: SLOT_COLOR.into(),
+                        border_color: Color::srgba(0.8, 0.9, 1.0, 0.5).into(),
+                        border_radius: BorderRadius::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    HotbarSlot(slot),
+                    Draggable {
+                        label: String::new(),
+                    },
+                    DropTarget,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        ImageBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        HotbarSlotIcon(slot),
+                    ));
+                    button.spawn(
+                        TextBundle::from_section(
+                            key_label(*key),
+                            TextStyle {
+                                font_size: 11.0,
+                                color: Color::srgba(1.0, 1.0, 1.0, 0.6),
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(3.0),
+                            top: Val::Px(1.0),
+                            ..default()
+                        }),
+                    );
+                    button.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 10.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        )
+                        .with_text_justify(JustifyText::Center),
+                        HotbarSlotName(slot),
+                    ));
+                });
+            }
+        });
+}
+
+// Names and icons of the bound skills, refreshed as they are rebound or load
+fn name_hotbar_slots(
+    mut commands: Commands,
+    bindings: Res<SkillBindings>,
+    registry: Res<SkillRegistry>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut loaded: EventReader<AssetEvent<SkillDefinition>>,
+    mut slots: Query<(&HotbarSlot, &mut Draggable)>,
+    mut names: Query<(&HotbarSlotName, &mut Text)>,
+    mut icons: Query<(Entity, &HotbarSlotIcon, &mut Visibility)>,
+) {
+    let loaded = loaded.read().count() > 0;
+    if !bindings.is_changed() && !loaded {
+        return;
+    }
+    let label = |slot: usize| {
+        bindings
+            .get(HOTBAR_KEYS[slot].0)
+            .map(|skill| skill_label(&definitions, &registry, skill))
+            .unwrap_or_default()
+    };
+    for (slot, mut draggable) in slots.iter_mut() {
+        draggable.label = label(slot.0);
+    }
+    for (slot, mut text) in names.iter_mut() {
+        text.sections[0].value = label(slot.0);
+    }
+    for (entity, slot, mut visibility) in icons.iter_mut() {
+        let definition = bindings
+            .get(HOTBAR_KEYS[slot.0].0)
+            .and_then(|skill| definitions.get(skill));
+        let Some(definition) = definition else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+        let (image, atlas) = skill_icon(definition);
+        let mut icon = commands.entity(entity);
+        icon.insert(image);
+        match atlas {
+            Some(atlas) => icon.insert(atlas),
+            None => icon.remove::<TextureAtlas>(),
+        };
+    }
+}
+
+fn highlight_drop_targets(
+    mut slots: Query<(&mut BackgroundColor, Has<DropHovered>), With<HotbarSlot>>,
+) {
+    for (mut color, hovered) in slots.iter_mut() {
+        let wanted = if hovered {
+            HOVERED_SLOT_COLOR
+        } else {
+            SLOT_COLOR
+        };
+        color.set_if_neq(wanted.into());
+    }
+}
+
+// A spellbook skill dropped on a slot takes it, two slots dropped on each
+// other swap, and a slot dragged off the bar is emptied
+fn rearrange_hotbar(
+    mut drops: EventReader<DragDropped>,
+    mut bindings: ResMut<SkillBindings>,
+    slots: Query<&HotbarSlot>,
+    entries: Query<&SpellbookEntry>,
+) {
+    for drop in drops.read() {
+        let target = drop.target.and_then(|target| slots.get(target).ok());
+        let key = |slot: &HotbarSlot| HOTBAR_KEYS[slot.0].0;
+        if let Ok(SpellbookEntry(skill)) = entries.get(drop.source) {
+            if let Some(target) = target {
+                bindings.set(key(target), Some(skill.clone()));
+            }
+            continue;
+        }
+        let Ok(source) = slots.get(drop.source) else {
+            continue;
+        };
+        match (target, drop.target) {
+            (Some(target), _) => {
+                let moved = bindings.get(key(source)).cloned();
+                let replaced = bindings.get(key(target)).cloned();
+                bindings.set(key(target), moved);
+                bindings.set(key(source), replaced);
+            }
+            (None, None) => bindings.set(key(source), None),
+            // Dropped on something that isn't a slot
+            (None, Some(_)) => {}
+        }
+    }
+}
+
+// Skills without a file, like an auditioned sheet, can't be saved and come
+// back as an empty slot
+fn save_hotbar(bindings: Res<SkillBindings>) {
+    if !bindings.is_changed() {
+        return;
+    }
+    let mut save = SaveFile::load();
+    save.hotbar = HOTBAR_KEYS
+        .iter()
+        .map(|(key, _)| {
+            let path = bindings.get(*key)?.path()?;
+            Some(path.to_string())
+        })
+        .collect();
+    save.save();
+}
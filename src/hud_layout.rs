@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::schedule::GameSet;
+use crate::settings::InterfaceSettings;
+
+// Window height the HUD was laid out for, taller windows scale it up
+const REFERENCE_HEIGHT: f32 = 720.0;
+// Keeps very small or very large windows from shrinking the HUD to nothing or
+// blowing it up past the screen
+const WINDOW_SCALE_RANGE: (f32, f32) = (0.6, 2.0);
+// The HUD stays within a centered area this wide for its height, so on
+// ultrawide screens it isn't pushed out to the far corners
+const MAX_HUD_ASPECT: f32 = 16.0 / 9.0;
+
+// Space kept clear of the HUD along each edge of the window, in UI pixels
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SafeArea {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+// HUD roots kept inside the `SafeArea`. Sides set in pixels are pushed in by
+// the matching margin; percentages and unset sides are left alone.
+#[derive(Component, Default)]
+pub struct SafeAreaAnchor {
+    // The sides as spawned, before any margin was added
+    base: Option<UiRect>,
+}
+
+pub struct HudLayoutPlugin;
+
+impl Plugin for HudLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SafeArea>().add_systems(
+            Update,
+            (update_ui_layout, anchor_to_safe_area)
+                .chain()
+                .in_set(GameSet::Presentation),
+        );
+    }
+}
+
+// Reruns on every resize so dragging the window rescales the HUD live
+fn update_ui_layout(
+    settings: Res<InterfaceSettings>,
+    mut resized: EventReader<WindowResized>,
+    windows: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+    mut safe_area: ResMut<SafeArea>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let resized = resized.read().count() > 0;
+    if !resized && !settings.is_changed() && !window.is_added() {
+        return;
+    }
+    let (width, height) = (window.width(), window.height());
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+    let window_scale =
+        (height / REFERENCE_HEIGHT).clamp(WINDOW_SCALE_RANGE.0, WINDOW_SCALE_RANGE.1);
+    let scale = window_scale * settings.ui_scale;
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+
+    // Margins are worked out in window pixels and then brought into UI pixels
+    let margin = settings.safe_area.max(0.0);
+    let pillarbox = ((width - height * MAX_HUD_ASPECT) / 2.0).max(0.0);
+    let side = pillarbox + width * margin;
+    let edge = height * margin;
+    safe_area.set_if_neq(SafeArea {
+        left: side / scale,
+        right: side / scale,
+        top: edge / scale,
+        bottom: edge / scale,
+    });
+}
+
+fn anchor_to_safe_area(
+    safe_area: Res<SafeArea>,
+    mut anchors: Query<(&mut SafeAreaAnchor, &mut Style)>,
+) {
+    for (mut anchor, mut style) in anchors.iter_mut() {
+        if !safe_area.is_changed() && anchor.base.is_some() {
+            continue;
+        }
+        let base = *anchor.base.get_or_insert(UiRect {
+            left: style.left,
+            right: style.right,
+            top: style.top,
+            bottom: style.bottom,
+        });
+        style.left = inset(base.left, safe_area.left);
+        style.right = inset(base.right, safe_area.right);
+        style.top = inset(base.top, safe_area.top);
+        style.bottom = inset(base.bottom, safe_area.bottom);
+    }
+}
+
+fn inset(side: Val, margin: f32) -> Val {
+    match side {
+        Val::Px(px) => Val::Px(px + margin),
+        other => other,
+    }
+}
@@ -0,0 +1,330 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::HashMap;
+
+use crate::billboard::Billboard;
+use crate::equipment::{Equipment, Item};
+use crate::locale::Locale;
+use crate::outline::Outline;
+use crate::schedule::GameSet;
+use crate::toast::ToastEvent;
+use crate::Player;
+
+pub const INTERACT_KEY: KeyCode = KeyCode::KeyE;
+const FOCUS_OUTLINE_COLOR: Color = Color::srgb(0.85, 0.95, 1.0);
+
+// Something the player can use by standing within `radius` and pressing the
+// interact key; what happens is decided by the other components on the entity
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Interactable {
+    pub radius: f32,
+    // Where the prompt icon floats, relative to the entity
+    pub prompt_offset: Vec3,
+}
+
+impl Default for Interactable {
+    fn default() -> Self {
+        Self {
+            radius: 1.5,
+            prompt_offset: Vec3::Y * 1.5,
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Door {
+    pub open: bool,
+    closed_rotation: Option<Quat>,
+}
+
+impl Door {
+    pub fn new(open: bool) -> Self {
+        Self {
+            open,
+            closed_rotation: None,
+        }
+    }
+}
+
+// Gives its item to whoever opens it, equipping it straight away
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Chest {
+    pub item: Option<Item>,
+}
+
+// Toggles every door in `targets` when pulled
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Lever {
+    pub on: bool,
+    pub targets: Vec<Entity>,
+}
+
+#[derive(Event)]
+pub struct InteractEvent {
+    pub interactor: Entity,
+    pub target: Entity,
+}
+
+#[derive(Component)]
+struct InteractionPrompt;
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Interactable>()
+            .register_type::<Door>()
+            .register_type::<Chest>()
+            .register_type::<Lever>()
+            .add_event::<InteractEvent>()
+            .add_systems(Startup, spawn_interaction_prompt)
+            .add_systems(
+                Update,
+                (
+                    (
+                        update_interaction_prompt,
+                        outline_focused_interactable,
+                        send_interactions,
+                    ),
+                    (open_doors, open_chests, pull_levers),
+                    (animate_doors, animate_levers),
+                )
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// Closest interactable the player is in range of
+fn focused_interactable(
+    player: Vec3,
+    interactables: &Query<(Entity, &GlobalTransform, &Interactable)>,
+) -> Option<(Entity, Vec3, Vec3)> {
+    interactables
+        .iter()
+        .map(|(entity, transform, interactable)| {
+            (
+                entity,
+                transform.translation(),
+                transform.translation() + interactable.prompt_offset,
+                interactable.radius,
+            )
+        })
+        .filter(|(_, position, _, radius)| position.distance(player) <= *radius)
+        .min_by(|a, b| a.1.distance(player).total_cmp(&b.1.distance(player)))
+        .map(|(entity, position, prompt, _)| (entity, position, prompt))
+}
+
+fn spawn_interaction_prompt(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color_texture: Some(images.add(key_icon_image())),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Rectangle::new(0.5, 0.5))),
+            material,
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Billboard,
+        InteractionPrompt,
+    ));
+}
+
+// Small key cap with an "E" on it, drawn in code so no asset is needed
+fn key_icon_image() -> Image {
+    const GLYPH: [&str; 7] = [
+        "#####", "#....", "#....", "####.", "#....", "#....", "#####",
+    ];
+    const SIZE: usize = 16;
+    let mut data = vec![0u8; SIZE * SIZE * 4];
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let border = x == 0 || y == 0 || x == SIZE - 1 || y == SIZE - 1;
+            let glyph = (4..11).contains(&y)
+                && (5..10).contains(&x)
+                && GLYPH[y - 4].as_bytes()[x - 5] == b'#';
+            let pixel = if border || glyph {
+                [255, 255, 255, 255]
+            } else {
+                [30, 30, 30, 200]
+            };
+            let index = (y * SIZE + x) * 4;
+            data[index..index + 4].copy_from_slice(&pixel);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: SIZE as u32,
+            height: SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+fn update_interaction_prompt(
+    players: Query<&GlobalTransform, With<Player>>,
+    interactables: Query<(Entity, &GlobalTransform, &Interactable)>,
+    mut prompts: Query<(&mut Transform, &mut Visibility), With<InteractionPrompt>>,
+) {
+    let Ok((mut transform, mut visibility)) = prompts.get_single_mut() else {
+        return;
+    };
+    // There is one prompt, shown over the first thing any player can use
+    let focused = players
+        .iter()
+        .find_map(|player| focused_interactable(player.translation(), &interactables));
+    match focused {
+        Some((_, _, prompt)) => {
+            transform.translation = prompt;
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+fn outline_focused_interactable(
+    mut commands: Commands,
+    players: Query<&GlobalTransform, With<Player>>,
+    interactables: Query<(Entity, &GlobalTransform, &Interactable)>,
+    outlined: Query<Entity, (With<Interactable>, With<Outline>)>,
+) {
+    let focused: Vec<Entity> = players
+        .iter()
+        .filter_map(|player| focused_interactable(player.translation(), &interactables))
+        .map(|(entity, _, _)| entity)
+        .collect();
+    for entity in outlined.iter() {
+        if !focused.contains(&entity) {
+            commands.entity(entity).remove::<Outline>();
+        }
+    }
+    for focused in focused {
+        if !outlined.contains(focused) {
+            commands
+                .entity(focused)
+                .insert(Outline::new(FOCUS_OUTLINE_COLOR));
+        }
+    }
+}
+
+fn send_interactions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    interactables: Query<(Entity, &GlobalTransform, &Interactable)>,
+    mut interactions: EventWriter<InteractEvent>,
+) {
+    if !keyboard_input.just_pressed(INTERACT_KEY) {
+        return;
+    }
+    // One press flips a door or lever once, even with several players on it;
+    // the nearest of them does the interacting
+    let mut nearest: HashMap<Entity, (Entity, f32)> = HashMap::new();
+    for (interactor, player) in players.iter() {
+        let player = player.translation();
+        if let Some((target, position, _)) = focused_interactable(player, &interactables) {
+            let distance = position.distance(player);
+            if nearest
+                .get(&target)
+                .is_none_or(|(_, nearest)| distance < *nearest)
+            {
+                nearest.insert(target, (interactor, distance));
+            }
+        }
+    }
+    for (target, (interactor, _)) in nearest {
+        interactions.send(InteractEvent { interactor, target });
+    }
+}
+
+fn open_doors(mut interactions: EventReader<InteractEvent>, mut doors: Query<&mut Door>) {
+    for interaction in interactions.read() {
+        if let Ok(mut door) = doors.get_mut(interaction.target) {
+            door.open = !door.open;
+            println!("Door {}", if door.open { "opened" } else { "closed" });
+        }
+    }
+}
+
+fn open_chests(
+    mut interactions: EventReader<InteractEvent>,
+    mut chests: Query<&mut Chest>,
+    mut equipment: Query<&mut Equipment>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for interaction in interactions.read() {
+        let Ok(mut chest) = chests.get_mut(interaction.target) else {
+            continue;
+        };
+        let Some(item) = chest.item.take() else {
+            println!("The chest is empty");
+            continue;
+        };
+        println!("Found {}", item.name);
+        toasts.send(ToastEvent::new(
+            locale.format("toast.found_item", &[("item", &item.name)]),
+        ));
+        if let Ok(mut equipment) = equipment.get_mut(interaction.interactor) {
+            equipment.equip(item);
+        }
+    }
+}
+
+fn pull_levers(
+    mut interactions: EventReader<InteractEvent>,
+    mut levers: Query<&mut Lever>,
+    mut doors: Query<&mut Door>,
+) {
+    for interaction in interactions.read() {
+        let Ok(mut lever) = levers.get_mut(interaction.target) else {
+            continue;
+        };
+        lever.on = !lever.on;
+        println!("Lever pulled");
+        for target in lever.targets.iter() {
+            if let Ok(mut door) = doors.get_mut(*target) {
+                door.open = !door.open;
+            }
+        }
+    }
+}
+
+// Doors swing a quarter turn around their own origin
+fn animate_doors(mut doors: Query<(&mut Door, &mut Transform), Changed<Door>>) {
+    for (mut door, mut transform) in doors.iter_mut() {
+        let closed = *door
+            .bypass_change_detection()
+            .closed_rotation
+            .get_or_insert(transform.rotation);
+        transform.rotation = if door.open {
+            closed * Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)
+        } else {
+            closed
+        };
+    }
+}
+
+fn animate_levers(mut levers: Query<(&Lever, &mut Transform), Changed<Lever>>) {
+    for (lever, mut transform) in levers.iter_mut() {
+        let tilt = if lever.on { -0.6 } else { 0.6 };
+        transform.rotation = Quat::from_rotation_z(tilt);
+    }
+}
@@ -0,0 +1,501 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, RecursiveDependencyLoadState};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::console::{Console, RegisterConsoleCommand};
+use crate::controller::MovementIntent;
+use crate::ground::Ground;
+use crate::hazard::Hazard;
+use crate::locale::Locale;
+use crate::portal::{Portal, PortalDestination};
+use crate::prefab::PrefabSpawner;
+use crate::respawn::RespawnPoint;
+use crate::schedule::GameSet;
+use crate::sky::Sky;
+use crate::state_cleanup::{LevelScoped, LevelTransition};
+use crate::toast::ToastEvent;
+use crate::Player;
+
+// Seconds the screen takes to go black, and to come back
+pub(crate) const FADE_TIME: f32 = 0.4;
+const WALL_HEIGHT: f32 = 2.0;
+// Players standing this close to an exit's center take it
+const EXIT_RADIUS: f32 = 0.8;
+// Space between players arriving together
+const ARRIVAL_SPACING: f32 = 1.0;
+
+// A room laid out as a grid of tiles, in `assets/levels/*.level.ron`.
+// `tiles` is read top to bottom with one character per tile: `#` is a wall,
+// `P` where players arrive, `>` the exit to `next`, anything else floor.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct LevelDefinition {
+    pub name: String,
+    #[serde(default = "default_tile_size")]
+    pub tile_size: f32,
+    pub tiles: Vec<String>,
+    #[serde(default)]
+    pub props: Vec<LevelProp>,
+    #[serde(default)]
+    pub portals: Vec<LevelPortal>,
+    #[serde(default)]
+    pub hazards: Vec<LevelHazard>,
+    // Path of the level the exit leads to, loaded in the background while
+    // this one is played
+    #[serde(default)]
+    pub next: Option<String>,
+    // The default sky when unset
+    #[serde(default)]
+    pub sky: Option<Sky>,
+}
+
+// A prefab placed on a tile, by column and row
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelProp {
+    pub prefab: String,
+    pub tile: (u32, u32),
+}
+
+// A portal on a tile, leading to another portal by name or to a level
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelPortal {
+    pub name: String,
+    pub destination: PortalDestination,
+    pub tile: (u32, u32),
+}
+
+// A hazard covering the tiles from one corner to the other, both included.
+// Its own `size` is taken from the tiles.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelHazard {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+    pub hazard: Hazard,
+}
+
+fn default_tile_size() -> f32 {
+    2.0
+}
+
+impl LevelDefinition {
+    // Tiles with their column and row, row 0 at the top
+    fn tiles(&self) -> impl Iterator<Item = (u32, u32, char)> + '_ {
+        self.tiles.iter().enumerate().flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .map(move |(column, tile)| (column as u32, row as u32, tile))
+        })
+    }
+
+    fn size(&self) -> UVec2 {
+        let columns = self.tiles.iter().map(|line| line.chars().count());
+        UVec2::new(columns.max().unwrap_or(0) as u32, self.tiles.len() as u32)
+    }
+
+    // Center of a tile on the floor, the grid being centered on the origin
+    pub fn tile_position(&self, column: u32, row: u32) -> Vec3 {
+        let size = self.size().as_vec2();
+        let offset = (Vec2::new(column as f32, row as f32) + 0.5 - size / 2.0) * self.tile_size;
+        Vec3::new(offset.x, 0.0, offset.y)
+    }
+
+    fn arrival(&self) -> Vec3 {
+        self.tiles()
+            .find(|(_, _, tile)| *tile == 'P')
+            .map_or(Vec3::ZERO, |(column, row, _)| {
+                self.tile_position(column, row)
+            })
+    }
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+#[derive(Debug, Error)]
+pub enum LevelLoaderError {
+    #[error("could not read level: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<LevelDefinition, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+// Asks to leave the current level for the one at `path`
+#[derive(Event, Clone)]
+pub struct LoadLevel {
+    pub path: String,
+}
+
+// Walking onto it loads `level`
+#[derive(Component)]
+pub struct LevelExit {
+    pub level: String,
+}
+
+// Full-screen black overlay faded in and out around level changes
+#[derive(Component)]
+struct LevelFade;
+
+#[derive(Default)]
+enum StreamingPhase {
+    #[default]
+    Idle,
+    FadingOut(Handle<LevelDefinition>, Timer),
+    // Black until the level has loaded and the old one is torn down
+    Loading(Handle<LevelDefinition>),
+    Building(Handle<LevelDefinition>),
+    FadingIn(Timer),
+}
+
+// Moves between levels: the screen fades out, the old level's `LevelScoped`
+// entities are despawned once the new one has loaded, the new one is built
+// around the players and the screen fades back in. Players and everything on
+// them, like equipment, carry over.
+#[derive(Resource, Default)]
+pub struct LevelStreaming {
+    pub current: Option<Handle<LevelDefinition>>,
+    // Kept so the next level stays loaded until it is entered
+    next: Option<Handle<LevelDefinition>>,
+    phase: StreamingPhase,
+}
+
+impl LevelStreaming {
+    pub fn is_transitioning(&self) -> bool {
+        !matches!(self.phase, StreamingPhase::Idle)
+    }
+}
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelDefinition>()
+            .init_asset_loader::<LevelLoader>()
+            .init_resource::<LevelStreaming>()
+            .add_event::<LoadLevel>()
+            .register_console_command("level", "level <name>", level_command)
+            .add_systems(Startup, spawn_level_fade)
+            .add_systems(Update, start_level_loads.in_set(GameSet::Input))
+            .add_systems(Update, take_level_exits.in_set(GameSet::Simulation))
+            // After the Input set, where the old level is cleaned up
+            .add_systems(
+                Update,
+                (stream_levels, fade_level_overlay)
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn level_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let name = args.first().ok_or("expected a level name")?;
+    let path = format!("levels/{}.level.ron", name);
+    world.send_event(LoadLevel { path: path.clone() });
+    Ok(format!("Loading {}", path))
+}
+
+fn spawn_level_fade(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.0).into(),
+            // Over the HUD and menus too
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        LevelFade,
+    ));
+}
+
+fn start_level_loads(
+    mut loads: EventReader<LoadLevel>,
+    mut streaming: ResMut<LevelStreaming>,
+    asset_server: Res<AssetServer>,
+) {
+    // Only the last request counts, and none while already moving
+    let Some(load) = loads.read().last() else {
+        return;
+    };
+    if streaming.is_transitioning() {
+        return;
+    }
+    // Loading starts now and carries on in the background during the fade
+    let level = asset_server.load(load.path.clone());
+    streaming.phase =
+        StreamingPhase::FadingOut(level, Timer::from_seconds(FADE_TIME, TimerMode::Once));
+}
+
+fn take_level_exits(
+    streaming: Res<LevelStreaming>,
+    exits: Query<(&GlobalTransform, &LevelExit)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    mut loads: EventWriter<LoadLevel>,
+) {
+    if streaming.is_transitioning() {
+        return;
+    }
+    let taken = exits.iter().find(|(exit, _)| {
+        players.iter().any(|player| {
+            player.translation().xz().distance(exit.translation().xz()) <= EXIT_RADIUS
+        })
+    });
+    if let Some((_, exit)) = taken {
+        loads.send(LoadLevel {
+            path: exit.level.clone(),
+        });
+    }
+}
+
+fn stream_levels(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut streaming: ResMut<LevelStreaming>,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<LevelDefinition>>,
+    mut builder: LevelBuilder,
+    mut console: ResMut<Console>,
+    mut transitions: EventWriter<LevelTransition>,
+) {
+    let streaming = &mut *streaming;
+    streaming.phase = match std::mem::take(&mut streaming.phase) {
+        StreamingPhase::Idle => StreamingPhase::Idle,
+        StreamingPhase::FadingOut(level, mut timer) => {
+            if timer.tick(time.delta()).finished() {
+                StreamingPhase::Loading(level)
+            } else {
+                StreamingPhase::FadingOut(level, timer)
+            }
+        }
+        StreamingPhase::Loading(level) => {
+            match asset_server.recursive_dependency_load_state(&level) {
+                RecursiveDependencyLoadState::Loaded => {
+                    transitions.send(LevelTransition);
+                    StreamingPhase::Building(level)
+                }
+                // Nothing to go to, so stay in the current level
+                RecursiveDependencyLoadState::Failed => {
+                    console.print(format!(
+                        "Could not load level {}",
+                        level.path().map_or_else(String::new, ToString::to_string)
+                    ));
+                    StreamingPhase::FadingIn(Timer::from_seconds(FADE_TIME, TimerMode::Once))
+                }
+                _ => StreamingPhase::Loading(level),
+            }
+        }
+        StreamingPhase::Building(level) => {
+            if let Some(definition) = levels.get(&level) {
+                builder.build(definition);
+                commands.insert_resource(definition.sky.clone().unwrap_or_default());
+                streaming.next = definition
+                    .next
+                    .as_ref()
+                    .map(|next| asset_server.load(next.clone()));
+            }
+            streaming.current = Some(level);
+            StreamingPhase::FadingIn(Timer::from_seconds(FADE_TIME, TimerMode::Once))
+        }
+        StreamingPhase::FadingIn(mut timer) => {
+            if timer.tick(time.delta()).finished() {
+                StreamingPhase::Idle
+            } else {
+                StreamingPhase::FadingIn(timer)
+            }
+        }
+    };
+}
+
+#[derive(SystemParam)]
+struct LevelBuilder<'w, 's> {
+    prefabs: PrefabSpawner<'w, 's>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    respawn: ResMut<'w, RespawnPoint>,
+    players: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut Transform,
+            Option<&'static mut MovementIntent>,
+        ),
+        With<Player>,
+    >,
+    locale: Res<'w, Locale>,
+    toasts: EventWriter<'w, ToastEvent>,
+}
+
+impl LevelBuilder<'_, '_> {
+    fn build(&mut self, level: &LevelDefinition) {
+        let size = level.size().as_vec2() * level.tile_size;
+        self.prefabs.commands.spawn((
+            PbrBundle {
+                mesh: self.meshes.add(Plane3d::new(Vec3::Y, size / 2.0)),
+                material: self.materials.add(Color::srgb(0.3, 0.45, 0.5)),
+                ..default()
+            },
+            Ground,
+            LevelScoped,
+        ));
+
+        // Walls count as ground too, so projectiles bounce off them and
+        // nothing grounded climbs them
+        let wall = self
+            .meshes
+            .add(Cuboid::new(level.tile_size, WALL_HEIGHT, level.tile_size));
+        let wall_material = self.materials.add(Color::srgb(0.35, 0.35, 0.4));
+        let exit = self.meshes.add(Cuboid::new(
+            level.tile_size * 0.8,
+            0.05,
+            level.tile_size * 0.8,
+        ));
+        let exit_material = self.materials.add(StandardMaterial {
+            base_color: Color::srgb(0.3, 0.8, 1.0),
+            emissive: LinearRgba::rgb(0.5, 1.5, 2.0),
+            ..default()
+        });
+        for (column, row, tile) in level.tiles() {
+            let position = level.tile_position(column, row);
+            match tile {
+                '#' => {
+                    self.prefabs.commands.spawn((
+                        PbrBundle {
+                            mesh: wall.clone(),
+                            material: wall_material.clone(),
+                            transform: Transform::from_translation(
+                                position + Vec3::Y * WALL_HEIGHT / 2.0,
+                            ),
+                            ..default()
+                        },
+                        Ground,
+                        LevelScoped,
+                    ));
+                }
+                '>' => {
+                    let Some(next) = &level.next else {
+                        warn!("{} has an exit but no next level", level.name);
+                        continue;
+                    };
+                    self.prefabs.commands.spawn((
+                        PbrBundle {
+                            mesh: exit.clone(),
+                            material: exit_material.clone(),
+                            transform: Transform::from_translation(position),
+                            ..default()
+                        },
+                        LevelExit {
+                            level: next.clone(),
+                        },
+                        LevelScoped,
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        for prop in &level.props {
+            let position = level.tile_position(prop.tile.0, prop.tile.1) + Vec3::Y * 0.5;
+            match self
+                .prefabs
+                .spawn(&prop.prefab, Transform::from_translation(position))
+            {
+                Ok(mut entity) => {
+                    entity.insert(LevelScoped);
+                }
+                Err(error) => warn!("{}", error),
+            }
+        }
+
+        for LevelHazard { from, to, hazard } in &level.hazards {
+            let corners = [
+                level.tile_position(from.0, from.1),
+                level.tile_position(to.0, to.1),
+            ];
+            let size = (corners[0] - corners[1]).xz().abs() + level.tile_size;
+            self.prefabs.commands.spawn((
+                SpatialBundle::from_transform(Transform::from_translation(
+                    (corners[0] + corners[1]) / 2.0,
+                )),
+                Hazard {
+                    size,
+                    ..hazard.clone()
+                },
+                LevelScoped,
+            ));
+        }
+
+        for portal in &level.portals {
+            let position = level.tile_position(portal.tile.0, portal.tile.1);
+            self.prefabs.commands.spawn((
+                SpatialBundle::from_transform(Transform::from_translation(position)),
+                Portal::new(portal.name.clone(), portal.destination.clone()),
+                LevelScoped,
+            ));
+        }
+
+        // Everyone arrives side by side and comes back here after dying
+        let arrival = level.arrival();
+        let mut players: Vec<_> = self.players.iter_mut().collect();
+        players.sort_by_key(|(entity, _, _)| *entity);
+        let count = players.len();
+        for (i, (_, mut transform, intent)) in players.into_iter().enumerate() {
+            let offset = (i as f32 - (count as f32 - 1.0) / 2.0) * ARRIVAL_SPACING;
+            transform.translation = arrival + Vec3::new(offset, 0.5, 0.0);
+            if let Some(mut intent) = intent {
+                intent.0 = Vec3::ZERO;
+            }
+        }
+        *self.respawn = RespawnPoint {
+            position: Some(arrival + Vec3::Y * 0.5),
+            checkpoint: None,
+        };
+        self.toasts.send(ToastEvent::new(
+            self.locale
+                .format("toast.level_entered", &[("level", &level.name)]),
+        ));
+    }
+}
+
+fn fade_level_overlay(
+    streaming: Res<LevelStreaming>,
+    mut fades: Query<&mut BackgroundColor, With<LevelFade>>,
+) {
+    let alpha = match &streaming.phase {
+        StreamingPhase::Idle => 0.0,
+        StreamingPhase::FadingOut(_, timer) => timer.fraction(),
+        StreamingPhase::Loading(_) | StreamingPhase::Building(_) => 1.0,
+        StreamingPhase::FadingIn(timer) => 1.0 - timer.fraction(),
+    };
+    for mut color in fades.iter_mut() {
+        if color.0.alpha() != alpha {
+            color.0.set_alpha(alpha);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Bevy system signatures trip these lints routinely
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use bevy::prelude::*;
+
+pub mod animation;
+pub mod arena;
+pub mod billboard;
+pub mod boss;
+pub mod camera;
+pub mod cinematic;
+pub mod combat;
+pub mod console;
+pub mod content_pack;
+pub mod controller;
+pub mod corpse;
+pub mod cursor;
+pub mod damage;
+pub mod day_night;
+pub mod destructible;
+pub mod diagnostics;
+pub mod drag_drop;
+pub mod equipment;
+pub mod gallery;
+pub mod ground;
+pub mod hazard;
+pub mod hit_volume;
+pub mod hotbar;
+pub mod hud_layout;
+pub mod interaction;
+pub mod level;
+pub mod locale;
+pub mod loot;
+pub mod minimap;
+pub mod music;
+pub mod offscreen_indicator;
+pub mod on_death;
+pub mod outline;
+pub mod patrol;
+pub mod pause;
+pub mod photo_mode;
+pub mod portal;
+pub mod portrait;
+pub mod post_process;
+pub mod prefab;
+pub mod progression;
+pub mod render_preview;
+pub mod respawn;
+pub mod rumble;
+pub mod save;
+pub mod schedule;
+pub mod screen_space;
+pub mod settings;
+pub mod skills;
+pub mod sky;
+pub mod socket;
+pub mod spellbook;
+pub mod state_cleanup;
+pub mod stats;
+pub mod status;
+pub mod status_visuals;
+pub mod targeting;
+pub mod terrain;
+pub mod threat;
+pub mod time_scale;
+pub mod toast;
+pub mod touch;
+pub mod transition;
+pub mod tutorial;
+pub mod weather;
+pub mod world_check;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Player;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Enemy;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct MainCamera;
+
+// Midpoint of every player, what views shared between them center on.
+// `None` when there are no players.
+pub fn players_center(positions: impl IntoIterator<Item = Vec3>) -> Option<Vec3> {
+    let (sum, count) = positions
+        .into_iter()
+        .fold((Vec3::ZERO, 0), |(sum, count), position| {
+            (sum + position, count + 1)
+        });
+    (count > 0).then(|| sum / count as f32)
+}
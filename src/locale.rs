@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::GameSet;
+use crate::settings::InterfaceSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    // Shown in the language's own words, so it can be found from any other
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    // Key to string map, built in so text is there before any asset loads
+    fn strings(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../assets/locales/en.ron"),
+            Language::French => include_str!("../assets/locales/fr.ron"),
+        }
+    }
+}
+
+fn parse_strings(language: Language) -> HashMap<String, String> {
+    ron::from_str(language.strings()).unwrap_or_else(|error| {
+        warn!("Invalid strings for {:?}: {}", language, error);
+        HashMap::default()
+    })
+}
+
+// Strings for the current language. Missing keys fall back to English, then
+// to the key itself so they are easy to spot.
+#[derive(Resource)]
+pub struct Locale {
+    language: Language,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            strings: parse_strings(language),
+            fallback: parse_strings(Language::English),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    // Fills `{name}` placeholders in the string, e.g.
+    // `locale.format("toast.found_item", &[("item", &item.name)])`
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+// Text whose first section shows a locale string, refreshed when the language
+// changes. Text rebuilt every frame formats through `Locale` directly instead.
+#[derive(Component, Clone)]
+pub struct Localized {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Localized {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn text(&self, locale: &Locale) -> String {
+        let args: Vec<(&str, &dyn Display)> = self
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect();
+        locale.format(self.key, &args)
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>().add_systems(
+            Update,
+            (
+                apply_language
+                    .run_if(resource_exists_and_changed::<InterfaceSettings>)
+                    .in_set(GameSet::Input),
+                update_localized_texts.in_set(GameSet::Presentation),
+            ),
+        );
+    }
+}
+
+fn apply_language(settings: Res<InterfaceSettings>, mut locale: ResMut<Locale>) {
+    if locale.language() != settings.language {
+        *locale = Locale::new(settings.language);
+    }
+}
+
+fn update_localized_texts(locale: Res<Locale>, mut texts: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in texts.iter_mut() {
+        if !locale.is_changed() && !localized.is_changed() {
+            continue;
+        }
+        if let Some(section) = text.sections.first_mut() {
+            section.value = localized.text(&locale);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Health, Mana};
+use crate::prefab::PrefabSpawner;
+use crate::schedule::GameSet;
+use crate::state_cleanup::LevelScoped;
+use crate::Player;
+
+// Players walking this close to a pickup collect it
+const PICKUP_RADIUS: f32 = 0.8;
+// How far pickups bob up and down, and how fast
+const BOB_HEIGHT: f32 = 0.15;
+const BOB_SPEED: f32 = 3.0;
+
+// A prefab dropped with the given chance, from 0 to 1
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct LootDrop {
+    pub prefab: String,
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+// Collected by the first player to walk over it, listed as `Pickup(...)` in
+// its prefab
+#[derive(Component, Debug, Clone, Copy, Deserialize, Reflect)]
+#[reflect(Component)]
+pub enum Pickup {
+    Health(f32),
+    Mana(f32),
+}
+
+// Where a pickup rests, so bobbing doesn't drift it
+#[derive(Component)]
+struct PickupRest(Vec3);
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Pickup>()
+            .add_systems(Update, collect_pickups.in_set(GameSet::Simulation))
+            .add_systems(Update, bob_pickups.in_set(GameSet::Animation));
+    }
+}
+
+// Rolls every drop on its own and scatters what comes up around `position`.
+// Loot belongs to the level when whatever dropped it did.
+pub fn drop_loot(prefabs: &mut PrefabSpawner, loot: &[LootDrop], position: Vec3, scoped: bool) {
+    let dropped: Vec<_> = loot
+        .iter()
+        .filter(|drop| rand::random::<f32>() < drop.chance)
+        .collect();
+    for (i, drop) in dropped.iter().enumerate() {
+        let angle = std::f32::consts::TAU * i as f32 / dropped.len() as f32;
+        let offset = if dropped.len() > 1 {
+            Vec3::new(angle.cos(), 0.0, angle.sin()) * 0.6
+        } else {
+            Vec3::ZERO
+        };
+        match prefabs.spawn(&drop.prefab, Transform::from_translation(position + offset)) {
+            Ok(mut entity) => {
+                if scoped {
+                    entity.insert(LevelScoped);
+                }
+            }
+            Err(error) => warn!("{}", error),
+        }
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    pickups: Query<(Entity, &GlobalTransform, &Pickup)>,
+    mut players: Query<(&GlobalTransform, Option<&mut Health>, Option<&mut Mana>), With<Player>>,
+) {
+    for (entity, transform, pickup) in pickups.iter() {
+        let position = transform.translation();
+        let Some((_, health, mana)) = players.iter_mut().find(|(player, _, _)| {
+            player.translation().xz().distance(position.xz()) <= PICKUP_RADIUS
+        }) else {
+            continue;
+        };
+        match *pickup {
+            Pickup::Health(amount) => {
+                if let Some(mut health) = health {
+                    health.current = (health.current + amount).min(health.max);
+                }
+            }
+            Pickup::Mana(amount) => {
+                if let Some(mut mana) = mana {
+                    mana.current = (mana.current + amount).min(mana.max);
+                }
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn bob_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pickups: Query<(Entity, &mut Transform, Option<&PickupRest>), With<Pickup>>,
+) {
+    for (entity, mut transform, rest) in pickups.iter_mut() {
+        let Some(PickupRest(rest)) = rest else {
+            commands
+                .entity(entity)
+                .insert(PickupRest(transform.translation));
+            continue;
+        };
+        let bob = (time.elapsed_seconds() * BOB_SPEED + rest.x + rest.z).sin() * BOB_HEIGHT;
+        transform.translation = *rest + Vec3::Y * bob;
+    }
+}
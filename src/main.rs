@@ -1,57 +1,180 @@
 use bevy::math::prelude::*;
 use bevy::prelude::*;
-
-const SPRITE_SIZE: f32 = 192.0;
-const SPRITE_COLS: usize = 5;
-const SPRITE_ROWS: usize = 5;
-const TOTAL_FRAMES: usize = SPRITE_COLS * SPRITE_ROWS;
-
-#[derive(Component)]
-struct WaterSkill {
-    animation_timer: Timer,
-    lifetime: Timer,
-}
-
-#[derive(Component)]
-struct Player;
-
-#[derive(Component)]
-struct Enemy;
-
-#[derive(Component)]
-struct MainCamera;
-
-#[derive(Resource)]
-struct SkillSpriteSheet {
-    texture: Handle<Image>,
-    atlas_layout: Handle<TextureAtlasLayout>,
-}
+use twodinthreedbevy::animation::AnimationPlugin;
+use twodinthreedbevy::arena::{Arena, ArenaPlugin};
+use twodinthreedbevy::billboard::BillboardPlugin;
+use twodinthreedbevy::boss::BossPlugin;
+use twodinthreedbevy::camera::{CameraMode, CameraModePlugin};
+use twodinthreedbevy::cinematic::CinematicPlugin;
+use twodinthreedbevy::combat::CombatPlugin;
+use twodinthreedbevy::console::ConsolePlugin;
+use twodinthreedbevy::content_pack::ContentPackPlugin;
+use twodinthreedbevy::controller::ControllerPlugin;
+use twodinthreedbevy::corpse::CorpsePlugin;
+use twodinthreedbevy::cursor::CursorPlugin;
+use twodinthreedbevy::day_night::{DayNightLight, DayNightPlugin};
+use twodinthreedbevy::destructible::DestructiblePlugin;
+use twodinthreedbevy::diagnostics::DiagnosticsOverlayPlugin;
+use twodinthreedbevy::drag_drop::DragDropPlugin;
+use twodinthreedbevy::equipment::{Equipment, EquipmentPlugin, EquipmentSlot, Item};
+use twodinthreedbevy::gallery::{Gallery, GalleryPlugin};
+use twodinthreedbevy::ground::{Ground, GroundPlugin};
+use twodinthreedbevy::hazard::{Hazard, HazardEffect, HazardPlugin};
+use twodinthreedbevy::hit_volume::HitVolumePlugin;
+use twodinthreedbevy::hotbar::HotbarPlugin;
+use twodinthreedbevy::hud_layout::HudLayoutPlugin;
+use twodinthreedbevy::interaction::{Chest, InteractionPlugin, Lever};
+use twodinthreedbevy::level::LevelPlugin;
+use twodinthreedbevy::locale::LocalePlugin;
+use twodinthreedbevy::loot::LootPlugin;
+use twodinthreedbevy::minimap::MinimapPlugin;
+use twodinthreedbevy::music::MusicPlugin;
+use twodinthreedbevy::offscreen_indicator::OffscreenIndicatorPlugin;
+use twodinthreedbevy::on_death::OnDeathPlugin;
+use twodinthreedbevy::outline::OutlinePlugin;
+use twodinthreedbevy::patrol::{Patrol, PatrolPlugin};
+use twodinthreedbevy::pause::{GameState, PausePlugin};
+use twodinthreedbevy::photo_mode::PhotoModePlugin;
+use twodinthreedbevy::portal::{Portal, PortalDestination, PortalPlugin};
+use twodinthreedbevy::portrait::PortraitPlugin;
+use twodinthreedbevy::post_process::PostProcessPlugin;
+use twodinthreedbevy::prefab::{PrefabPlugin, PrefabSpawnError, PrefabSpawner};
+use twodinthreedbevy::progression::ProgressionPlugin;
+use twodinthreedbevy::render_preview::RenderPreviewPlugin;
+use twodinthreedbevy::respawn::RespawnPlugin;
+use twodinthreedbevy::rumble::RumblePlugin;
+use twodinthreedbevy::schedule::{GameSchedulePlugin, GameSet};
+use twodinthreedbevy::settings::SettingsPlugin;
+use twodinthreedbevy::skills::on_hit::OnHitEffect;
+use twodinthreedbevy::skills::SkillsPlugin;
+use twodinthreedbevy::sky::SkyPlugin;
+use twodinthreedbevy::spellbook::SpellbookPlugin;
+use twodinthreedbevy::state_cleanup::{LevelScoped, StateCleanupPlugin};
+use twodinthreedbevy::stats::{ModifierKind, Stat, StatsPlugin};
+use twodinthreedbevy::status::{StackPolicy, StatusPlugin};
+use twodinthreedbevy::status_visuals::StatusVisualsPlugin;
+use twodinthreedbevy::targeting::TargetingPlugin;
+use twodinthreedbevy::terrain::{ProceduralLevel, TerrainPlugin};
+use twodinthreedbevy::threat::ThreatPlugin;
+use twodinthreedbevy::time_scale::TimeScalePlugin;
+use twodinthreedbevy::toast::ToastPlugin;
+use twodinthreedbevy::touch::TouchPlugin;
+use twodinthreedbevy::transition::TransitionPlugin;
+use twodinthreedbevy::tutorial::TutorialPlugin;
+use twodinthreedbevy::weather::WeatherPlugin;
+use twodinthreedbevy::world_check::WorldCheckPlugin;
+use twodinthreedbevy::MainCamera;
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_systems(Startup, setup)
+    let mut app = App::new();
+    // `--procedural` or `--procedural=<seed>` replaces the flat demo plane
+    // with a generated level
+    if let Some(arg) = std::env::args().find(|arg| arg.starts_with("--procedural")) {
+        let seed = arg
+            .strip_prefix("--procedural=")
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or(1);
+        app.insert_resource(ProceduralLevel { seed, ..default() });
+    }
+    // `--gallery` shows every skill in a grid for art review instead of a level
+    if std::env::args().any(|arg| arg == "--gallery") {
+        app.insert_resource(Gallery::default());
+    }
+    // `--arena` plays timed survival rounds instead of the demo encounter
+    if std::env::args().any(|arg| arg == "--arena") {
+        app.insert_resource(Arena::default());
+    }
+    app.add_plugins((DefaultPlugins, GameSchedulePlugin))
+        // Gameplay
+        .add_plugins((
+            ArenaPlugin,
+            BossPlugin,
+            CombatPlugin,
+            ControllerPlugin,
+            EquipmentPlugin,
+            GroundPlugin,
+            HitVolumePlugin,
+            InteractionPlugin,
+            OnDeathPlugin,
+            ProgressionPlugin,
+            SkillsPlugin,
+            StatsPlugin,
+            StatusPlugin,
+            TargetingPlugin,
+            TerrainPlugin,
+        ))
+        // Enemy AI and transitions
+        .add_plugins((ThreatPlugin, PatrolPlugin, TransitionPlugin, CorpsePlugin))
+        // Breakable props, hazards and what props drop
+        .add_plugins((DestructiblePlugin, HazardPlugin, LootPlugin))
+        // Player death and respawning, and what ends with the level or the run
+        .add_plugins((RespawnPlugin, StateCleanupPlugin))
+        // Content
+        .add_plugins((ContentPackPlugin, LevelPlugin, PortalPlugin, PrefabPlugin))
+        // Presentation and camera
+        .add_plugins((
+            AnimationPlugin,
+            BillboardPlugin,
+            CameraModePlugin,
+            CinematicPlugin,
+            DayNightPlugin,
+            MusicPlugin,
+            OutlinePlugin,
+            PostProcessPlugin,
+            RenderPreviewPlugin,
+            SkyPlugin,
+            StatusVisualsPlugin,
+            WeatherPlugin,
+        ))
+        // UI and feedback
+        .add_plugins((
+            CursorPlugin,
+            DragDropPlugin,
+            HudLayoutPlugin,
+            LocalePlugin,
+            PausePlugin,
+            PhotoModePlugin,
+            RumblePlugin,
+            SettingsPlugin,
+            ToastPlugin,
+            TouchPlugin,
+            TutorialPlugin,
+        ))
+        // HUD widgets
+        .add_plugins((
+            HotbarPlugin,
+            MinimapPlugin,
+            OffscreenIndicatorPlugin,
+            PortraitPlugin,
+            SpellbookPlugin,
+        ))
+        // Developer tools
+        .add_plugins((
+            ConsolePlugin,
+            DiagnosticsOverlayPlugin,
+            GalleryPlugin,
+            TimeScalePlugin,
+            WorldCheckPlugin,
+        ))
+        .add_systems(Startup, setup.map(bevy::utils::warn))
         .add_systems(
             Update,
-            (
-                spawn_skill,
-                animate_skills,
-                despawn_skills,
-                camera_controls,
-                player_movement,
-                debug_skill_info,
-            ),
+            camera_controls
+                .run_if(not(in_state(GameState::PhotoMode)))
+                .in_set(GameSet::Input),
         )
         .run();
 }
 
 fn setup(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-) {
+    mut prefabs: PrefabSpawner,
+    procedural: Option<Res<ProceduralLevel>>,
+    gallery: Option<Res<Gallery>>,
+    arena: Option<Res<Arena>>,
+) -> Result<(), PrefabSpawnError> {
     // Set up the camera
     commands.spawn((
         Camera3dBundle {
@@ -62,134 +185,187 @@ fn setup(
     ));
 
     // Add a light
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
-            intensity: 1500.0,
-            shadows_enabled: true,
-            ..default()
-        },
-        transform: Transform::from_xyz(4.0, 8.0, 4.0),
-        ..default()
-    });
-
-    // Create a plane
-    commands.spawn(PbrBundle {
-        mesh: meshes.add(Mesh::from(Plane3d::new(Vec3::Y, Vec2::splat(10.0)))),
-        material: materials.add(Color::rgb(0.3, 0.5, 0.3)),
-        transform: Transform::from_xyz(0.0, 0.0, 0.0),
-        ..default()
-    });
-
-    // Create the player
     commands.spawn((
-        PbrBundle {
-            mesh: meshes.add(Mesh::from(Cuboid::new(1.0, 1.0, 1.0))),
-            material: materials.add(Color::rgb(0.8, 0.2, 0.3)),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
+        PointLightBundle {
+            point_light: PointLight {
+                intensity: 1500.0,
+                shadows_enabled: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(4.0, 8.0, 4.0),
             ..default()
         },
-        Player,
-    ));
-
-    // Create an enemy
-    commands.spawn((
-        PbrBundle {
-            mesh: meshes.add(Mesh::from(Cuboid::new(1.0, 1.0, 1.0))),
-            material: materials.add(Color::rgb(0.2, 0.3, 0.8)),
-            transform: Transform::from_xyz(5.0, 0.5, 5.0),
-            ..default()
+        DayNightLight {
+            base_intensity: 1500.0,
         },
-        Enemy,
     ));
 
-    // Set up the skill sprite sheet
-    let texture_handle: Handle<Image> = asset_server.load("water.png");
-    let layout = TextureAtlasLayout::from_grid(
-        UVec2::new(SPRITE_SIZE as u32, SPRITE_SIZE as u32),
-        SPRITE_COLS as u32,
-        SPRITE_ROWS as u32,
-        None,
-        None,
-    );
-    let atlas_layout_handle = texture_atlas_layouts.add(layout);
+    // The gallery only needs the camera and light
+    if gallery.is_some() {
+        return Ok(());
+    }
 
-    commands.insert_resource(SkillSpriteSheet {
-        texture: texture_handle,
-        atlas_layout: atlas_layout_handle,
-    });
-}
+    // Create a plane, unless the terrain plugin is generating the level
+    if procedural.is_none() {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Plane3d::new(Vec3::Y, Vec2::splat(10.0)))),
+                material: materials.add(Color::srgb(0.3, 0.5, 0.3)),
+                transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                ..default()
+            },
+            Ground,
+            LevelScoped,
+        ));
+    }
 
-fn spawn_skill(
-    mut commands: Commands,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    skill_spritesheet: Res<SkillSpriteSheet>,
-    query: Query<&Transform, With<Player>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        if let Ok(player_transform) = query.get_single() {
-            let spawn_position = player_transform.translation + Vec3::new(1.0, 1.0, 0.0);
+    // Starting gear
+    let mut equipment = Equipment::default();
+    equipment.equip(
+        Item::new("Coral Staff", EquipmentSlot::Weapon)
+            .with_modifier(Stat::Attack, ModifierKind::Flat, 15.0)
+            .with_skill_tint(Color::srgb(1.0, 0.75, 0.8))
+            .with_on_hit(OnHitEffect::Burn {
+                damage_per_second: 2.0,
+                duration: 1.5,
+                stacking: StackPolicy::Refresh,
+            }),
+    );
+    equipment.equip(
+        Item::new("Kelp Mail", EquipmentSlot::Armor)
+            .with_modifier(Stat::Defense, ModifierKind::Flat, 15.0)
+            .with_modifier(Stat::Speed, ModifierKind::Percent, -0.05),
+    );
 
-            let material_handle = materials.add(StandardMaterial {
-                base_color_texture: Some(skill_spritesheet.texture.clone()),
-                alpha_mode: AlphaMode::Blend,
-                unlit: true,
-                ..default()
-            });
+    // Create the player
+    prefabs
+        .spawn("player", Transform::from_xyz(0.0, 0.5, 0.0))?
+        .insert(equipment);
 
-            let quad_handle = meshes.add(Mesh::from(Rectangle::new(1.0, 1.0)));
+    // Non-combat props: a lever that works the door, and a chest
+    let door = prefabs
+        .spawn("door", Transform::from_xyz(-4.0, 1.0, -3.0))?
+        .insert(LevelScoped)
+        .id();
+    prefabs
+        .spawn("lever", Transform::from_xyz(-2.0, 0.4, -3.0))?
+        .insert((
+            Lever {
+                on: false,
+                targets: vec![door],
+            },
+            LevelScoped,
+        ));
+    prefabs
+        .spawn("chest", Transform::from_xyz(-3.0, 0.3, 2.0))?
+        .insert((
+            Chest {
+                item: Some(
+                    Item::new("Tidal Ring", EquipmentSlot::Armor)
+                        .with_modifier(Stat::CooldownReduction, ModifierKind::Flat, 0.15)
+                        .with_skill_tint(Color::srgb(0.7, 0.9, 1.0)),
+                ),
+            },
+            LevelScoped,
+        ));
 
-            commands.spawn((
-                PbrBundle {
-                    mesh: quad_handle,
-                    material: material_handle,
-                    transform: Transform::from_translation(spawn_position)
-                        .with_rotation(Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2))
-                        .with_scale(Vec3::splat(0.5)),
-                    ..default()
-                },
-                WaterSkill {
-                    animation_timer: Timer::from_seconds(0.05, TimerMode::Repeating),
-                    lifetime: Timer::from_seconds(3.0, TimerMode::Once),
-                },
-            ));
-            println!("Skill spawned at {:?}", spawn_position);
-        }
-    }
-}
+    // A checkpoint on the way to the boss
+    prefabs
+        .spawn("checkpoint", Transform::from_xyz(2.0, 0.8, -6.0))?
+        .insert(LevelScoped);
 
-fn animate_skills(time: Res<Time>, mut query: Query<(&mut WaterSkill, &mut TextureAtlas)>) {
-    for (mut skill, mut atlas) in query.iter_mut() {
-        skill.animation_timer.tick(time.delta());
-        if skill.animation_timer.just_finished() {
-            atlas.index = (atlas.index + 1) % TOTAL_FRAMES;
-            if atlas.index == 0 {
-                atlas.index = 1; // Skip frame 0, start from 1
-            }
-        }
+    // Create a small group of enemies, generated levels and the arena place
+    // their own. The bomber explodes on death and the splitter splits in two.
+    if procedural.is_some() || arena.is_some() {
+        return Ok(());
     }
-}
-
-fn despawn_skills(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut WaterSkill)>,
-) {
-    for (entity, mut skill) in query.iter_mut() {
-        skill.lifetime.tick(time.delta());
-        if skill.lifetime.finished() {
-            commands.entity(entity).despawn();
-            println!("Skill despawned");
-        }
+    for (prefab, position) in [
+        ("enemy", Vec3::new(5.0, 0.5, 5.0)),
+        ("bomber", Vec3::new(2.5, 0.5, 6.0)),
+        ("splitter", Vec3::new(6.5, 0.5, 2.5)),
+        ("enemy", Vec3::new(3.0, 0.5, 3.0)),
+        ("crate", Vec3::new(-2.0, 0.4, 3.0)),
+        ("crate", Vec3::new(-2.9, 0.4, 3.2)),
+        ("barrel", Vec3::new(-2.4, 0.45, 4.0)),
+    ] {
+        prefabs
+            .spawn(prefab, Transform::from_translation(position))?
+            .insert(LevelScoped);
     }
+    // A sentry walking a square out past the door
+    prefabs
+        .spawn("enemy", Transform::from_xyz(-9.0, 0.5, 6.0))?
+        .insert((
+            Patrol::new(
+                vec![
+                    Vec3::ZERO,
+                    Vec3::new(-4.0, 0.0, 0.0),
+                    Vec3::new(-4.0, 0.0, 4.0),
+                    Vec3::new(0.0, 0.0, 4.0),
+                ],
+                1.0,
+            ),
+            LevelScoped,
+        ));
+    prefabs
+        .spawn("boss", Transform::from_xyz(0.0, 1.0, -12.0))?
+        .insert(LevelScoped);
+    // A lava pool and a strip of spikes between the start and the boss
+    prefabs.commands.spawn((
+        SpatialBundle::from_transform(Transform::from_xyz(7.0, 0.0, -4.0)),
+        Hazard {
+            size: Vec2::new(4.0, 2.0),
+            damage: 6.0,
+            interval: 0.5,
+            effects: vec![HazardEffect::Burn {
+                damage_per_second: 3.0,
+                duration: 2.0,
+            }],
+            color: Some((0.9, 0.3, 0.05, 0.8)),
+            overlay: Some("skills/lava.skill.ron".to_string()),
+        },
+        LevelScoped,
+    ));
+    prefabs.commands.spawn((
+        SpatialBundle::from_transform(Transform::from_xyz(-5.0, 0.0, -6.0)),
+        Hazard {
+            size: Vec2::new(1.0, 4.0),
+            damage: 12.0,
+            interval: 1.0,
+            effects: vec![HazardEffect::Slow {
+                factor: 0.6,
+                duration: 1.0,
+            }],
+            color: Some((0.35, 0.35, 0.4, 0.9)),
+            overlay: None,
+        },
+        LevelScoped,
+    ));
+    // A linked pair of portals, one near the start and one by the boss
+    for (name, to, position) in [
+        ("near", "far", Vec3::new(-3.0, 0.0, -2.0)),
+        ("far", "near", Vec3::new(4.0, 0.0, -8.0)),
+    ] {
+        prefabs.commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(position)),
+            Portal::new(name, PortalDestination::Portal(to.to_string())),
+            LevelScoped,
+        ));
+    }
+    Ok(())
 }
 
 fn camera_controls(
-    time: Res<Time>,
+    // Real time so the debug camera keeps its speed in slow motion
+    time: Res<Time<Real>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    mode: Res<CameraMode>,
     mut query: Query<&mut Transform, With<MainCamera>>,
 ) {
+    // The first-person camera is driven by the mouse instead
+    if *mode == CameraMode::FirstPerson {
+        return;
+    }
     if let Ok(mut transform) = query.get_single_mut() {
         let mut movement = Vec3::ZERO;
         let mut rotation = Vec3::ZERO;
@@ -208,12 +384,6 @@ fn camera_controls(
         if keyboard_input.pressed(KeyCode::KeyD) {
             movement.x += 1.0;
         }
-        if keyboard_input.pressed(KeyCode::KeyQ) {
-            movement.y -= 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::KeyE) {
-            movement.y += 1.0;
-        }
 
         if keyboard_input.pressed(KeyCode::ArrowLeft) {
             rotation.y += 1.0;
@@ -233,38 +403,3 @@ fn camera_controls(
         transform.rotate_y(rotation.y * rotate_speed * time.delta_seconds());
     }
 }
-
-fn player_movement(
-    time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut Transform, With<Player>>,
-) {
-    if let Ok(mut transform) = query.get_single_mut() {
-        let mut movement = Vec3::ZERO;
-        let speed = 3.0;
-
-        if keyboard_input.pressed(KeyCode::KeyI) {
-            movement.z -= 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::KeyK) {
-            movement.z += 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::KeyJ) {
-            movement.x -= 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::KeyL) {
-            movement.x += 1.0;
-        }
-
-        transform.translation += movement * speed * time.delta_seconds();
-    }
-}
-
-fn debug_skill_info(query: Query<(&Transform, &TextureAtlas), With<WaterSkill>>) {
-    for (transform, atlas) in query.iter() {
-        println!(
-            "Skill position: {:?}, Current frame: {}",
-            transform.translation, atlas.index
-        );
-    }
-}
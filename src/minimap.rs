@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::schedule::GameSet;
+use crate::skills::WaterSkill;
+use crate::{players_center, Enemy, Player};
+
+const MAP_SIZE: f32 = 160.0;
+const ICON_SIZE: f32 = 8.0;
+const MIN_RANGE: f32 = 5.0;
+const MAX_RANGE: f32 = 40.0;
+
+// World distance from the player to the minimap's edge, changed with +/-
+#[derive(Resource)]
+pub struct MinimapZoom {
+    pub range: f32,
+}
+
+impl Default for MinimapZoom {
+    fn default() -> Self {
+        Self { range: 12.0 }
+    }
+}
+
+#[derive(Component)]
+struct MinimapFrame;
+
+#[derive(Component)]
+struct MinimapIcon;
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapZoom>()
+            .add_systems(Startup, spawn_minimap)
+            .add_systems(
+                Update,
+                (zoom_minimap, update_minimap_icons)
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn spawn_minimap(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                width: Val::Px(MAP_SIZE),
+                height: Val::Px(MAP_SIZE),
+                border: UiRect::all(Val::Px(2.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            background_color: Color::srgba(0.05, 0.1, 0.1, 0.7).into(),
+            border_color: Color::srgba(0.8, 0.9, 1.0, 0.8).into(),
+            ..default()
+        },
+        MinimapFrame,
+        SafeAreaAnchor::default(),
+    ));
+}
+
+fn zoom_minimap(keyboard_input: Res<ButtonInput<KeyCode>>, mut zoom: ResMut<MinimapZoom>) {
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        zoom.range = (zoom.range * 0.8).max(MIN_RANGE);
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        zoom.range = (zoom.range * 1.25).min(MAX_RANGE);
+    }
+}
+
+// Icons are generated from entity positions every frame, one UI node per
+// tracked entity, drawn relative to the players' midpoint with north up
+fn update_minimap_icons(
+    mut commands: Commands,
+    mut icons: Local<HashMap<Entity, Entity>>,
+    zoom: Res<MinimapZoom>,
+    frame: Query<Entity, With<MinimapFrame>>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    enemies: Query<(Entity, &GlobalTransform), With<Enemy>>,
+    skills: Query<(Entity, &GlobalTransform), With<WaterSkill>>,
+    mut styles: Query<&mut Style, With<MinimapIcon>>,
+) {
+    let Ok(frame) = frame.get_single() else {
+        return;
+    };
+    let Some(center) = players_center(players.iter().map(|(_, player)| player.translation()))
+    else {
+        return;
+    };
+
+    let tracked: Vec<(Entity, Vec3, Color, f32)> = players
+        .iter()
+        .map(|(entity, transform)| {
+            (
+                entity,
+                transform.translation(),
+                Color::srgb(1.0, 1.0, 1.0),
+                ICON_SIZE * 1.25,
+            )
+        })
+        .chain(enemies.iter().map(|(entity, transform)| {
+            (
+                entity,
+                transform.translation(),
+                Color::srgb(1.0, 0.3, 0.3),
+                ICON_SIZE,
+            )
+        }))
+        .chain(skills.iter().map(|(entity, transform)| {
+            (
+                entity,
+                transform.translation(),
+                Color::srgba(0.3, 0.7, 1.0, 0.8),
+                ICON_SIZE * 0.75,
+            )
+        }))
+        .collect();
+
+    // Drop icons whose entity no longer exists
+    icons.retain(|entity, icon| {
+        let alive = tracked.iter().any(|(tracked, ..)| tracked == entity);
+        if !alive {
+            commands.entity(*icon).despawn_recursive();
+        }
+        alive
+    });
+
+    let scale = MAP_SIZE * 0.5 / zoom.range;
+    for (entity, position, color, size) in tracked {
+        let offset = (position - center).xz() * scale;
+        let visible = offset.abs().max_element() <= MAP_SIZE * 0.5;
+        let left = Val::Px(MAP_SIZE * 0.5 + offset.x - size * 0.5);
+        let top = Val::Px(MAP_SIZE * 0.5 + offset.y - size * 0.5);
+        let display = if visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+
+        match icons
+            .get(&entity)
+            .and_then(|icon| styles.get_mut(*icon).ok())
+        {
+            Some(mut style) => {
+                style.left = left;
+                style.top = top;
+                style.display = display;
+            }
+            None => {
+                let icon = commands
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left,
+                                top,
+                                width: Val::Px(size),
+                                height: Val::Px(size),
+                                display,
+                                ..default()
+                            },
+                            background_color: color.into(),
+                            ..default()
+                        },
+                        MinimapIcon,
+                    ))
+                    .id();
+                commands.entity(frame).add_child(icon);
+                icons.insert(entity, icon);
+            }
+        }
+    }
+}
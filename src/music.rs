@@ -0,0 +1,191 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::cinematic::PlayCameraSequence;
+use crate::schedule::GameSet;
+use crate::{Enemy, Player};
+
+const CALM_TRACK: &str = "music/calm.ogg";
+const COMBAT_TRACK: &str = "music/combat.ogg";
+
+// Music layer played at a given intensity, 0 being calm and 1 full combat
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicLayer {
+    Calm,
+    Combat,
+}
+
+impl MusicLayer {
+    // Each layer fades out as the other fades in, with an equal power curve so
+    // the mix doesn't dip in loudness halfway through
+    fn gain(self, intensity: f32) -> f32 {
+        let angle = intensity.clamp(0.0, 1.0) * std::f32::consts::FRAC_PI_2;
+        match self {
+            MusicLayer::Calm => angle.cos(),
+            MusicLayer::Combat => angle.sin(),
+        }
+    }
+}
+
+// Lowers the music by `amount` (0 to 1) for `duration` seconds, so a big
+// impact can be heard over it
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckMusic {
+    pub amount: f32,
+    pub duration: f32,
+}
+
+// Owns the music mix: how intense it is, where it is fading to and how much
+// it is ducked
+#[derive(Resource, Debug, Clone)]
+pub struct AudioDirector {
+    pub music_volume: f32,
+    // Enemies within this distance of the player count towards the intensity
+    pub combat_radius: f32,
+    // Enough nearby enemies to play the combat layer alone
+    pub full_combat_enemies: usize,
+    // Seconds a full crossfade between the layers takes
+    pub crossfade_time: f32,
+    // Seconds ducking takes to come in and to recover
+    pub duck_fade_time: f32,
+    // Ducking sent whenever an ultimate's camera sequence starts
+    pub impact_duck: DuckMusic,
+    pub intensity: f32,
+    pub target_intensity: f32,
+    // How far the music is currently lowered, and how far it is headed
+    pub duck: f32,
+    pub duck_target: f32,
+    pub duck_remaining: f32,
+}
+
+impl Default for AudioDirector {
+    fn default() -> Self {
+        Self {
+            music_volume: 0.6,
+            combat_radius: 12.0,
+            full_combat_enemies: 4,
+            crossfade_time: 2.5,
+            duck_fade_time: 0.15,
+            impact_duck: DuckMusic {
+                amount: 0.6,
+                duration: 1.2,
+            },
+            intensity: 0.0,
+            target_intensity: 0.0,
+            duck: 0.0,
+            duck_target: 0.0,
+            duck_remaining: 0.0,
+        }
+    }
+}
+
+impl AudioDirector {
+    pub fn layer_volume(&self, layer: MusicLayer) -> f32 {
+        self.music_volume * layer.gain(self.intensity) * (1.0 - self.duck)
+    }
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioDirector>()
+            .add_event::<DuckMusic>()
+            .add_systems(Startup, spawn_music_layers)
+            .add_systems(
+                Update,
+                (
+                    measure_intensity,
+                    duck_on_impacts,
+                    apply_ducking,
+                    mix_music_layers,
+                )
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn spawn_music_layers(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    director: Res<AudioDirector>,
+) {
+    for (layer, path) in [
+        (MusicLayer::Calm, CALM_TRACK),
+        (MusicLayer::Combat, COMBAT_TRACK),
+    ] {
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(path),
+                settings: PlaybackSettings::LOOP
+                    .with_volume(Volume::new(director.layer_volume(layer))),
+            },
+            layer,
+        ));
+    }
+}
+
+fn measure_intensity(
+    mut director: ResMut<AudioDirector>,
+    players: Query<&GlobalTransform, With<Player>>,
+    enemies: Query<&GlobalTransform, With<Enemy>>,
+) {
+    // Enemies near any of the players count
+    let nearby = enemies
+        .iter()
+        .filter(|enemy| {
+            players.iter().any(|player| {
+                enemy.translation().distance(player.translation()) <= director.combat_radius
+            })
+        })
+        .count();
+    director.target_intensity =
+        (nearby as f32 / director.full_combat_enemies.max(1) as f32).min(1.0);
+}
+
+fn duck_on_impacts(
+    director: Res<AudioDirector>,
+    mut sequences: EventReader<PlayCameraSequence>,
+    mut ducks: EventWriter<DuckMusic>,
+) {
+    if sequences.read().count() > 0 {
+        ducks.send(director.impact_duck);
+    }
+}
+
+// Overlapping ducks keep the deepest amount and the longest time left
+fn apply_ducking(
+    time: Res<Time<Real>>,
+    mut director: ResMut<AudioDirector>,
+    mut ducks: EventReader<DuckMusic>,
+) {
+    for duck in ducks.read() {
+        director.duck_target = director.duck_target.max(duck.amount.clamp(0.0, 1.0));
+        director.duck_remaining = director.duck_remaining.max(duck.duration);
+    }
+    let dt = time.delta_seconds();
+    director.duck_remaining = (director.duck_remaining - dt).max(0.0);
+    if director.duck_remaining <= 0.0 {
+        director.duck_target = 0.0;
+    }
+    let step = dt / director.duck_fade_time.max(0.01);
+    director.duck = move_towards(director.duck, director.duck_target, step);
+}
+
+// Fades on real time so the music keeps moving while the game is paused
+fn mix_music_layers(
+    time: Res<Time<Real>>,
+    mut director: ResMut<AudioDirector>,
+    sinks: Query<(&MusicLayer, &AudioSink)>,
+) {
+    let step = time.delta_seconds() / director.crossfade_time.max(0.01);
+    director.intensity = move_towards(director.intensity, director.target_intensity, step);
+    for (layer, sink) in sinks.iter() {
+        sink.set_volume(director.layer_volume(*layer));
+    }
+}
+
+fn move_towards(current: f32, target: f32, step: f32) -> f32 {
+    current + (target - current).clamp(-step, step)
+}
@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+
+use crate::hud_layout::SafeArea;
+use crate::schedule::GameSet;
+use crate::screen_space::ScreenProjection;
+use crate::skills::projectile::ProjectileSkill;
+use crate::{Enemy, MainCamera, Player};
+
+// Distance from the screen edge, inside the safe area, arrows are kept at
+const EDGE_MARGIN: f32 = 24.0;
+const ENEMY_ARROW_SIZE: f32 = 16.0;
+const PROJECTILE_ARROW_SIZE: f32 = 11.0;
+const ENEMY_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.9);
+const PROJECTILE_COLOR: Color = Color::srgba(1.0, 0.7, 0.2, 0.9);
+
+// Full-screen layer holding the arrows, so they can be laid out in screen
+// pixels
+#[derive(Component)]
+struct IndicatorLayer;
+
+#[derive(Component)]
+struct OffscreenArrow;
+
+pub struct OffscreenIndicatorPlugin;
+
+impl Plugin for OffscreenIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_indicator_layer).add_systems(
+            Update,
+            update_offscreen_arrows.in_set(GameSet::Presentation),
+        );
+    }
+}
+
+fn spawn_indicator_layer(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            ..default()
+        },
+        IndicatorLayer,
+    ));
+}
+
+// Where along the screen's edge an arrow pointing `direction` from the center
+// sits, kept inside the safe area
+fn edge_point(direction: Vec2, size: Vec2, safe_area: &SafeArea) -> Vec2 {
+    let min = Vec2::new(safe_area.left, safe_area.top) + EDGE_MARGIN;
+    let max = size - Vec2::new(safe_area.right, safe_area.bottom) - EDGE_MARGIN;
+    let center = (min + max) / 2.0;
+    let half = ((max - min) / 2.0).max(Vec2::ZERO);
+    let reach = |half: f32, along: f32| {
+        if along.abs() < 1e-4 {
+            f32::INFINITY
+        } else {
+            half / along.abs()
+        }
+    };
+    let distance = reach(half.x, direction.x).min(reach(half.y, direction.y));
+    center + direction * distance
+}
+
+// One arrow per tracked entity, kept across frames like the minimap's icons.
+// Projectiles count when cast by anyone but a player and heading toward the
+// players.
+fn update_offscreen_arrows(
+    mut commands: Commands,
+    mut arrows: Local<HashMap<Entity, Entity>>,
+    ui_scale: Res<UiScale>,
+    safe_area: Res<SafeArea>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    layers: Query<Entity, With<IndicatorLayer>>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    enemies: Query<(Entity, &GlobalTransform), With<Enemy>>,
+    projectiles: Query<(Entity, &GlobalTransform, &ProjectileSkill)>,
+    mut styles: Query<(&mut Style, &mut Transform), With<OffscreenArrow>>,
+) {
+    let (Ok(window), Ok((camera, camera_transform)), Ok(layer)) = (
+        windows.get_single(),
+        cameras.get_single(),
+        layers.get_single(),
+    ) else {
+        return;
+    };
+    let Some(projection) = ScreenProjection::new(camera, camera_transform) else {
+        return;
+    };
+    let size = window.size() / ui_scale.0;
+    let player_positions: Vec<Vec3> = players
+        .iter()
+        .map(|(_, transform)| transform.translation())
+        .collect();
+    let incoming = |projectile: &ProjectileSkill, position: Vec3| {
+        !players.contains(projectile.caster)
+            && player_positions
+                .iter()
+                .any(|player| projectile.velocity.dot(*player - position) > 0.0)
+    };
+
+    let tracked: Vec<(Entity, Vec3, Color, f32)> = enemies
+        .iter()
+        .map(|(entity, transform)| {
+            (
+                entity,
+                transform.translation(),
+                ENEMY_COLOR,
+                ENEMY_ARROW_SIZE,
+            )
+        })
+        .chain(
+            projectiles
+                .iter()
+                .filter(|(_, transform, projectile)| incoming(projectile, transform.translation()))
+                .map(|(entity, transform, _)| {
+                    (
+                        entity,
+                        transform.translation(),
+                        PROJECTILE_COLOR,
+                        PROJECTILE_ARROW_SIZE,
+                    )
+                }),
+        )
+        .collect();
+
+    // Drop arrows whose entity is gone or no longer tracked
+    arrows.retain(|entity, arrow| {
+        let alive = tracked.iter().any(|(tracked, ..)| tracked == entity);
+        if !alive {
+            commands.entity(*arrow).despawn_recursive();
+        }
+        alive
+    });
+
+    for (entity, position, color, arrow_size) in tracked {
+        let placement = projection
+            .direction_from_center(position)
+            .filter(|(_, on_screen)| !on_screen)
+            .map(|(direction, _)| {
+                let point = edge_point(direction, size, &safe_area);
+                // The square's sharp top left corner is what points
+                let angle = direction.to_angle() - Vec2::NEG_ONE.to_angle();
+                (point, angle)
+            });
+        let display = if placement.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let (point, angle) = placement.unwrap_or_default();
+        let left = Val::Px(point.x - arrow_size / 2.0);
+        let top = Val::Px(point.y - arrow_size / 2.0);
+
+        match arrows
+            .get(&entity)
+            .and_then(|arrow| styles.get_mut(*arrow).ok())
+        {
+            Some((mut style, mut transform)) => {
+                style.left = left;
+                style.top = top;
+                style.display = display;
+                transform.rotation = Quat::from_rotation_z(angle);
+            }
+            None => {
+                let rounded = Val::Percent(50.0);
+                let arrow = commands
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left,
+                                top,
+                                width: Val::Px(arrow_size),
+                                height: Val::Px(arrow_size),
+                                display,
+                                ..default()
+                            },
+                            background_color: color.into(),
+                            border_radius: BorderRadius {
+                                top_left: Val::Px(0.0),
+                                top_right: rounded,
+                                bottom_right: rounded,
+                                bottom_left: rounded,
+                            },
+                            transform: Transform::from_rotation(Quat::from_rotation_z(angle)),
+                            ..default()
+                        },
+                        OffscreenArrow,
+                    ))
+                    .id();
+                commands.entity(layer).add_child(arrow);
+                arrows.insert(entity, arrow);
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{despawn_dead_enemies, Health};
+use crate::prefab::PrefabSpawner;
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillSpawner};
+use crate::Enemy;
+
+// Effects listed under `OnDeath` in an enemy prefab, triggered when it dies
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub enum OnDeathEffect {
+    // Casts a skill where the enemy died. Skills hit enemies, so an explosion
+    // can kill its neighbours and set off their own death effects.
+    SpawnSkill {
+        skill: String,
+        #[serde(skip)]
+        handle: Handle<SkillDefinition>,
+    },
+    // Spawns `count` instances of another prefab, usually a smaller variant,
+    // spread around where the enemy died
+    Split {
+        prefab: String,
+        count: u32,
+        #[serde(default = "default_spread")]
+        spread: f32,
+    },
+}
+
+fn default_spread() -> f32 {
+    0.8
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct OnDeathEffects(pub Vec<OnDeathEffect>);
+
+pub struct OnDeathPlugin;
+
+impl Plugin for OnDeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<OnDeathEffects>().add_systems(
+            Update,
+            // While the dying enemy still exists to cast from
+            trigger_death_effects
+                .before(despawn_dead_enemies)
+                .in_set(GameSet::Simulation),
+        );
+    }
+}
+
+fn trigger_death_effects(
+    dying: Query<
+        (Entity, &Health, &GlobalTransform, &OnDeathEffects),
+        (With<Enemy>, Changed<Health>),
+    >,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut skills: SkillSpawner,
+    mut prefabs: PrefabSpawner,
+) {
+    for (entity, health, transform, effects) in dying.iter() {
+        if health.current > 0.0 {
+            continue;
+        }
+        let position = transform.translation();
+        for effect in effects.0.iter() {
+            match effect {
+                OnDeathEffect::SpawnSkill { handle, .. } => {
+                    if let Some(definition) = definitions.get(handle) {
+                        if let Err(error) = skills.spawn(handle, definition, entity, position, None)
+                        {
+                            skills.report(&error);
+                        }
+                    }
+                }
+                OnDeathEffect::Split {
+                    prefab,
+                    count,
+                    spread,
+                } => {
+                    for i in 0..*count {
+                        let angle = std::f32::consts::TAU * i as f32 / *count as f32;
+                        let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * *spread;
+                        let transform = Transform::from_translation(position + offset);
+                        if let Err(error) = prefabs.spawn(prefab, transform) {
+                            warn!("{}", error);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+
+// Draws a colored outline around the entity's mesh. The outline is an
+// inverted hull: a slightly larger copy of the mesh with its front faces
+// culled, so only the rim around the silhouette shows.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Outline {
+    pub color: Color,
+    // Hull scale added on top of the mesh, 0.08 is 8% larger
+    pub width: f32,
+}
+
+impl Outline {
+    pub fn new(color: Color) -> Self {
+        Self { color, width: 0.08 }
+    }
+}
+
+// Hull child spawned for an `Outline`
+#[derive(Component)]
+struct OutlineHull {
+    hull: Entity,
+    material: Handle<StandardMaterial>,
+}
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Outline>().add_systems(
+            PostUpdate,
+            (
+                remove_outline_hulls,
+                (spawn_outline_hulls, update_outline_hulls),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn outline_material(outline: &Outline) -> StandardMaterial {
+    StandardMaterial {
+        base_color: outline.color,
+        unlit: true,
+        cull_mode: Some(Face::Front),
+        ..default()
+    }
+}
+
+fn spawn_outline_hulls(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    outlined: Query<(Entity, &Outline, &Handle<Mesh>), Without<OutlineHull>>,
+) {
+    for (entity, outline, mesh) in outlined.iter() {
+        let material = materials.add(outline_material(outline));
+        let hull = commands
+            .spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform: Transform::from_scale(Vec3::splat(1.0 + outline.width)),
+                    ..default()
+                },
+                NotShadowCaster,
+            ))
+            .set_parent(entity)
+            .id();
+        commands
+            .entity(entity)
+            .insert(OutlineHull { hull, material });
+    }
+}
+
+fn update_outline_hulls(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    outlined: Query<(&Outline, &OutlineHull), Changed<Outline>>,
+    mut hulls: Query<&mut Transform>,
+) {
+    for (outline, hull) in outlined.iter() {
+        if let Some(material) = materials.get_mut(&hull.material) {
+            material.base_color = outline.color;
+        }
+        if let Ok(mut transform) = hulls.get_mut(hull.hull) {
+            transform.scale = Vec3::splat(1.0 + outline.width);
+        }
+    }
+}
+
+fn remove_outline_hulls(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Outline>,
+    hulls: Query<&OutlineHull, Without<Outline>>,
+) {
+    for entity in removed.read() {
+        // Despawned entirely, the hull went with it as a child; dead enemies are
+        // despawned recursively for this
+        let Ok(hull) = hulls.get(entity) else {
+            continue;
+        };
+        commands.entity(hull.hull).despawn_recursive();
+        commands.entity(entity).remove::<OutlineHull>();
+    }
+}
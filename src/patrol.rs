@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::animation::Animator;
+use crate::controller::MovementIntent;
+use crate::schedule::GameSet;
+use crate::threat::{chase_threat_target, ThreatTable, ThreatTarget};
+use crate::time_scale::TimeScale;
+
+// Fraction of full speed enemies stroll at while nothing has their attention
+const IDLE_SPEED: f32 = 0.4;
+// Close enough to a waypoint or wander spot to count as there
+const ARRIVE_DISTANCE: f32 = 0.3;
+// Enemies ease off within this distance of where they are headed, so they
+// round corners instead of snapping onto the next leg
+const SLOW_DOWN_DISTANCE: f32 = 1.0;
+// Seconds a wandering enemy stands still between strolls
+const WANDER_PAUSE: (f32, f32) = (1.0, 3.0);
+
+// Walks between `waypoints` in a loop while no target is in range, stopping
+// `wait` seconds at each. Waypoints are offsets from where the enemy first
+// went idle, so prefabs and levels can share routes.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Patrol {
+    pub waypoints: Vec<Vec3>,
+    pub wait: f32,
+    pub next: usize,
+    pub waiting: f32,
+    pub origin: Option<Vec3>,
+}
+
+impl Patrol {
+    pub fn new(waypoints: Vec<Vec3>, wait: f32) -> Self {
+        Self {
+            waypoints,
+            wait,
+            next: 0,
+            waiting: 0.0,
+            origin: None,
+        }
+    }
+}
+
+// Strolls to random spots within `radius` of where the enemy first went idle
+// while no target is in range, pausing between them
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Wander {
+    pub radius: f32,
+    pub home: Option<Vec3>,
+    pub destination: Option<Vec3>,
+    pub waiting: f32,
+}
+
+impl Wander {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            home: None,
+            destination: None,
+            waiting: 0.0,
+        }
+    }
+}
+
+// What an enemy is up to, driving which animation clip it plays
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub enum AiState {
+    #[default]
+    Idle,
+    Patrol,
+    Wander,
+    Chase,
+}
+
+impl AiState {
+    pub fn clip(self) -> &'static str {
+        match self {
+            AiState::Idle => "idle",
+            AiState::Patrol | AiState::Wander => "walk",
+            AiState::Chase => "run",
+        }
+    }
+}
+
+pub struct PatrolPlugin;
+
+impl Plugin for PatrolPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Patrol>()
+            .register_type::<Wander>()
+            .register_type::<AiState>()
+            .add_systems(
+                Update,
+                (
+                    (patrol, wander, update_ai_state)
+                        .chain()
+                        .after(chase_threat_target)
+                        .in_set(GameSet::Simulation),
+                    play_ai_state_clips.in_set(GameSet::Animation),
+                ),
+            );
+    }
+}
+
+// Whether the enemy's threat table points at a target that still exists
+fn has_target(table: Option<&ThreatTable>, targets: &Query<(), With<ThreatTarget>>) -> bool {
+    table
+        .and_then(ThreatTable::target)
+        .is_some_and(|target| targets.contains(target))
+}
+
+// Heads for `destination` at strolling speed, easing off as it gets close.
+// Returns whether it has arrived.
+fn walk_towards(position: Vec3, destination: Vec3, intent: &mut MovementIntent) -> bool {
+    let offset = (destination - position).with_y(0.0);
+    let distance = offset.length();
+    if distance <= ARRIVE_DISTANCE {
+        intent.0 = Vec3::ZERO;
+        return true;
+    }
+    let ease = (distance / SLOW_DOWN_DISTANCE).clamp(0.25, 1.0);
+    intent.0 = offset / distance * IDLE_SPEED * ease;
+    false
+}
+
+fn patrol(
+    time: Res<Time>,
+    mut enemies: Query<(
+        &Transform,
+        &mut Patrol,
+        &mut MovementIntent,
+        Option<&ThreatTable>,
+        Option<&TimeScale>,
+    )>,
+    targets: Query<(), With<ThreatTarget>>,
+) {
+    for (transform, mut patrol, mut intent, table, time_scale) in enemies.iter_mut() {
+        let dt = time.delta_seconds() * TimeScale::of(time_scale);
+        if has_target(table, &targets) || patrol.waypoints.is_empty() {
+            continue;
+        }
+        let origin = *patrol.origin.get_or_insert(transform.translation);
+        if patrol.waiting > 0.0 {
+            patrol.waiting -= dt;
+            intent.0 = Vec3::ZERO;
+            continue;
+        }
+        let next = patrol.next % patrol.waypoints.len();
+        let destination = origin + patrol.waypoints[next];
+        if walk_towards(transform.translation, destination, &mut intent) {
+            patrol.next = (next + 1) % patrol.waypoints.len();
+            patrol.waiting = patrol.wait;
+        }
+    }
+}
+
+fn wander(
+    time: Res<Time>,
+    mut enemies: Query<
+        (
+            &Transform,
+            &mut Wander,
+            &mut MovementIntent,
+            Option<&ThreatTable>,
+            Option<&TimeScale>,
+        ),
+        Without<Patrol>,
+    >,
+    targets: Query<(), With<ThreatTarget>>,
+) {
+    let mut rng = rand::thread_rng();
+    for (transform, mut wander, mut intent, table, time_scale) in enemies.iter_mut() {
+        let dt = time.delta_seconds() * TimeScale::of(time_scale);
+        if has_target(table, &targets) {
+            // Picks a fresh spot from wherever the chase ends
+            wander.destination = None;
+            continue;
+        }
+        let home = *wander.home.get_or_insert(transform.translation);
+        if wander.waiting > 0.0 {
+            wander.waiting -= dt;
+            intent.0 = Vec3::ZERO;
+            continue;
+        }
+        let radius = wander.radius;
+        let destination = *wander.destination.get_or_insert_with(|| {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let distance = radius * rng.gen::<f32>().sqrt();
+            home + Vec3::new(angle.cos(), 0.0, angle.sin()) * distance
+        });
+        if walk_towards(transform.translation, destination, &mut intent) {
+            wander.destination = None;
+            wander.waiting = rng.gen_range(WANDER_PAUSE.0..WANDER_PAUSE.1);
+        }
+    }
+}
+
+fn update_ai_state(
+    mut commands: Commands,
+    enemies: Query<
+        (
+            Entity,
+            &MovementIntent,
+            Option<&ThreatTable>,
+            Has<Patrol>,
+            Has<Wander>,
+            Option<&AiState>,
+        ),
+        Or<(With<ThreatTable>, With<Patrol>, With<Wander>)>,
+    >,
+    targets: Query<(), With<ThreatTarget>>,
+) {
+    for (entity, intent, table, patrols, wanders, current) in enemies.iter() {
+        let state = if has_target(table, &targets) {
+            AiState::Chase
+        } else if intent.0 == Vec3::ZERO {
+            AiState::Idle
+        } else if patrols {
+            AiState::Patrol
+        } else if wanders {
+            AiState::Wander
+        } else {
+            AiState::Idle
+        };
+        if current != Some(&state) {
+            commands.entity(entity).insert(state);
+        }
+    }
+}
+
+// Enemies without a clip for their state keep playing whatever they were
+fn play_ai_state_clips(mut enemies: Query<(&AiState, &mut Animator), Changed<AiState>>) {
+    for (state, mut animator) in enemies.iter_mut() {
+        if animator.current() != Some(state.clip()) {
+            animator.play(state.clip());
+        }
+    }
+}
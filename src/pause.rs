@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::locale::Localized;
+use crate::schedule::GameSet;
+
+pub const PAUSE_KEY: KeyCode = KeyCode::Escape;
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Paused,
+    PhotoMode,
+    // The run has ended, e.g. the arena timer ran out; the world keeps
+    // running behind the results until a restart
+    GameOver,
+}
+
+// Root node of the pause menu, despawned when unpausing
+#[derive(Component)]
+pub struct PauseMenu;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_systems(Update, toggle_pause.in_set(GameSet::Input))
+            .add_systems(OnEnter(GameState::Paused), (pause_time, spawn_pause_menu))
+            .add_systems(OnExit(GameState::Paused), (resume_time, despawn_pause_menu));
+    }
+}
+
+fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(PAUSE_KEY) {
+        next_state.set(match state.get() {
+            GameState::Playing => GameState::Paused,
+            GameState::Paused | GameState::PhotoMode => GameState::Playing,
+            GameState::GameOver => return,
+        });
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            PauseMenu,
+        ))
+        .with_children(|menu| {
+            menu.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.0,
+                        ..default()
+                    },
+                ),
+                Localized::new("pause.title"),
+            ));
+            menu.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                ),
+                Localized::new("pause.resume"),
+            ));
+        });
+}
+
+fn despawn_pause_menu(mut commands: Commands, menus: Query<Entity, With<PauseMenu>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
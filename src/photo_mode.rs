@@ -0,0 +1,250 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::render::view::ColorGrading;
+use bevy::window::PrimaryWindow;
+
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+use crate::MainCamera;
+
+pub const PHOTO_MODE_KEY: KeyCode = KeyCode::KeyP;
+pub const FILTER_KEY: KeyCode = KeyCode::KeyF;
+pub const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+const SCREENSHOT_DIR: &str = "screenshots";
+const MOVE_SPEED: f32 = 3.0;
+const TURN_SPEED: f32 = 0.8;
+const MOUSE_SENSITIVITY: f32 = 0.002;
+// Holding shift slows everything down for fine framing
+const FINE_FACTOR: f32 = 0.2;
+const FOV_RANGE: (f32, f32) = (10.0, 100.0);
+// Degrees per second on the keys, per notch on the wheel
+const FOV_SPEED: f32 = 30.0;
+const FOV_WHEEL_STEP: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhotoFilter {
+    #[default]
+    None,
+    BlackAndWhite,
+    Warm,
+    Cool,
+    Vivid,
+}
+
+impl PhotoFilter {
+    const ALL: [PhotoFilter; 5] = [
+        PhotoFilter::None,
+        PhotoFilter::BlackAndWhite,
+        PhotoFilter::Warm,
+        PhotoFilter::Cool,
+        PhotoFilter::Vivid,
+    ];
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|filter| *filter == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
+    fn apply(self, base: &ColorGrading) -> ColorGrading {
+        let mut grading = base.clone();
+        match self {
+            PhotoFilter::None => {}
+            PhotoFilter::BlackAndWhite => grading.global.post_saturation = 0.0,
+            PhotoFilter::Warm => grading.global.temperature = 0.2,
+            PhotoFilter::Cool => grading.global.temperature = -0.2,
+            PhotoFilter::Vivid => grading.global.post_saturation = 1.5,
+        }
+        grading
+    }
+}
+
+// Camera as it was before photo mode, put back on exit
+#[derive(Resource, Default)]
+struct PhotoModeSaved(Option<(Transform, Projection, ColorGrading)>);
+
+#[derive(Resource, Default)]
+pub struct PhotoMode {
+    pub filter: PhotoFilter,
+}
+
+// Root UI node hidden for the photo, with the visibility it goes back to
+#[derive(Component)]
+struct HiddenForPhoto(Visibility);
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .init_resource::<PhotoModeSaved>()
+            .add_systems(
+                Update,
+                (
+                    toggle_photo_mode,
+                    (
+                        move_photo_camera,
+                        change_photo_filter,
+                        take_photo.after(change_photo_filter),
+                    )
+                        .run_if(in_state(GameState::PhotoMode)),
+                )
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(OnEnter(GameState::PhotoMode), enter_photo_mode)
+            .add_systems(OnExit(GameState::PhotoMode), exit_photo_mode);
+    }
+}
+
+fn toggle_photo_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.just_pressed(PHOTO_MODE_KEY) {
+        return;
+    }
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::PhotoMode),
+        GameState::PhotoMode => next_state.set(GameState::Playing),
+        GameState::Paused | GameState::GameOver => {}
+    }
+}
+
+fn enter_photo_mode(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut saved: ResMut<PhotoModeSaved>,
+    cameras: Query<(&Transform, &Projection, &ColorGrading), With<MainCamera>>,
+    mut roots: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+) {
+    time.pause();
+    photo_mode.filter = PhotoFilter::None;
+    if let Ok((transform, projection, grading)) = cameras.get_single() {
+        saved.0 = Some((*transform, projection.clone(), grading.clone()));
+    }
+    for (entity, mut visibility) in roots.iter_mut() {
+        commands.entity(entity).insert(HiddenForPhoto(*visibility));
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn exit_photo_mode(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut saved: ResMut<PhotoModeSaved>,
+    mut cameras: Query<(&mut Transform, &mut Projection, &mut ColorGrading), With<MainCamera>>,
+    mut hidden: Query<(Entity, &HiddenForPhoto, &mut Visibility)>,
+) {
+    time.unpause();
+    if let (Some((transform, projection, grading)), Ok(camera)) =
+        (saved.0.take(), cameras.get_single_mut())
+    {
+        let (mut camera_transform, mut camera_projection, mut camera_grading) = camera;
+        *camera_transform = transform;
+        *camera_projection = projection;
+        *camera_grading = grading;
+    }
+    for (entity, previous, mut visibility) in hidden.iter_mut() {
+        *visibility = previous.0;
+        commands.entity(entity).remove::<HiddenForPhoto>();
+    }
+}
+
+// WASD moves along the view and Q/E down and up, arrows or dragging with the
+// right mouse button turn, Z/C roll, and the wheel or PageUp/PageDown zoom
+fn move_photo_camera(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    let fine = if keyboard_input.pressed(KeyCode::ShiftLeft) {
+        FINE_FACTOR
+    } else {
+        1.0
+    };
+    let delta = time.delta_seconds() * fine;
+    let axis = |negative: KeyCode, positive: KeyCode| {
+        keyboard_input.pressed(positive) as i32 as f32
+            - keyboard_input.pressed(negative) as i32 as f32
+    };
+
+    let movement = *transform.forward() * axis(KeyCode::KeyS, KeyCode::KeyW)
+        + *transform.right() * axis(KeyCode::KeyA, KeyCode::KeyD)
+        + Vec3::Y * axis(KeyCode::KeyQ, KeyCode::KeyE);
+    transform.translation += movement * MOVE_SPEED * delta;
+
+    let mut yaw = axis(KeyCode::ArrowRight, KeyCode::ArrowLeft) * TURN_SPEED * delta;
+    let mut pitch = axis(KeyCode::ArrowDown, KeyCode::ArrowUp) * TURN_SPEED * delta;
+    let roll = axis(KeyCode::KeyC, KeyCode::KeyZ) * TURN_SPEED * delta;
+    let dragged: Vec2 = motion.read().map(|event| event.delta).sum();
+    if mouse.pressed(MouseButton::Right) {
+        yaw -= dragged.x * MOUSE_SENSITIVITY * fine;
+        pitch -= dragged.y * MOUSE_SENSITIVITY * fine;
+    }
+    // Yaw around the world up so rolled shots still turn level
+    transform.rotate_y(yaw);
+    transform.rotate_local_x(pitch);
+    transform.rotate_local_z(roll);
+
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        let notches: f32 = wheel.read().map(|event| event.y).sum();
+        let fov = perspective.fov.to_degrees()
+            - notches * FOV_WHEEL_STEP * fine
+            - axis(KeyCode::PageDown, KeyCode::PageUp) * FOV_SPEED * delta;
+        perspective.fov = fov.clamp(FOV_RANGE.0, FOV_RANGE.1).to_radians();
+    }
+}
+
+fn change_photo_filter(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    saved: Res<PhotoModeSaved>,
+    mut cameras: Query<&mut ColorGrading, With<MainCamera>>,
+) {
+    if !keyboard_input.just_pressed(FILTER_KEY) {
+        return;
+    }
+    let Some((_, _, base)) = &saved.0 else {
+        return;
+    };
+    photo_mode.filter = photo_mode.filter.next();
+    for mut grading in cameras.iter_mut() {
+        *grading = photo_mode.filter.apply(base);
+    }
+    println!("Photo filter: {:?}", photo_mode.filter);
+}
+
+fn take_photo(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+) {
+    if !keyboard_input.just_pressed(SCREENSHOT_KEY) {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    if let Err(error) = std::fs::create_dir_all(SCREENSHOT_DIR) {
+        warn!("Could not create {}: {}", SCREENSHOT_DIR, error);
+        return;
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis());
+    let path = format!("{}/photo-{}.png", SCREENSHOT_DIR, stamp);
+    match screenshots.save_screenshot_to_disk(window, &path) {
+        Ok(()) => println!("Saved {}", path),
+        Err(error) => warn!("Could not take a screenshot: {}", error),
+    }
+}
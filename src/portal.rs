@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Hitbox, Invulnerable};
+use crate::level::{LevelStreaming, LoadLevel, FADE_TIME};
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillSpawner, WaterSkill};
+use crate::Player;
+
+const SWIRL_SKILL: &str = "skills/portal_swirl.skill.ron";
+// Played where a player leaves and where they arrive
+const BURST_SKILL: &str = "skills/spawn_portal.skill.ron";
+// Seconds to shrink into a portal and grow back out of the other one
+const TELEPORT_TIME: f32 = 0.5;
+// Seconds of invulnerability after arriving, so nothing waiting at the other
+// end gets a free hit
+const ARRIVAL_INVULNERABILITY: f32 = 1.0;
+
+// Where a portal sends whoever steps in
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub enum PortalDestination {
+    // The portal with this name in the same level
+    Portal(String),
+    // Another level, by path
+    Level(String),
+}
+
+// Teleports players who step within `radius`. Its swirl is a skill instance
+// kept alive for as long as the portal exists.
+#[derive(Component, Debug, Clone, Deserialize, Reflect)]
+#[reflect(Component)]
+pub struct Portal {
+    pub name: String,
+    pub destination: PortalDestination,
+    #[serde(default = "default_portal_radius")]
+    pub radius: f32,
+    #[serde(skip)]
+    #[reflect(ignore)]
+    swirl: Option<Entity>,
+}
+
+fn default_portal_radius() -> f32 {
+    0.8
+}
+
+impl Portal {
+    pub fn new(name: impl Into<String>, destination: PortalDestination) -> Self {
+        Self {
+            name: name.into(),
+            destination,
+            radius: default_portal_radius(),
+            swirl: None,
+        }
+    }
+}
+
+// Shrinking into one portal and growing back out of another. Its transform
+// jumps halfway through.
+#[derive(Component)]
+struct Teleporting {
+    to: Vec3,
+    arrival: Entity,
+    scale: Vec3,
+    timer: Timer,
+    arrived: bool,
+}
+
+// Just came out of this portal, so standing in it doesn't send the player
+// straight back. Cleared once they step out.
+#[derive(Component)]
+struct PortalGrace(Entity);
+
+#[derive(Resource)]
+struct PortalSkills {
+    swirl: Handle<SkillDefinition>,
+    burst: Handle<SkillDefinition>,
+}
+
+pub struct PortalPlugin;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Portal>()
+            .add_systems(Startup, load_portal_skills)
+            .add_systems(
+                Update,
+                (
+                    keep_portal_swirls,
+                    enter_portals,
+                    teleport,
+                    clear_portal_grace,
+                )
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+fn load_portal_skills(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PortalSkills {
+        swirl: asset_server.load(SWIRL_SKILL),
+        burst: asset_server.load(BURST_SKILL),
+    });
+}
+
+// Spawns a skill that only shows, its hitbox would hurt enemies standing in it
+fn spawn_effect(
+    spawner: &mut SkillSpawner,
+    definitions: &Assets<SkillDefinition>,
+    handle: &Handle<SkillDefinition>,
+    caster: Entity,
+    position: Vec3,
+) -> Option<Entity> {
+    let definition = definitions.get(handle)?;
+    let skill = match spawner.spawn(handle, definition, caster, position, None) {
+        Ok(skill) => skill,
+        Err(error) => {
+            spawner.report(&error);
+            return None;
+        }
+    };
+    spawner.commands.entity(skill).remove::<Hitbox>();
+    Some(skill)
+}
+
+// Spawned again whenever it's gone, e.g. after its lifetime ran out
+fn keep_portal_swirls(
+    skills: Res<PortalSkills>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut portals: Query<(Entity, &GlobalTransform, &mut Portal)>,
+    skill_instances: Query<(), With<WaterSkill>>,
+    mut spawner: SkillSpawner,
+) {
+    for (entity, transform, mut portal) in portals.iter_mut() {
+        if portal
+            .swirl
+            .is_some_and(|swirl| skill_instances.contains(swirl))
+        {
+            continue;
+        }
+        portal.swirl = spawn_effect(
+            &mut spawner,
+            &definitions,
+            &skills.swirl,
+            entity,
+            transform.translation(),
+        );
+    }
+}
+
+fn enter_portals(
+    mut commands: Commands,
+    skills: Res<PortalSkills>,
+    definitions: Res<Assets<SkillDefinition>>,
+    streaming: Res<LevelStreaming>,
+    portals: Query<(Entity, &GlobalTransform, &Portal)>,
+    players: Query<
+        (Entity, &GlobalTransform, &Transform, Option<&PortalGrace>),
+        (With<Player>, Without<Teleporting>),
+    >,
+    mut loads: EventWriter<LoadLevel>,
+    mut spawner: SkillSpawner,
+) {
+    if streaming.is_transitioning() {
+        return;
+    }
+    for (player, player_global, player_transform, grace) in players.iter() {
+        let position = player_global.translation();
+        let entered = portals.iter().find(|(entity, transform, portal)| {
+            grace.is_none_or(|grace| grace.0 != *entity)
+                && position.xz().distance(transform.translation().xz()) <= portal.radius
+        });
+        let Some((entity, transform, portal)) = entered else {
+            continue;
+        };
+        match &portal.destination {
+            PortalDestination::Portal(name) => {
+                let target = portals.iter().find(|(other, _, other_portal)| {
+                    *other != entity && other_portal.name == *name
+                });
+                let Some((arrival, target, _)) = target else {
+                    warn!(
+                        "Portal {:?} leads to missing portal {:?}",
+                        portal.name, name
+                    );
+                    commands.entity(player).insert(PortalGrace(entity));
+                    continue;
+                };
+                commands.entity(player).insert((
+                    Teleporting {
+                        to: target.translation() + Vec3::Y * 0.5,
+                        arrival,
+                        scale: player_transform.scale,
+                        timer: Timer::from_seconds(TELEPORT_TIME, TimerMode::Once),
+                        arrived: false,
+                    },
+                    Invulnerable::new(TELEPORT_TIME + ARRIVAL_INVULNERABILITY),
+                ));
+                spawn_effect(
+                    &mut spawner,
+                    &definitions,
+                    &skills.burst,
+                    entity,
+                    target.translation(),
+                );
+            }
+            PortalDestination::Level(path) => {
+                loads.send(LoadLevel { path: path.clone() });
+                // Covers the fade out and back in
+                commands
+                    .entity(player)
+                    .insert(Invulnerable::new(FADE_TIME * 2.0 + ARRIVAL_INVULNERABILITY));
+            }
+        }
+        spawn_effect(
+            &mut spawner,
+            &definitions,
+            &skills.burst,
+            entity,
+            transform.translation(),
+        );
+    }
+}
+
+fn teleport(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &mut Transform, &mut Teleporting)>,
+) {
+    for (entity, mut transform, mut teleporting) in players.iter_mut() {
+        teleporting.timer.tick(time.delta());
+        let t = teleporting.timer.fraction();
+        if t >= 0.5 && !teleporting.arrived {
+            teleporting.arrived = true;
+            transform.translation = teleporting.to;
+            commands
+                .entity(entity)
+                .insert(PortalGrace(teleporting.arrival));
+        }
+        // Down to nothing and back, never quite zero so the transform stays valid
+        let size = ((t - 0.5).abs() * 2.0).max(0.01);
+        transform.scale = teleporting.scale * size;
+        if teleporting.timer.finished() {
+            transform.scale = teleporting.scale;
+            commands.entity(entity).remove::<Teleporting>();
+        }
+    }
+}
+
+fn clear_portal_grace(
+    mut commands: Commands,
+    players: Query<(Entity, &GlobalTransform, &PortalGrace), Without<Teleporting>>,
+    portals: Query<(&GlobalTransform, &Portal)>,
+) {
+    for (player, transform, grace) in players.iter() {
+        let inside = portals
+            .get(grace.0)
+            .is_ok_and(|(portal_transform, portal)| {
+                transform
+                    .translation()
+                    .xz()
+                    .distance(portal_transform.translation().xz())
+                    <= portal.radius
+            });
+        if !inside {
+            commands.entity(player).remove::<PortalGrace>();
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::render_preview::{PreviewSubject, RenderPreview};
+use crate::schedule::GameSet;
+use crate::Player;
+
+const PORTRAIT_SIZE: f32 = 72.0;
+
+#[derive(Component)]
+struct Portrait;
+
+pub struct PortraitPlugin;
+
+impl Plugin for PortraitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_portrait)
+            .add_systems(Update, follow_player.in_set(GameSet::Presentation));
+    }
+}
+
+fn spawn_portrait(mut commands: Commands) {
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                width: Val::Px(PORTRAIT_SIZE),
+                height: Val::Px(PORTRAIT_SIZE),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            ..default()
+        },
+        BorderColor(Color::srgba(0.8, 0.9, 1.0, 0.5)),
+        RenderPreview::entity(Entity::PLACEHOLDER, UVec2::splat(PORTRAIT_SIZE as u32 * 2))
+            .with_clear_color(Color::srgba(0.1, 0.12, 0.15, 0.75)),
+        Portrait,
+        SafeAreaAnchor::default(),
+    ));
+}
+
+// Picks the player up again after respawning or a level change
+fn follow_player(
+    players: Query<Entity, With<Player>>,
+    mut portraits: Query<&mut RenderPreview, With<Portrait>>,
+) {
+    let Some(player) = players.iter().next() else {
+        return;
+    };
+    for mut portrait in portraits.iter_mut() {
+        if let PreviewSubject::Entity { entity, .. } = &mut portrait.subject {
+            if *entity != player {
+                *entity = player;
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// The size checks generated by `ShaderType` are never called
+#![allow(dead_code)]
+
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::extract_component::{
+    ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+    UniformComponentPlugin,
+};
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::ViewTarget;
+use bevy::render::RenderApp;
+
+use crate::cinematic::PlayCameraSequence;
+use crate::combat::Health;
+use crate::schedule::GameSet;
+use crate::settings::GraphicsSettings;
+use crate::{MainCamera, Player};
+
+const SHADER_PATH: &str = "shaders/post_process.wgsl";
+
+// Tuning for the gameplay-driven screen effects. Whether they run at all is
+// the `post_processing` graphics setting.
+#[derive(Resource, Debug, Clone)]
+pub struct PostProcessSettings {
+    // Health fraction below which the vignette and aberration fade in
+    pub low_health_threshold: f32,
+    pub vignette_strength: f32,
+    pub aberration_strength: f32,
+    // Played when an ultimate's camera sequence starts
+    pub radial_blur_strength: f32,
+    pub radial_blur_duration: f32,
+}
+
+impl Default for PostProcessSettings {
+    fn default() -> Self {
+        Self {
+            low_health_threshold: 0.35,
+            vignette_strength: 0.8,
+            aberration_strength: 0.006,
+            radial_blur_strength: 0.06,
+            radial_blur_duration: 0.4,
+        }
+    }
+}
+
+// Per-camera effect amounts, uploaded as the shader uniform
+#[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+pub struct ScreenEffects {
+    pub vignette: f32,
+    pub aberration: f32,
+    pub radial_blur: f32,
+    _padding: f32,
+}
+
+#[derive(Resource, Default)]
+struct RadialBlur(Option<Timer>);
+
+pub struct PostProcessPlugin;
+
+impl Plugin for PostProcessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<ScreenEffects>::default(),
+            UniformComponentPlugin::<ScreenEffects>::default(),
+        ))
+        .init_resource::<PostProcessSettings>()
+        .init_resource::<RadialBlur>()
+        .add_systems(Update, update_screen_effects.in_set(GameSet::Presentation));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<ScreenEffectsNode>>(Core3d, ScreenEffectsLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    ScreenEffectsLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<ScreenEffectsPipeline>();
+    }
+}
+
+fn update_screen_effects(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    graphics: Res<GraphicsSettings>,
+    settings: Res<PostProcessSettings>,
+    mut blur: ResMut<RadialBlur>,
+    mut sequences: EventReader<PlayCameraSequence>,
+    players: Query<&Health, With<Player>>,
+    mut cameras: Query<(Entity, Option<&mut ScreenEffects>), With<MainCamera>>,
+) {
+    if sequences.read().count() > 0 {
+        blur.0 = Some(Timer::from_seconds(
+            settings.radial_blur_duration,
+            TimerMode::Once,
+        ));
+    }
+    let mut radial_blur = 0.0;
+    if let Some(timer) = &mut blur.0 {
+        // Real time so slow-motion cinematics don't stretch the blur
+        timer.tick(time.delta());
+        radial_blur = settings.radial_blur_strength * (1.0 - timer.fraction());
+        if timer.finished() {
+            blur.0 = None;
+        }
+    }
+
+    // The shared screen shows whichever player is closest to dying
+    let danger = players
+        .iter()
+        .map(|health| {
+            let fraction = health.current / health.max.max(1.0);
+            (1.0 - fraction / settings.low_health_threshold).clamp(0.0, 1.0)
+        })
+        .fold(0.0, f32::max);
+    let target = ScreenEffects {
+        vignette: settings.vignette_strength * danger,
+        aberration: settings.aberration_strength * danger,
+        radial_blur,
+        _padding: 0.0,
+    };
+
+    for (camera, effects) in cameras.iter_mut() {
+        // Without the component the render node skips the camera entirely
+        match (graphics.post_processing, effects) {
+            (true, Some(mut effects)) => *effects = target,
+            (true, None) => {
+                commands.entity(camera).insert(target);
+            }
+            (false, Some(_)) => {
+                commands.entity(camera).remove::<ScreenEffects>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ScreenEffectsLabel;
+
+#[derive(Default)]
+struct ScreenEffectsNode;
+
+impl ViewNode for ScreenEffectsNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ScreenEffects,
+        &'static DynamicUniformIndex<ScreenEffects>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, _effects, effects_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let effects_pipeline = world.resource::<ScreenEffectsPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(effects_pipeline.pipeline_id)
+        else {
+            return Ok(());
+        };
+        let uniforms = world.resource::<ComponentUniforms<ScreenEffects>>();
+        let Some(uniforms_binding) = uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "screen_effects_bind_group",
+            &effects_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &effects_pipeline.sampler,
+                uniforms_binding.clone(),
+            )),
+        );
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("screen_effects_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[effects_index.index()]);
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct ScreenEffectsPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for ScreenEffectsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "screen_effects_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<ScreenEffects>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset(SHADER_PATH);
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("screen_effects_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: TextureFormat::bevy_default(),
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                });
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
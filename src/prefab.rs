@@ -0,0 +1,456 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::system::{EntityCommands, RunSystemOnce, SystemParam};
+use bevy::ecs::world::EntityWorldMut;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::boss::{Boss, BossDefinition};
+use crate::combat::{Health, Hurtbox, Mana};
+use crate::console::RegisterConsoleCommand;
+use crate::controller::{CharacterController, MovementIntent};
+use crate::corpse::Remains;
+use crate::destructible::Destructible;
+use crate::equipment::Equipment;
+use crate::ground::Grounded;
+use crate::interaction::{Chest, Door, Interactable, Lever};
+use crate::loot::Pickup;
+use crate::on_death::{OnDeathEffect, OnDeathEffects};
+use crate::patrol::{Patrol, Wander};
+use crate::progression::Experience;
+use crate::respawn::Checkpoint;
+use crate::settings::{DifficultyMultipliers, GameplaySettings};
+use crate::skills::cooldown::SkillCooldowns;
+use crate::skills::input_buffer::SkillInputBuffer;
+use crate::skills::rank::{SkillPoints, SkillRanks};
+use crate::socket::{spawn_sockets, Socket, Sockets};
+use crate::stats::{
+    ModifierKind, ModifierSource, Stat, StatModifier, StatModifiers, Stats, StatsBundle,
+};
+use crate::status::StatusEffects;
+use crate::threat::{ThreatTable, ThreatTarget};
+use crate::transition::{DeathAnimation, EnterEffect, Entering};
+use crate::{players_center, Enemy, Player};
+
+// Prefabs available to `PrefabSpawner::spawn`, by name
+const PREFABS: [&str; 14] = [
+    "player",
+    "enemy",
+    "bomber",
+    "splitter",
+    "splitling",
+    "boss",
+    "door",
+    "lever",
+    "chest",
+    "checkpoint",
+    "crate",
+    "barrel",
+    "health_potion",
+    "mana_potion",
+];
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum PrefabMesh {
+    Cuboid(f32, f32, f32),
+    Sphere(f32),
+    Plane(f32, f32),
+    // Radius and height
+    Cylinder(f32, f32),
+}
+
+impl PrefabMesh {
+    fn mesh(self) -> Mesh {
+        match self {
+            PrefabMesh::Cuboid(x, y, z) => Mesh::from(Cuboid::new(x, y, z)),
+            PrefabMesh::Sphere(radius) => Mesh::from(Sphere::new(radius)),
+            PrefabMesh::Plane(x, z) => Mesh::from(Plane3d::new(Vec3::Y, Vec2::new(x, z) * 0.5)),
+            PrefabMesh::Cylinder(radius, height) => Mesh::from(Cylinder::new(radius, height)),
+        }
+    }
+}
+
+// Gameplay components a prefab can list, with their data-driven parameters
+#[derive(Debug, Clone, Deserialize)]
+pub enum PrefabComponent {
+    Player,
+    Enemy,
+    Health(f32),
+    Mana {
+        max: f32,
+        regen: f32,
+    },
+    Hurtbox(f32),
+    // Height above the ground to keep the entity's origin at
+    Grounded(f32),
+    Stats(Stats),
+    StatusEffects,
+    Experience,
+    // Skill points, ranks, cooldowns and input buffering, everything needed to cast
+    SkillUser,
+    Equipment,
+    CharacterController,
+    Interactable {
+        radius: f32,
+    },
+    Door {
+        open: bool,
+    },
+    Chest,
+    Lever,
+    // Where the player respawns once they have walked within this radius
+    Checkpoint(f32),
+    OnDeath(Vec<OnDeathEffect>),
+    Boss(BossDefinition),
+    // Picks targets by threat, noticing them within this range
+    Threat(f32),
+    // Can be picked as a target by enemies with `Threat`
+    ThreatTarget,
+    // Route walked while idle, as offsets from where the entity starts
+    Patrol {
+        waypoints: Vec<(f32, f32, f32)>,
+        #[serde(default)]
+        wait: f32,
+    },
+    // Strolls around within this radius of where it starts while idle
+    Wander(f32),
+    // Played on arrival, before it can be hit or move
+    Enter(EnterEffect),
+    // Played once killed, before it is despawned
+    DeathAnimation(DeathAnimation),
+    // Left behind once the death animation has played
+    Remains(Remains),
+    // Attachment points by name, as offsets from the entity's origin
+    Sockets(HashMap<String, (f32, f32, f32)>),
+    // Breaks under skill damage, see `Destructible`
+    Destructible(Destructible),
+    // Collected by walking over it
+    Pickup(Pickup),
+}
+
+// Components already on the entity win, so callers can override prefab
+// defaults by inserting them right after spawning
+fn insert_missing<T: Component>(entity: &mut EntityWorldMut, component: T) {
+    if !entity.contains::<T>() {
+        entity.insert(component);
+    }
+}
+
+impl PrefabComponent {
+    fn apply(&self, entity: &mut EntityWorldMut) {
+        match self {
+            PrefabComponent::Player => insert_missing(entity, Player),
+            PrefabComponent::Enemy => insert_missing(entity, Enemy),
+            PrefabComponent::Health(max) => insert_missing(entity, Health::new(*max)),
+            PrefabComponent::Mana { max, regen } => insert_missing(entity, Mana::new(*max, *regen)),
+            PrefabComponent::Hurtbox(radius) => insert_missing(entity, Hurtbox { radius: *radius }),
+            PrefabComponent::Grounded(height) => {
+                insert_missing(entity, Grounded { height: *height })
+            }
+            PrefabComponent::Stats(base) => {
+                if !entity.contains::<Stats>() {
+                    entity.insert(StatsBundle::new(*base));
+                }
+            }
+            PrefabComponent::StatusEffects => insert_missing(entity, StatusEffects::default()),
+            PrefabComponent::Experience => insert_missing(entity, Experience::default()),
+            PrefabComponent::SkillUser => {
+                insert_missing(entity, SkillPoints::default());
+                insert_missing(entity, SkillRanks::default());
+                insert_missing(entity, SkillCooldowns::default());
+                insert_missing(entity, SkillInputBuffer::default());
+            }
+            PrefabComponent::Equipment => insert_missing(entity, Equipment::default()),
+            PrefabComponent::CharacterController => {
+                insert_missing(entity, CharacterController::default());
+                insert_missing(entity, MovementIntent::default());
+            }
+            PrefabComponent::Interactable { radius } => insert_missing(
+                entity,
+                Interactable {
+                    radius: *radius,
+                    ..default()
+                },
+            ),
+            PrefabComponent::Door { open } => insert_missing(entity, Door::new(*open)),
+            PrefabComponent::Chest => insert_missing(entity, Chest { item: None }),
+            PrefabComponent::Lever => insert_missing(
+                entity,
+                Lever {
+                    on: false,
+                    targets: Vec::new(),
+                },
+            ),
+            PrefabComponent::Checkpoint(radius) => {
+                insert_missing(entity, Checkpoint { radius: *radius })
+            }
+            PrefabComponent::OnDeath(effects) => {
+                insert_missing(entity, OnDeathEffects(effects.clone()))
+            }
+            PrefabComponent::Boss(definition) => {
+                insert_missing(entity, Boss::new(definition.clone()))
+            }
+            PrefabComponent::Threat(aggro_range) => {
+                insert_missing(entity, ThreatTable::new(*aggro_range))
+            }
+            PrefabComponent::ThreatTarget => insert_missing(entity, ThreatTarget),
+            PrefabComponent::Patrol { waypoints, wait } => {
+                let waypoints = waypoints
+                    .iter()
+                    .map(|(x, y, z)| Vec3::new(*x, *y, *z))
+                    .collect();
+                insert_missing(entity, Patrol::new(waypoints, *wait))
+            }
+            PrefabComponent::Wander(radius) => insert_missing(entity, Wander::new(*radius)),
+            PrefabComponent::Enter(effect) => insert_missing(entity, Entering::new(effect.clone())),
+            PrefabComponent::DeathAnimation(animation) => insert_missing(entity, *animation),
+            PrefabComponent::Remains(remains) => insert_missing(entity, *remains),
+            PrefabComponent::Sockets(offsets) => {
+                if !entity.contains::<Sockets>() {
+                    let offsets = offsets
+                        .iter()
+                        .map(|(name, (x, y, z))| (name.clone(), Vec3::new(*x, *y, *z)))
+                        .collect();
+                    spawn_sockets(entity, &offsets);
+                }
+            }
+            PrefabComponent::Destructible(destructible) => {
+                insert_missing(entity, destructible.clone())
+            }
+            PrefabComponent::Pickup(pickup) => insert_missing(entity, *pickup),
+        }
+    }
+}
+
+// Enemies are scaled by the difficulty in effect when they spawn; changing it
+// later only affects new ones
+fn apply_difficulty(entity: &mut EntityWorldMut, difficulty: &DifficultyMultipliers) {
+    if let Some(mut health) = entity.get_mut::<Health>() {
+        health.max *= difficulty.enemy_health;
+        health.current *= difficulty.enemy_health;
+    }
+    if let Some(mut modifiers) = entity.get_mut::<StatModifiers>() {
+        modifiers.remove_source(ModifierSource::Difficulty);
+        modifiers.add(StatModifier {
+            stat: Stat::Speed,
+            kind: ModifierKind::Percent,
+            value: difficulty.enemy_speed - 1.0,
+            source: ModifierSource::Difficulty,
+        });
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct Prefab {
+    pub mesh: Option<PrefabMesh>,
+    #[serde(default = "default_color")]
+    pub color: (f32, f32, f32),
+    #[serde(default)]
+    pub components: Vec<PrefabComponent>,
+}
+
+fn default_color() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+#[derive(Default)]
+pub struct PrefabLoader;
+
+#[derive(Debug, Error)]
+pub enum PrefabSpawnError {
+    #[error("unknown prefab {0:?}")]
+    UnknownPrefab(String),
+}
+
+#[derive(Debug, Error)]
+pub enum PrefabLoaderError {
+    #[error("could not read prefab: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse prefab: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for PrefabLoader {
+    type Asset = Prefab;
+    type Settings = ();
+    type Error = PrefabLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Prefab, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut prefab: Prefab = ron::de::from_bytes(&bytes)?;
+        for component in prefab.components.iter_mut() {
+            match component {
+                PrefabComponent::OnDeath(effects) => {
+                    for effect in effects.iter_mut() {
+                        if let OnDeathEffect::SpawnSkill { skill, handle } = effect {
+                            *handle = load_context.load(skill.clone());
+                        }
+                    }
+                }
+                PrefabComponent::Boss(definition) => {
+                    for attack in definition
+                        .phases
+                        .iter_mut()
+                        .flat_map(|p| p.attacks.iter_mut())
+                    {
+                        attack.handle = load_context.load(attack.skill.clone());
+                    }
+                }
+                PrefabComponent::Enter(EnterEffect::Portal { skill, handle, .. }) => {
+                    *handle = load_context.load(skill.clone());
+                }
+                PrefabComponent::Destructible(destructible) => {
+                    if let Some(effect) = &destructible.effect {
+                        destructible.effect_handle = load_context.load(effect.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(prefab)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["prefab.ron"]
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Prefabs(pub HashMap<String, Handle<Prefab>>);
+
+// Waiting for its prefab asset to finish loading
+#[derive(Component)]
+pub struct PendingPrefab(pub Handle<Prefab>);
+
+// Spawns prefabs by name. The entity exists immediately so it can be
+// referenced and customized; the prefab's components are added once the
+// asset has loaded. Nothing is spawned for a name that isn't a prefab.
+#[derive(SystemParam)]
+pub struct PrefabSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub prefabs: Res<'w, Prefabs>,
+}
+
+impl<'w, 's> PrefabSpawner<'w, 's> {
+    pub fn spawn(
+        &mut self,
+        name: &str,
+        transform: Transform,
+    ) -> Result<EntityCommands<'_>, PrefabSpawnError> {
+        let handle = self
+            .prefabs
+            .0
+            .get(name)
+            .ok_or_else(|| PrefabSpawnError::UnknownPrefab(name.to_string()))?;
+        Ok(self.commands.spawn((
+            SpatialBundle::from_transform(transform),
+            PendingPrefab(handle.clone()),
+        )))
+    }
+}
+
+pub struct PrefabPlugin;
+
+impl Plugin for PrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Socket>()
+            .register_type::<Sockets>()
+            .init_asset::<Prefab>()
+            .init_asset_loader::<PrefabLoader>()
+            .init_resource::<Prefabs>()
+            .register_console_command("spawn", "spawn <prefab> [count]", spawn_command)
+            .add_systems(PreStartup, load_prefabs)
+            .add_systems(PreUpdate, resolve_pending_prefabs);
+    }
+}
+
+fn spawn_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let name = args.first().ok_or("expected a prefab name")?.to_string();
+    let count = match args.get(1) {
+        Some(count) => count.parse().map_err(|_| "count must be a number")?,
+        None => 1,
+    };
+    if !world.resource::<Prefabs>().0.contains_key(&name) {
+        return Err(format!("unknown prefab {:?}", name));
+    }
+    world.run_system_once_with((name.clone(), count), spawn_around_player);
+    Ok(format!("Spawned {} {}", count, name))
+}
+
+// Spreads the instances on a circle around the players' midpoint
+fn spawn_around_player(
+    In((name, count)): In<(String, u32)>,
+    mut prefabs: PrefabSpawner,
+    players: Query<&Transform, With<Player>>,
+) {
+    let origin =
+        players_center(players.iter().map(|transform| transform.translation)).unwrap_or_default();
+    for i in 0..count {
+        let angle = std::f32::consts::TAU * i as f32 / count as f32;
+        let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * 4.0;
+        if let Err(error) = prefabs.spawn(&name, Transform::from_translation(origin + offset)) {
+            warn!("{}", error);
+        }
+    }
+}
+
+pub(crate) fn load_prefabs(mut prefabs: ResMut<Prefabs>, asset_server: Res<AssetServer>) {
+    for name in PREFABS {
+        let handle = asset_server.load(format!("prefabs/{}.prefab.ron", name));
+        prefabs.0.insert(name.to_string(), handle);
+    }
+}
+
+fn resolve_pending_prefabs(
+    mut commands: Commands,
+    prefabs: Res<Assets<Prefab>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    // Shared per prefab so instances batch together
+    mut render_handles: Local<HashMap<AssetId<Prefab>, (Handle<Mesh>, Handle<StandardMaterial>)>>,
+    pending: Query<(Entity, &PendingPrefab)>,
+) {
+    for (entity, PendingPrefab(handle)) in pending.iter() {
+        let Some(prefab) = prefabs.get(handle) else {
+            continue;
+        };
+        let render = prefab.mesh.map(|mesh| {
+            render_handles
+                .entry(handle.id())
+                .or_insert_with(|| {
+                    let (r, g, b) = prefab.color;
+                    (meshes.add(mesh.mesh()), materials.add(Color::srgb(r, g, b)))
+                })
+                .clone()
+        });
+        let components = prefab.components.clone();
+        commands.entity(entity).remove::<PendingPrefab>();
+        commands.add(move |world: &mut World| {
+            let difficulty = world
+                .get_resource::<GameplaySettings>()
+                .map(GameplaySettings::multipliers)
+                .unwrap_or_default();
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            if let Some((mesh, material)) = render {
+                insert_missing(&mut entity, mesh);
+                insert_missing(&mut entity, material);
+            }
+            for component in components.iter() {
+                component.apply(&mut entity);
+            }
+            if entity.contains::<Enemy>() {
+                apply_difficulty(&mut entity, &difficulty);
+            }
+        });
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+
+use bevy::ecs::system::RunSystemOnce;
+
+use crate::combat::EnemyKilled;
+use crate::console::RegisterConsoleCommand;
+use crate::locale::Locale;
+use crate::schedule::GameSet;
+use crate::skills::rank::SkillPoints;
+use crate::stats::{ModifierKind, ModifierSource, Stat, StatModifier, StatModifiers};
+use crate::toast::ToastEvent;
+use crate::Player;
+
+const XP_PER_KILL: u32 = 40;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Experience {
+    pub level: u32,
+    pub xp: u32,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self { level: 1, xp: 0 }
+    }
+}
+
+impl Experience {
+    pub fn xp_to_next_level(&self) -> u32 {
+        self.level * 100
+    }
+}
+
+#[derive(Event)]
+pub struct LevelUpEvent {
+    pub entity: Entity,
+    pub level: u32,
+}
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Experience>()
+            .add_event::<LevelUpEvent>()
+            .register_console_command("give", "give xp <amount>", give_command)
+            .add_systems(
+                Update,
+                (award_kill_experience, apply_level_modifiers)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+fn give_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    match args {
+        ["xp", amount] => {
+            let amount = amount.parse().map_err(|_| "amount must be a number")?;
+            world.run_system_once_with(amount, grant_player_experience);
+            Ok(format!("Gave {} xp", amount))
+        }
+        _ => Err("expected give xp <amount>".to_string()),
+    }
+}
+
+fn award_kill_experience(
+    mut kills: EventReader<EnemyKilled>,
+    players: Query<(Entity, &mut Experience, Option<&mut SkillPoints>), With<Player>>,
+    level_ups: EventWriter<LevelUpEvent>,
+    locale: Res<Locale>,
+    toasts: EventWriter<ToastEvent>,
+) {
+    let amount = kills.read().count() as u32 * XP_PER_KILL;
+    if amount > 0 {
+        grant_player_experience(In(amount), players, level_ups, locale, toasts);
+    }
+}
+
+fn grant_player_experience(
+    In(amount): In<u32>,
+    mut players: Query<(Entity, &mut Experience, Option<&mut SkillPoints>), With<Player>>,
+    mut level_ups: EventWriter<LevelUpEvent>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for (entity, mut experience, mut points) in players.iter_mut() {
+        experience.xp += amount;
+        while experience.xp >= experience.xp_to_next_level() {
+            experience.xp -= experience.xp_to_next_level();
+            experience.level += 1;
+            if let Some(points) = points.as_mut() {
+                points.0 += 1;
+            }
+            level_ups.send(LevelUpEvent {
+                entity,
+                level: experience.level,
+            });
+            println!("Level up! Now level {}", experience.level);
+            toasts.send(ToastEvent::new(
+                locale.format("toast.level_up", &[("level", &experience.level)]),
+            ));
+        }
+    }
+}
+
+// Each level past the first gives +5 attack, which is already in percent
+// points, and +2 defense
+fn apply_level_modifiers(
+    mut level_ups: EventReader<LevelUpEvent>,
+    mut query: Query<(&Experience, &mut StatModifiers)>,
+) {
+    for level_up in level_ups.read() {
+        let Ok((experience, mut modifiers)) = query.get_mut(level_up.entity) else {
+            continue;
+        };
+        let levels = experience.level.saturating_sub(1) as f32;
+        modifiers.remove_source(ModifierSource::Level);
+        modifiers.add(StatModifier {
+            stat: Stat::Attack,
+            kind: ModifierKind::Flat,
+            value: 5.0 * levels,
+            source: ModifierSource::Level,
+        });
+        modifiers.add(StatModifier {
+            stat: Stat::Defense,
+            kind: ModifierKind::Flat,
+            value: 2.0 * levels,
+            source: ModifierSource::Level,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Stats;
+
+    #[test]
+    fn levelling_up_raises_attack_from_zero() {
+        let mut world = World::new();
+        world.init_resource::<Events<LevelUpEvent>>();
+        let player = world
+            .spawn((Experience { level: 3, xp: 0 }, StatModifiers::default()))
+            .id();
+        world.send_event(LevelUpEvent {
+            entity: player,
+            level: 3,
+        });
+        world.run_system_once(apply_level_modifiers);
+
+        let base = Stats::default();
+        assert_eq!(base.attack, 0.0);
+        let stats = world.get::<StatModifiers>(player).unwrap().apply(&base);
+        assert_eq!(stats.attack, 10.0);
+        assert_eq!(stats.defense, 4.0);
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{
+    Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::view::RenderLayers;
+
+use crate::schedule::GameSet;
+use crate::MainCamera;
+
+// Stages are laid out from here, far from any level, each on its own render
+// layer so only its camera sees it
+const STAGE_ORIGIN: Vec3 = Vec3::new(0.0, -500.0, 0.0);
+const STAGE_SPACING: f32 = 50.0;
+const FIRST_PREVIEW_LAYER: usize = 8;
+
+// What a preview's camera looks at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewSubject {
+    // Whatever is spawned under the preview's `PreviewStage`, e.g. a skill in
+    // the spellbook, seen from `camera` relative to the stage
+    Stage {
+        camera: Vec3,
+    },
+    // Something in the world, e.g. the player for a portrait. Framed from the
+    // main camera's side so billboards face the preview too.
+    Entity {
+        entity: Entity,
+        distance: f32,
+        height: f32,
+    },
+}
+
+// A UI image showing what a small camera of its own renders. Spawn it on an
+// `ImageBundle`; once set up the image is the camera's render target and a
+// `PreviewStage` is added for stage content. The camera only renders while
+// the image is visible, and goes away with it.
+#[derive(Component, Debug, Clone)]
+pub struct RenderPreview {
+    pub subject: PreviewSubject,
+    // Render target size in pixels
+    pub resolution: UVec2,
+    pub clear_color: Color,
+}
+
+impl RenderPreview {
+    pub fn stage(resolution: UVec2) -> Self {
+        Self {
+            subject: PreviewSubject::Stage {
+                camera: Vec3::new(0.0, 0.0, 4.0),
+            },
+            resolution,
+            clear_color: Color::NONE,
+        }
+    }
+
+    pub fn entity(entity: Entity, resolution: UVec2) -> Self {
+        Self {
+            subject: PreviewSubject::Entity {
+                entity,
+                distance: 3.0,
+                height: 0.5,
+            },
+            resolution,
+            clear_color: Color::NONE,
+        }
+    }
+
+    pub fn with_clear_color(mut self, color: Color) -> Self {
+        self.clear_color = color;
+        self
+    }
+}
+
+// Where to spawn what a `Stage` preview shows. Anything under it is moved
+// onto the preview's render layer.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PreviewStage(pub Entity);
+
+#[derive(Component)]
+struct PreviewCamera {
+    widget: Entity,
+    stage: Entity,
+}
+
+#[derive(Component)]
+struct StageLayer(RenderLayers);
+
+pub struct RenderPreviewPlugin;
+
+impl Plugin for RenderPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                set_up_previews,
+                layer_stage_content,
+                aim_preview_cameras,
+                clear_orphaned_previews,
+            )
+                .chain()
+                .in_set(GameSet::Presentation),
+        );
+    }
+}
+
+fn render_target(resolution: UVec2) -> Image {
+    let size = Extent3d {
+        width: resolution.x.max(1),
+        height: resolution.y.max(1),
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("render_preview"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    image
+}
+
+fn set_up_previews(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut count: Local<usize>,
+    widgets: Query<(Entity, &RenderPreview), Without<PreviewStage>>,
+) {
+    for (widget, preview) in widgets.iter() {
+        let index = *count;
+        *count += 1;
+        let layer = RenderLayers::layer(FIRST_PREVIEW_LAYER + index);
+        let target = images.add(render_target(preview.resolution));
+
+        let origin = STAGE_ORIGIN + Vec3::X * STAGE_SPACING * index as f32;
+        let stage = commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(origin)),
+                StageLayer(layer.clone()),
+                layer.clone(),
+            ))
+            .with_children(|stage| {
+                // Something to light lit content with, as the level's lights
+                // are far away
+                stage.spawn((
+                    PointLightBundle {
+                        point_light: PointLight {
+                            intensity: 20000.0,
+                            range: 20.0,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(2.0, 3.0, 4.0),
+                        ..default()
+                    },
+                    layer.clone(),
+                ));
+            })
+            .id();
+
+        let (transform, camera_layer) = match preview.subject {
+            PreviewSubject::Stage { camera } => (
+                Transform::from_translation(origin + camera).looking_at(origin, Vec3::Y),
+                layer,
+            ),
+            // Sees the world like the main camera does
+            PreviewSubject::Entity { .. } => (Transform::default(), RenderLayers::default()),
+        };
+        commands.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    // Drawn before the main camera samples it
+                    order: -1,
+                    target: RenderTarget::Image(target.clone()),
+                    clear_color: ClearColorConfig::Custom(preview.clear_color),
+                    is_active: false,
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            camera_layer,
+            PreviewCamera { widget, stage },
+        ));
+        commands
+            .entity(widget)
+            .insert((UiImage::new(target), PreviewStage(stage)));
+    }
+}
+
+// Children aren't given their parent's layers, so stage content is moved onto
+// the stage's layer here
+fn layer_stage_content(
+    mut commands: Commands,
+    stages: Query<(Entity, &StageLayer)>,
+    children: Query<&Children>,
+    layers: Query<Option<&RenderLayers>>,
+) {
+    for (stage, layer) in stages.iter() {
+        for descendant in children.iter_descendants(stage) {
+            if layers.get(descendant).ok().flatten() != Some(&layer.0) {
+                commands.entity(descendant).insert(layer.0.clone());
+            }
+        }
+    }
+}
+
+fn aim_preview_cameras(
+    widgets: Query<(&RenderPreview, &ViewVisibility)>,
+    targets: Query<&GlobalTransform>,
+    main_cameras: Query<&GlobalTransform, With<MainCamera>>,
+    mut cameras: Query<(&PreviewCamera, &mut Camera, &mut Transform)>,
+) {
+    for (preview_camera, mut camera, mut transform) in cameras.iter_mut() {
+        let Ok((preview, visibility)) = widgets.get(preview_camera.widget) else {
+            continue;
+        };
+        let target = match preview.subject {
+            PreviewSubject::Entity { entity, .. } => targets.get(entity).ok(),
+            PreviewSubject::Stage { .. } => None,
+        };
+        let has_subject =
+            target.is_some() || matches!(preview.subject, PreviewSubject::Stage { .. });
+        let active = visibility.get() && has_subject;
+        if camera.is_active != active {
+            camera.is_active = active;
+        }
+        let (
+            Some(target),
+            PreviewSubject::Entity {
+                distance, height, ..
+            },
+        ) = (target, preview.subject)
+        else {
+            continue;
+        };
+        let focus = target.translation() + Vec3::Y * height;
+        let toward_viewer = main_cameras
+            .get_single()
+            .ok()
+            .and_then(|main| (main.translation() - focus).try_normalize())
+            .unwrap_or(Vec3::Z);
+        *transform = Transform::from_translation(focus + toward_viewer * distance)
+            .looking_at(focus, Vec3::Y);
+    }
+}
+
+// Cameras and stages go with the image they render for
+fn clear_orphaned_previews(
+    mut commands: Commands,
+    cameras: Query<(Entity, &PreviewCamera)>,
+    widgets: Query<(), With<RenderPreview>>,
+) {
+    for (camera, preview_camera) in cameras.iter() {
+        if !widgets.contains(preview_camera.widget) {
+            commands.entity(camera).despawn();
+            commands.entity(preview_camera.stage).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+
+use crate::combat::{Health, Mana};
+use crate::controller::{CharacterController, MovementIntent};
+use crate::locale::{Locale, Localized};
+use crate::schedule::GameSet;
+use crate::skills::despawn::DespawnSkillExt;
+use crate::skills::{DespawnReason, SkillOwner};
+use crate::status::StatusEffects;
+use crate::threat::ThreatTarget;
+use crate::toast::ToastEvent;
+use crate::Player;
+
+// Seconds the death screen shows before the player comes back
+const RESPAWN_DELAY: f32 = 3.0;
+// Hostile skills this close to the respawn point are cleared so the player
+// doesn't come back into a hit
+const CLEAR_RADIUS: f32 = 6.0;
+
+// A level-defined place the player comes back to after dying, activated by
+// walking within `radius` of it
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Checkpoint {
+    pub radius: f32,
+}
+
+// Where the player comes back to, the last checkpoint reached or else where
+// they first appeared
+#[derive(Resource, Default)]
+pub struct RespawnPoint {
+    pub position: Option<Vec3>,
+    pub checkpoint: Option<Entity>,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct CheckpointReached {
+    pub checkpoint: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct PlayerDied {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct PlayerRespawned {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+// Dead and waiting out the death screen. Can't move, cast or be targeted.
+#[derive(Component)]
+pub struct Respawning {
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+struct DeathScreen;
+
+#[derive(Component)]
+struct DeathCountdown;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Checkpoint>()
+            .init_resource::<RespawnPoint>()
+            .add_event::<CheckpointReached>()
+            .add_event::<PlayerDied>()
+            .add_event::<PlayerRespawned>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        remember_first_spawn,
+                        reach_checkpoints,
+                        kill_player,
+                        respawn_player,
+                    )
+                        .chain()
+                        .in_set(GameSet::Simulation),
+                    update_death_countdown.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn remember_first_spawn(
+    mut respawn: ResMut<RespawnPoint>,
+    players: Query<&Transform, Added<Player>>,
+) {
+    for transform in players.iter() {
+        respawn.position.get_or_insert(transform.translation);
+    }
+}
+
+fn reach_checkpoints(
+    mut respawn: ResMut<RespawnPoint>,
+    checkpoints: Query<(Entity, &GlobalTransform, &Checkpoint)>,
+    players: Query<&GlobalTransform, (With<Player>, Without<Respawning>)>,
+    locale: Res<Locale>,
+    mut reached: EventWriter<CheckpointReached>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for player in players.iter() {
+        for (entity, transform, checkpoint) in checkpoints.iter() {
+            let position = transform.translation();
+            if respawn.checkpoint == Some(entity)
+                || player.translation().distance(position) > checkpoint.radius
+            {
+                continue;
+            }
+            respawn.checkpoint = Some(entity);
+            respawn.position = Some(position);
+            reached.send(CheckpointReached {
+                checkpoint: entity,
+                position,
+            });
+            toasts.send(ToastEvent::new(locale.get("toast.checkpoint")));
+        }
+    }
+}
+
+fn kill_player(
+    mut commands: Commands,
+    mut players: Query<
+        (Entity, &Health, &Transform, &mut Visibility),
+        (With<Player>, Without<Respawning>, Changed<Health>),
+    >,
+    screens: Query<(), With<DeathScreen>>,
+    mut died: EventWriter<PlayerDied>,
+) {
+    // One screen is shared by every dead player
+    let mut has_screen = !screens.is_empty();
+    for (entity, health, transform, mut visibility) in players.iter_mut() {
+        if health.current > 0.0 {
+            continue;
+        }
+        *visibility = Visibility::Hidden;
+        commands
+            .entity(entity)
+            .remove::<(CharacterController, ThreatTarget)>()
+            .insert(Respawning {
+                timer: Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once),
+            });
+        if !has_screen {
+            spawn_death_screen(&mut commands);
+            has_screen = true;
+        }
+        died.send(PlayerDied {
+            player: entity,
+            position: transform.translation,
+        });
+    }
+}
+
+fn respawn_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    respawn: Res<RespawnPoint>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Respawning,
+            &mut Transform,
+            &mut Visibility,
+            &mut Health,
+            Option<&mut Mana>,
+            Option<&mut StatusEffects>,
+            Option<&mut MovementIntent>,
+        ),
+        With<Player>,
+    >,
+    skills: Query<(Entity, &GlobalTransform, &SkillOwner)>,
+    owners: Query<(), With<Player>>,
+    screens: Query<Entity, With<DeathScreen>>,
+    mut respawned: EventWriter<PlayerRespawned>,
+) {
+    let dead = players.iter().len();
+    let mut back = 0;
+    for (entity, mut respawning, mut transform, mut visibility, mut health, mana, status, intent) in
+        players.iter_mut()
+    {
+        if !respawning.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let position = respawn.position.unwrap_or(transform.translation);
+        transform.translation = position;
+        *visibility = Visibility::Inherited;
+        health.current = health.max;
+        if let Some(mut mana) = mana {
+            mana.current = mana.max;
+        }
+        if let Some(mut status) = status {
+            *status = StatusEffects::default();
+        }
+        if let Some(mut intent) = intent {
+            intent.0 = Vec3::ZERO;
+        }
+        commands
+            .entity(entity)
+            .remove::<Respawning>()
+            .insert((CharacterController::default(), ThreatTarget));
+
+        // Co-op partners' skills are left alone
+        for (skill, skill_transform, owner) in skills.iter() {
+            if !owners.contains(owner.0)
+                && skill_transform.translation().distance(position) <= CLEAR_RADIUS
+            {
+                commands
+                    .entity(skill)
+                    .despawn_skill(DespawnReason::Dispelled);
+            }
+        }
+        back += 1;
+        respawned.send(PlayerRespawned {
+            player: entity,
+            position,
+        });
+    }
+    // The death screen stays up while anyone is still dead
+    if back > 0 && back == dead {
+        for screen in screens.iter() {
+            commands.entity(screen).despawn_recursive();
+        }
+    }
+}
+
+fn spawn_death_screen(commands: &mut Commands) {
+    let text = |localized: Localized, font_size: f32| {
+        (
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size,
+                    ..default()
+                },
+            ),
+            localized,
+        )
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.2, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DeathScreen,
+        ))
+        .with_children(|screen| {
+            screen.spawn(text(Localized::new("respawn.died"), 48.0));
+            screen.spawn((
+                text(
+                    Localized::new("respawn.countdown").with_arg("seconds", RESPAWN_DELAY),
+                    22.0,
+                ),
+                DeathCountdown,
+            ));
+        });
+}
+
+fn update_death_countdown(
+    players: Query<&Respawning, With<Player>>,
+    mut countdowns: Query<&mut Localized, With<DeathCountdown>>,
+) {
+    // Counts down to whichever dead player is back first
+    let Some(remaining) = players
+        .iter()
+        .map(|respawning| respawning.timer.remaining_secs())
+        .reduce(f32::min)
+    else {
+        return;
+    };
+    let seconds = remaining.ceil() as u32;
+    for mut localized in countdowns.iter_mut() {
+        let current = seconds.to_string();
+        if localized.args.first().map(|(_, value)| value) != Some(&current) {
+            *localized = Localized::new("respawn.countdown").with_arg("seconds", current);
+        }
+    }
+}
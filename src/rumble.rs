@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection, GamepadConnectionEvent,
+    GamepadRumbleIntensity, GamepadRumbleRequest,
+};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::combat::Health;
+use crate::schedule::GameSet;
+use crate::settings::ControlsSettings;
+use crate::skills::SkillCastEvent;
+use crate::Player;
+
+// Sticks resting slightly off center should not count as using the gamepad
+const AXIS_DEADZONE: f32 = 0.2;
+const CAST_RUMBLE: GamepadRumbleIntensity = GamepadRumbleIntensity::weak_motor(0.4);
+const CAST_RUMBLE_TIME: Duration = Duration::from_millis(80);
+const HIT_RUMBLE: GamepadRumbleIntensity = GamepadRumbleIntensity {
+    strong_motor: 0.8,
+    weak_motor: 0.3,
+};
+const HIT_RUMBLE_TIME: Duration = Duration::from_millis(200);
+
+// The gamepad the player is playing with, if the last input came from one.
+// Rumble is only sent to it, so keyboard players never get any.
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+pub struct RumblePlugin;
+
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveGamepad>().add_systems(
+            Update,
+            (
+                track_active_gamepad.in_set(GameSet::Input),
+                (rumble_on_cast, rumble_on_damage).in_set(GameSet::Simulation),
+            ),
+        );
+    }
+}
+
+fn track_active_gamepad(
+    mut active: ResMut<ActiveGamepad>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut buttons: EventReader<GamepadButtonChangedEvent>,
+    mut axes: EventReader<GamepadAxisChangedEvent>,
+    mut connections: EventReader<GamepadConnectionEvent>,
+) {
+    if keyboard_input.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+    {
+        active.0 = None;
+    }
+    for button in buttons.read() {
+        active.0 = Some(button.gamepad);
+    }
+    for axis in axes.read() {
+        if axis.value.abs() > AXIS_DEADZONE {
+            active.0 = Some(axis.gamepad);
+        }
+    }
+    for connection in connections.read() {
+        if connection.connection == GamepadConnection::Disconnected
+            && active.0 == Some(connection.gamepad)
+        {
+            active.0 = None;
+        }
+    }
+}
+
+fn rumble(
+    requests: &mut EventWriter<GamepadRumbleRequest>,
+    active: &ActiveGamepad,
+    settings: &ControlsSettings,
+    intensity: GamepadRumbleIntensity,
+    duration: Duration,
+) {
+    let Some(gamepad) = active.0 else {
+        return;
+    };
+    if settings.rumble <= 0.0 {
+        return;
+    }
+    requests.send(GamepadRumbleRequest::Add {
+        gamepad,
+        intensity: GamepadRumbleIntensity {
+            strong_motor: intensity.strong_motor * settings.rumble,
+            weak_motor: intensity.weak_motor * settings.rumble,
+        },
+        duration,
+    });
+}
+
+fn rumble_on_cast(
+    mut casts: EventReader<SkillCastEvent>,
+    players: Query<(), With<Player>>,
+    active: Res<ActiveGamepad>,
+    settings: Res<ControlsSettings>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+) {
+    if casts.read().any(|cast| players.contains(cast.caster)) {
+        rumble(
+            &mut requests,
+            &active,
+            &settings,
+            CAST_RUMBLE,
+            CAST_RUMBLE_TIME,
+        );
+    }
+}
+
+// Watches the player's health rather than any one damage source, so boss
+// attacks, burns and anything added later all rumble the same
+fn rumble_on_damage(
+    mut last_health: Local<HashMap<Entity, f32>>,
+    players: Query<(Entity, &Health), With<Player>>,
+    active: Res<ActiveGamepad>,
+    settings: Res<ControlsSettings>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+) {
+    let mut hurt = false;
+    for (player, health) in players.iter() {
+        if let Some(last) = last_health.insert(player, health.current) {
+            hurt |= health.current < last;
+        }
+    }
+    last_health.retain(|player, _| players.contains(*player));
+    if hurt {
+        rumble(
+            &mut requests,
+            &active,
+            &settings,
+            HIT_RUMBLE,
+            HIT_RUMBLE_TIME,
+        );
+    }
+}
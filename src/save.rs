@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub const SAVE_PATH: &str = "save.ron";
+
+// What the player arranged and keeps between runs, as opposed to settings
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SaveFile {
+    // Skill definition path in each hotbar slot, in `HOTBAR_KEYS` order.
+    // `None` is a slot left empty; slots past the end keep their default.
+    #[serde(default)]
+    pub hotbar: Vec<Option<String>>,
+}
+
+impl SaveFile {
+    // A missing or unreadable file starts from the defaults
+    pub fn load() -> Self {
+        load_ron(SAVE_PATH)
+    }
+
+    pub fn save(&self) {
+        save_ron(SAVE_PATH, self);
+    }
+}
+
+// Reads a RON file kept next to the game, like the save, settings and
+// leaderboard. A missing or unreadable file gives the defaults.
+pub fn load_ron<T: DeserializeOwned + Default>(path: &str) -> T {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return T::default();
+    };
+    ron::from_str(&contents).unwrap_or_else(|error| {
+        warn!("Ignoring invalid {}: {}", path, error);
+        T::default()
+    })
+}
+
+// Failing to write only warns, the game carries on with what it has
+pub fn save_ron<T: Serialize>(path: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| std::fs::write(path, contents).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        warn!("Could not save {}: {}", path, error);
+    }
+}
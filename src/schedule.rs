@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::animation::AnimationSystem;
+
+// Every Update system belongs to one of these, run in this order each frame:
+//
+// - Input: keyboard, mouse and UI buttons become intents, target locks,
+//   camera and state changes, and console commands
+// - Simulation: characters move, skills are cast and behave, hits are resolved
+//   into damage, deaths, experience and status effects
+// - Animation: animators advance and write the frame into the atlas, so
+//   anything spawned or switched this frame is already on the right frame
+// - Presentation: materials, lights, outlines, UI and the camera follow the
+//   state the earlier sets produced
+// - Debug: overlays and logging see the final state of the frame
+//
+// Ground snapping, billboards and outline hulls run in PostUpdate, after all of
+// these, so they see where everything ended up.
+//
+// Simulation and Animation only run while `Time<Virtual>` is unpaused, so the
+// pause menu and photo mode freeze the whole game at once. Gameplay timers
+// tick from the default `Time`, which is virtual time in Update; only camera,
+// UI and other things that must keep responding while paused read
+// `Time<Real>`. Input systems that start gameplay, like casting, use
+// `simulation_running` themselves.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Input,
+    Simulation,
+    Animation,
+    Presentation,
+    Debug,
+}
+
+pub struct GameSchedulePlugin;
+
+impl Plugin for GameSchedulePlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            Update,
+            (
+                GameSet::Input,
+                GameSet::Simulation,
+                GameSet::Animation,
+                GameSet::Presentation,
+                GameSet::Debug,
+            )
+                .chain(),
+        )
+        .configure_sets(
+            Update,
+            (GameSet::Simulation, GameSet::Animation).run_if(simulation_running),
+        )
+        .configure_sets(Update, AnimationSystem.in_set(GameSet::Animation));
+    }
+}
+
+// False while the game is paused, including photo mode
+pub fn simulation_running(time: Res<Time<Virtual>>) -> bool {
+    !time.is_paused()
+}
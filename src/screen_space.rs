@@ -0,0 +1,255 @@
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+
+// World to screen and back for one camera. Screen positions are logical
+// window pixels with the origin at the top left, like
+// `Window::cursor_position`. Everything goes through the camera's projection
+// matrix, so perspective and orthographic cameras work the same.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenProjection {
+    clip_from_world: Mat4,
+    world_from_clip: Mat4,
+    view_from_world: Affine3A,
+    viewport: Rect,
+}
+
+impl ScreenProjection {
+    // None until the camera has been laid out over its render target
+    pub fn new(camera: &Camera, transform: &GlobalTransform) -> Option<Self> {
+        Some(Self::from_parts(
+            camera.clip_from_view(),
+            transform,
+            camera.logical_viewport_rect()?,
+        ))
+    }
+
+    pub fn from_parts(clip_from_view: Mat4, transform: &GlobalTransform, viewport: Rect) -> Self {
+        let clip_from_world = clip_from_view * transform.compute_matrix().inverse();
+        Self {
+            clip_from_world,
+            world_from_clip: clip_from_world.inverse(),
+            view_from_world: transform.affine().inverse(),
+            viewport,
+        }
+    }
+
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    // None behind the camera or past its near and far planes. Points beside
+    // the view still project, outside the viewport.
+    pub fn world_to_screen(&self, world: Vec3) -> Option<Vec2> {
+        let ndc = self.clip_from_world.project_point3(world);
+        if !ndc.is_finite() || !(0.0..=1.0).contains(&ndc.z) {
+            return None;
+        }
+        // NDC y points up, screen y down
+        let uv = ndc.xy() * Vec2::new(0.5, -0.5) + 0.5;
+        Some(self.viewport.min + uv * self.viewport.size())
+    }
+
+    pub fn is_on_screen(&self, world: Vec3) -> bool {
+        self.world_to_screen(world)
+            .is_some_and(|screen| self.viewport.contains(screen))
+    }
+
+    // Ray from the near plane through `screen`. Perspective rays all start
+    // around the camera, orthographic ones are parallel.
+    pub fn screen_to_ray(&self, screen: Vec2) -> Option<Ray3d> {
+        let uv = (screen - self.viewport.min) / self.viewport.size();
+        let ndc = (uv - 0.5) * Vec2::new(2.0, -2.0);
+        // Reversed depth: 1 is the near plane, 0 the far one, which is at
+        // infinity for perspective cameras
+        let near = self.world_from_clip.project_point3(ndc.extend(1.0));
+        let far = self
+            .world_from_clip
+            .project_point3(ndc.extend(f32::EPSILON));
+        let direction = Dir3::new(far - near).ok()?;
+        Some(Ray3d::new(near, *direction))
+    }
+
+    // Where the ray through `screen` meets the level plane at `height`
+    pub fn screen_to_ground(&self, screen: Vec2, height: f32) -> Option<Vec3> {
+        let ray = self.screen_to_ray(screen)?;
+        let distance = ray.intersect_plane(Vec3::Y * height, InfinitePlane3d::new(Vec3::Y))?;
+        Some(ray.get_point(distance))
+    }
+
+    // Unit direction on screen from the viewport's center toward `world`, and
+    // whether it is on screen, e.g. for edge indicators. Points behind the
+    // camera are mirrored so the direction is still the way to turn.
+    pub fn direction_from_center(&self, world: Vec3) -> Option<(Vec2, bool)> {
+        let view = self.view_from_world.transform_point3(world);
+        // Cameras look down -Z
+        if view.z >= 0.0 {
+            let direction = Vec2::new(view.x, -view.y).try_normalize()?;
+            return Some((direction, false));
+        }
+        let screen = self.world_to_screen(world)?;
+        let direction = (screen - self.viewport.center()).try_normalize()?;
+        Some((direction, self.viewport.contains(screen)))
+    }
+}
+
+// Window pixels to the scaled pixels UI styles are laid out in
+pub fn to_ui(screen: Vec2, ui_scale: &UiScale) -> Vec2 {
+    screen / ui_scale.0
+}
+
+pub fn from_ui(ui: Vec2, ui_scale: &UiScale) -> Vec2 {
+    ui * ui_scale.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::camera::CameraProjection;
+
+    const SIZE: Vec2 = Vec2::new(800.0, 600.0);
+
+    fn looking_at_origin() -> GlobalTransform {
+        GlobalTransform::from(Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y))
+    }
+
+    fn perspective(viewport: Rect) -> ScreenProjection {
+        let mut projection = PerspectiveProjection::default();
+        projection.update(viewport.width(), viewport.height());
+        ScreenProjection::from_parts(
+            projection.get_clip_from_view(),
+            &looking_at_origin(),
+            viewport,
+        )
+    }
+
+    fn orthographic(viewport: Rect) -> ScreenProjection {
+        let mut projection = OrthographicProjection {
+            near: -100.0,
+            far: 100.0,
+            ..default()
+        };
+        projection.update(viewport.width(), viewport.height());
+        ScreenProjection::from_parts(
+            projection.get_clip_from_view(),
+            &looking_at_origin(),
+            viewport,
+        )
+    }
+
+    fn full_window() -> Rect {
+        Rect::from_corners(Vec2::ZERO, SIZE)
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-2, "{} is not {}", a, b);
+    }
+
+    #[test]
+    fn what_the_camera_looks_at_is_centered() {
+        for projection in [perspective(full_window()), orthographic(full_window())] {
+            let screen = projection.world_to_screen(Vec3::ZERO).unwrap();
+            assert_near(screen, SIZE / 2.0);
+        }
+    }
+
+    #[test]
+    fn up_in_the_world_is_up_on_screen() {
+        for projection in [perspective(full_window()), orthographic(full_window())] {
+            let screen = projection
+                .world_to_screen(Vec3::new(1.0, 1.0, 0.0))
+                .unwrap();
+            assert!(screen.x > SIZE.x / 2.0 && screen.y < SIZE.y / 2.0);
+        }
+    }
+
+    #[test]
+    fn nothing_projects_from_behind_a_perspective_camera() {
+        let projection = perspective(full_window());
+        assert_eq!(projection.world_to_screen(Vec3::new(0.0, 0.0, 20.0)), None);
+        assert!(!projection.is_on_screen(Vec3::new(0.0, 0.0, 20.0)));
+    }
+
+    #[test]
+    fn rays_pass_back_through_projected_points() {
+        let point = Vec3::new(1.5, -0.5, 2.0);
+        for projection in [perspective(full_window()), orthographic(full_window())] {
+            let screen = projection.world_to_screen(point).unwrap();
+            let ray = projection.screen_to_ray(screen).unwrap();
+            let along = (point - ray.origin).dot(*ray.direction);
+            assert!(ray.get_point(along).distance(point) < 1e-2);
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let projection = orthographic(full_window());
+        let a = projection.screen_to_ray(Vec2::new(10.0, 20.0)).unwrap();
+        let b = projection.screen_to_ray(Vec2::new(700.0, 500.0)).unwrap();
+        assert!(a.direction.dot(*b.direction) > 0.9999);
+        assert!(a.origin.distance(b.origin) > 1.0);
+    }
+
+    #[test]
+    fn perspective_rays_spread_from_the_camera() {
+        let projection = perspective(full_window());
+        let a = projection.screen_to_ray(Vec2::new(10.0, 20.0)).unwrap();
+        let b = projection.screen_to_ray(Vec2::new(700.0, 500.0)).unwrap();
+        assert!(a.direction.dot(*b.direction) < 0.99);
+        assert!(a.origin.distance(Vec3::new(0.0, 0.0, 10.0)) < 0.5);
+    }
+
+    #[test]
+    fn the_center_ray_reaches_the_ground_below() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(0.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        let mut perspective = PerspectiveProjection::default();
+        perspective.update(SIZE.x, SIZE.y);
+        let projection = ScreenProjection::from_parts(
+            perspective.get_clip_from_view(),
+            &transform,
+            full_window(),
+        );
+        let ground = projection.screen_to_ground(SIZE / 2.0, 0.0).unwrap();
+        assert!(ground.distance(Vec3::ZERO) < 1e-2);
+        // Looking down, the sky is never reached
+        let raised = projection.screen_to_ground(SIZE / 2.0, 20.0);
+        assert_eq!(raised, None);
+    }
+
+    #[test]
+    fn viewports_offset_the_screen() {
+        let viewport = Rect::from_corners(Vec2::new(100.0, 50.0), Vec2::new(500.0, 350.0));
+        let projection = perspective(viewport);
+        let screen = projection.world_to_screen(Vec3::ZERO).unwrap();
+        assert_near(screen, viewport.center());
+        let ray = projection.screen_to_ray(viewport.center()).unwrap();
+        assert!(ray.direction.dot(Vec3::NEG_Z) > 0.9999);
+    }
+
+    #[test]
+    fn directions_point_toward_off_screen_points() {
+        let projection = perspective(full_window());
+        let (direction, on_screen) = projection
+            .direction_from_center(Vec3::new(100.0, 0.0, 0.0))
+            .unwrap();
+        assert!(!on_screen);
+        assert_near(direction, Vec2::X);
+        // Behind and to the left is still to the left
+        let (direction, on_screen) = projection
+            .direction_from_center(Vec3::new(-5.0, 0.0, 20.0))
+            .unwrap();
+        assert!(!on_screen);
+        assert_near(direction, Vec2::NEG_X);
+        let (_, on_screen) = projection.direction_from_center(Vec3::X).unwrap();
+        assert!(on_screen);
+    }
+
+    #[test]
+    fn ui_pixels_scale_back_and_forth() {
+        let scale = UiScale(1.5);
+        let screen = Vec2::new(300.0, 150.0);
+        assert_near(from_ui(to_ui(screen, &scale), &scale), screen);
+        assert_near(to_ui(screen, &scale), Vec2::new(200.0, 100.0));
+    }
+}
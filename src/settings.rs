@@ -0,0 +1,751 @@
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::locale::{Language, Locale};
+use crate::pause::{GameState, PauseMenu};
+use crate::save::{load_ron, save_ron};
+use crate::schedule::GameSet;
+
+pub const SETTINGS_PATH: &str = "settings.ron";
+
+const RESOLUTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+const MSAA_SAMPLES: [u32; 4] = [1, 2, 4, 8];
+const RUMBLE_LEVELS: [f32; 4] = [0.0, 0.25, 0.5, 1.0];
+const CORPSE_LIMITS: [usize; 4] = [0, 15, 30, 60];
+const UI_SCALES: [f32; 5] = [0.75, 0.9, 1.0, 1.25, 1.5];
+const SAFE_AREAS: [f32; 4] = [0.0, 0.025, 0.05, 0.1];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphicsSettings {
+    pub resolution: (u32, u32),
+    pub display_mode: DisplayMode,
+    pub vsync: bool,
+    // Sample count, 1 turns MSAA off
+    pub msaa: u32,
+    // Low health vignette and ultimate blur, see `PostProcessSettings`
+    #[serde(default = "default_post_processing")]
+    pub post_processing: bool,
+    // Most corpses and scorch marks left lying around at once, 0 leaves none
+    #[serde(default = "default_corpse_limit")]
+    pub corpse_limit: usize,
+}
+
+fn default_post_processing() -> bool {
+    true
+}
+
+fn default_corpse_limit() -> usize {
+    30
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            resolution: (1280, 720),
+            display_mode: DisplayMode::Windowed,
+            vsync: true,
+            msaa: 4,
+            post_processing: true,
+            corpse_limit: 30,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlsSettings {
+    // Scales every gamepad rumble, 0 turns it off
+    pub rumble: f32,
+}
+
+impl Default for ControlsSettings {
+    fn default() -> Self {
+        Self { rumble: 1.0 }
+    }
+}
+
+// Colors for things told apart by color alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    Standard,
+    // Red-green safe, also fine for protanopia
+    Deuteranopia,
+    // Blue-yellow safe
+    Tritanopia,
+}
+
+impl Palette {
+    // Outline of the enemy under the cursor
+    pub fn hovered(self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(1.0, 0.95, 0.6),
+            Palette::Deuteranopia => Color::srgb(0.4, 0.75, 1.0),
+            Palette::Tritanopia => Color::srgb(0.6, 1.0, 0.95),
+        }
+    }
+
+    // Outline of the locked target
+    pub fn locked(self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(1.0, 0.3, 0.2),
+            Palette::Deuteranopia => Color::srgb(1.0, 0.6, 0.0),
+            Palette::Tritanopia => Color::srgb(1.0, 0.2, 0.5),
+        }
+    }
+
+    // Areas about to be hit, like boss telegraphs
+    pub fn danger(self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(1.0, 0.15, 0.1),
+            Palette::Deuteranopia => Color::srgb(1.0, 0.5, 0.0),
+            Palette::Tritanopia => Color::srgb(0.9, 0.0, 0.35),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    // Caps how bright and how fast skill lights can flash
+    pub reduce_flashing: bool,
+    pub palette: Palette,
+    // Channeled skills last while their key is held, or until it is pressed
+    // again when off
+    pub hold_to_channel: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            reduce_flashing: false,
+            palette: Palette::Standard,
+            hold_to_channel: true,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceSettings {
+    pub language: Language,
+    // Multiplies the scaling the HUD already gets from the window height
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    // Fraction of the window kept clear of the HUD on every side, for screens
+    // that crop their edges
+    #[serde(default)]
+    pub safe_area: f32,
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+impl Default for InterfaceSettings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            ui_scale: 1.0,
+            safe_area: 0.0,
+        }
+    }
+}
+
+// How much tougher or weaker enemies are than their prefabs say, and how many
+// of them spawners send
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyMultipliers {
+    pub enemy_health: f32,
+    pub enemy_damage: f32,
+    pub enemy_speed: f32,
+    pub spawn_count: f32,
+}
+
+impl DifficultyMultipliers {
+    pub const EASY: Self = Self {
+        enemy_health: 0.7,
+        enemy_damage: 0.6,
+        enemy_speed: 0.9,
+        spawn_count: 0.75,
+    };
+    pub const NORMAL: Self = Self {
+        enemy_health: 1.0,
+        enemy_damage: 1.0,
+        enemy_speed: 1.0,
+        spawn_count: 1.0,
+    };
+    pub const HARD: Self = Self {
+        enemy_health: 1.4,
+        enemy_damage: 1.5,
+        enemy_speed: 1.15,
+        spawn_count: 1.5,
+    };
+
+    // Never rounds a spawner down to nothing
+    pub fn scale_count(&self, count: u32) -> u32 {
+        ((count as f32 * self.spawn_count).round() as u32).max(1)
+    }
+}
+
+impl Default for DifficultyMultipliers {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    // Uses `GameplaySettings::custom`, only editable in the settings file
+    Custom,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameplaySettings {
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub custom: DifficultyMultipliers,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::Normal,
+            custom: DifficultyMultipliers::NORMAL,
+        }
+    }
+}
+
+impl GameplaySettings {
+    pub fn multipliers(&self) -> DifficultyMultipliers {
+        match self.difficulty {
+            Difficulty::Easy => DifficultyMultipliers::EASY,
+            Difficulty::Normal => DifficultyMultipliers::NORMAL,
+            Difficulty::Hard => DifficultyMultipliers::HARD,
+            Difficulty::Custom => self.custom,
+        }
+    }
+}
+
+// Everything persisted to `SETTINGS_PATH`, one field per settings group
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SettingsFile {
+    #[serde(default)]
+    pub gameplay: GameplaySettings,
+    #[serde(default)]
+    pub graphics: GraphicsSettings,
+    #[serde(default)]
+    pub controls: ControlsSettings,
+    #[serde(default)]
+    pub accessibility: AccessibilitySettings,
+    #[serde(default)]
+    pub interface: InterfaceSettings,
+}
+
+impl SettingsFile {
+    // A missing or unreadable file falls back to the defaults
+    pub fn load() -> Self {
+        load_ron(SETTINGS_PATH)
+    }
+
+    pub fn save(&self) {
+        save_ron(SETTINGS_PATH, self);
+    }
+}
+
+// A settings menu button showing and changing one field of its group
+trait SettingsOption: Component + Copy {
+    type Group: Resource;
+    const ALL: &'static [Self];
+
+    fn label(self, settings: &Self::Group, locale: &Locale) -> String;
+    fn cycle(self, settings: &mut Self::Group);
+}
+
+fn next<T: PartialEq + Copy>(options: &[T], current: T) -> T {
+    let index = options.iter().position(|option| *option == current);
+    options[index.map_or(0, |index| (index + 1) % options.len())]
+}
+
+fn on_off(locale: &Locale, value: bool) -> &str {
+    locale.get(if value { "settings.on" } else { "settings.off" })
+}
+
+#[derive(Component, Clone, Copy)]
+enum GameplayOption {
+    Difficulty,
+}
+
+impl SettingsOption for GameplayOption {
+    type Group = GameplaySettings;
+    const ALL: &'static [Self] = &[GameplayOption::Difficulty];
+
+    fn label(self, settings: &GameplaySettings, locale: &Locale) -> String {
+        match self {
+            GameplayOption::Difficulty => {
+                let level = locale.get(match settings.difficulty {
+                    Difficulty::Easy => "settings.difficulty.easy",
+                    Difficulty::Normal => "settings.difficulty.normal",
+                    Difficulty::Hard => "settings.difficulty.hard",
+                    Difficulty::Custom => "settings.difficulty.custom",
+                });
+                locale.format("settings.difficulty", &[("level", &level)])
+            }
+        }
+    }
+
+    // Custom is only offered once the settings file has set it
+    fn cycle(self, settings: &mut GameplaySettings) {
+        match self {
+            GameplayOption::Difficulty => {
+                let mut levels = vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+                if settings.custom != DifficultyMultipliers::NORMAL {
+                    levels.push(Difficulty::Custom);
+                }
+                settings.difficulty = next(&levels, settings.difficulty)
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum GraphicsOption {
+    Resolution,
+    DisplayMode,
+    Vsync,
+    Msaa,
+    PostProcessing,
+    Corpses,
+}
+
+impl SettingsOption for GraphicsOption {
+    type Group = GraphicsSettings;
+    const ALL: &'static [Self] = &[
+        GraphicsOption::Resolution,
+        GraphicsOption::DisplayMode,
+        GraphicsOption::Vsync,
+        GraphicsOption::Msaa,
+        GraphicsOption::PostProcessing,
+        GraphicsOption::Corpses,
+    ];
+
+    fn label(self, settings: &GraphicsSettings, locale: &Locale) -> String {
+        match self {
+            GraphicsOption::Resolution => locale.format(
+                "settings.resolution",
+                &[
+                    ("width", &settings.resolution.0),
+                    ("height", &settings.resolution.1),
+                ],
+            ),
+            GraphicsOption::DisplayMode => {
+                let mode = locale.get(match settings.display_mode {
+                    DisplayMode::Windowed => "settings.display.windowed",
+                    DisplayMode::Borderless => "settings.display.borderless",
+                    DisplayMode::Fullscreen => "settings.display.fullscreen",
+                });
+                locale.format("settings.display", &[("mode", &mode)])
+            }
+            GraphicsOption::Vsync => locale.format(
+                "settings.vsync",
+                &[("value", &on_off(locale, settings.vsync))],
+            ),
+            GraphicsOption::Msaa => {
+                let value = match settings.msaa {
+                    1 => on_off(locale, false).to_string(),
+                    samples => format!("{}x", samples),
+                };
+                locale.format("settings.msaa", &[("value", &value)])
+            }
+            GraphicsOption::PostProcessing => locale.format(
+                "settings.post_processing",
+                &[("value", &on_off(locale, settings.post_processing))],
+            ),
+            GraphicsOption::Corpses => {
+                let value = match settings.corpse_limit {
+                    0 => on_off(locale, false).to_string(),
+                    limit => limit.to_string(),
+                };
+                locale.format("settings.corpses", &[("value", &value)])
+            }
+        }
+    }
+
+    fn cycle(self, settings: &mut GraphicsSettings) {
+        match self {
+            GraphicsOption::Resolution => {
+                settings.resolution = next(&RESOLUTIONS, settings.resolution)
+            }
+            GraphicsOption::DisplayMode => {
+                settings.display_mode = next(
+                    &[
+                        DisplayMode::Windowed,
+                        DisplayMode::Borderless,
+                        DisplayMode::Fullscreen,
+                    ],
+                    settings.display_mode,
+                )
+            }
+            GraphicsOption::Vsync => settings.vsync = !settings.vsync,
+            GraphicsOption::Msaa => settings.msaa = next(&MSAA_SAMPLES, settings.msaa),
+            GraphicsOption::PostProcessing => settings.post_processing = !settings.post_processing,
+            GraphicsOption::Corpses => {
+                settings.corpse_limit = next(&CORPSE_LIMITS, settings.corpse_limit)
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum ControlsOption {
+    Rumble,
+}
+
+impl SettingsOption for ControlsOption {
+    type Group = ControlsSettings;
+    const ALL: &'static [Self] = &[ControlsOption::Rumble];
+
+    fn label(self, settings: &ControlsSettings, locale: &Locale) -> String {
+        match self {
+            ControlsOption::Rumble => {
+                let value = match settings.rumble {
+                    rumble if rumble <= 0.0 => on_off(locale, false).to_string(),
+                    rumble => format!("{:.0}%", rumble * 100.0),
+                };
+                locale.format("settings.rumble", &[("value", &value)])
+            }
+        }
+    }
+
+    fn cycle(self, settings: &mut ControlsSettings) {
+        match self {
+            ControlsOption::Rumble => settings.rumble = next(&RUMBLE_LEVELS, settings.rumble),
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum AccessibilityOption {
+    ReduceFlashing,
+    Palette,
+    ChannelInput,
+}
+
+impl SettingsOption for AccessibilityOption {
+    type Group = AccessibilitySettings;
+    const ALL: &'static [Self] = &[
+        AccessibilityOption::ReduceFlashing,
+        AccessibilityOption::Palette,
+        AccessibilityOption::ChannelInput,
+    ];
+
+    fn label(self, settings: &AccessibilitySettings, locale: &Locale) -> String {
+        match self {
+            AccessibilityOption::ReduceFlashing => locale.format(
+                "settings.reduce_flashing",
+                &[("value", &on_off(locale, settings.reduce_flashing))],
+            ),
+            AccessibilityOption::Palette => {
+                let palette = locale.get(match settings.palette {
+                    Palette::Standard => "settings.palette.standard",
+                    Palette::Deuteranopia => "settings.palette.deuteranopia",
+                    Palette::Tritanopia => "settings.palette.tritanopia",
+                });
+                locale.format("settings.palette", &[("palette", &palette)])
+            }
+            AccessibilityOption::ChannelInput => {
+                let mode = locale.get(if settings.hold_to_channel {
+                    "settings.channel.hold"
+                } else {
+                    "settings.channel.toggle"
+                });
+                locale.format("settings.channel", &[("mode", &mode)])
+            }
+        }
+    }
+
+    fn cycle(self, settings: &mut AccessibilitySettings) {
+        match self {
+            AccessibilityOption::ReduceFlashing => {
+                settings.reduce_flashing = !settings.reduce_flashing
+            }
+            AccessibilityOption::Palette => {
+                settings.palette = next(
+                    &[
+                        Palette::Standard,
+                        Palette::Deuteranopia,
+                        Palette::Tritanopia,
+                    ],
+                    settings.palette,
+                )
+            }
+            AccessibilityOption::ChannelInput => {
+                settings.hold_to_channel = !settings.hold_to_channel
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum InterfaceOption {
+    Language,
+    UiScale,
+    SafeArea,
+}
+
+impl SettingsOption for InterfaceOption {
+    type Group = InterfaceSettings;
+    const ALL: &'static [Self] = &[
+        InterfaceOption::Language,
+        InterfaceOption::UiScale,
+        InterfaceOption::SafeArea,
+    ];
+
+    fn label(self, settings: &InterfaceSettings, locale: &Locale) -> String {
+        match self {
+            InterfaceOption::Language => locale.format(
+                "settings.language",
+                &[("language", &settings.language.name())],
+            ),
+            InterfaceOption::UiScale => locale.format(
+                "settings.ui_scale",
+                &[("value", &format!("{:.0}%", settings.ui_scale * 100.0))],
+            ),
+            InterfaceOption::SafeArea => {
+                let value = match settings.safe_area {
+                    margin if margin <= 0.0 => on_off(locale, false).to_string(),
+                    margin => format!("{:.1}%", margin * 100.0),
+                };
+                locale.format("settings.safe_area", &[("value", &value)])
+            }
+        }
+    }
+
+    fn cycle(self, settings: &mut InterfaceSettings) {
+        match self {
+            InterfaceOption::Language => {
+                settings.language = next(&Language::ALL, settings.language)
+            }
+            InterfaceOption::UiScale => settings.ui_scale = next(&UI_SCALES, settings.ui_scale),
+            InterfaceOption::SafeArea => settings.safe_area = next(&SAFE_AREAS, settings.safe_area),
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let file = SettingsFile::load();
+        app.insert_resource(file.gameplay)
+            .insert_resource(file.graphics)
+            .insert_resource(file.controls)
+            .insert_resource(file.accessibility)
+            .insert_resource(file.interface)
+            .add_systems(OnEnter(GameState::Paused), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    (
+                        press_settings_buttons::<GameplayOption>,
+                        press_settings_buttons::<GraphicsOption>,
+                        press_settings_buttons::<ControlsOption>,
+                        press_settings_buttons::<AccessibilityOption>,
+                        press_settings_buttons::<InterfaceOption>,
+                    )
+                        .run_if(in_state(GameState::Paused))
+                        .in_set(GameSet::Input),
+                    (
+                        update_settings_labels::<GameplayOption>,
+                        update_settings_labels::<GraphicsOption>,
+                        update_settings_labels::<ControlsOption>,
+                        update_settings_labels::<AccessibilityOption>,
+                        update_settings_labels::<InterfaceOption>,
+                        apply_graphics_settings,
+                        save_settings,
+                    )
+                        .chain()
+                        .in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn spawn_settings_menu(
+    mut commands: Commands,
+    gameplay: Res<GameplaySettings>,
+    graphics: Res<GraphicsSettings>,
+    controls: Res<ControlsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    interface: Res<InterfaceSettings>,
+    locale: Res<Locale>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(40.0),
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexEnd,
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                },
+                ..default()
+            },
+            // Despawned together with the rest of the pause menu
+            PauseMenu,
+            SafeAreaAnchor::default(),
+        ))
+        // One column per settings group
+        .with_children(|menu| {
+            spawn_option_buttons::<GameplayOption>(menu, &gameplay, &locale);
+            spawn_option_buttons::<GraphicsOption>(menu, &graphics, &locale);
+            spawn_option_buttons::<ControlsOption>(menu, &controls, &locale);
+            spawn_option_buttons::<AccessibilityOption>(menu, &accessibility, &locale);
+            spawn_option_buttons::<InterfaceOption>(menu, &interface, &locale);
+        });
+}
+
+fn spawn_option_buttons<O: SettingsOption>(
+    menu: &mut ChildBuilder,
+    settings: &O::Group,
+    locale: &Locale,
+) {
+    menu.spawn(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        ..default()
+    })
+    .with_children(|column| {
+        for option in O::ALL.iter().copied() {
+            column
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(260.0),
+                            padding: UiRect::all(Val::Px(6.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.2, 0.25, 0.3, 0.9).into(),
+                        ..default()
+                    },
+                    option,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        option.label(settings, locale),
+                        TextStyle {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+fn press_settings_buttons<O: SettingsOption>(
+    buttons: Query<(&Interaction, &O), Changed<Interaction>>,
+    mut settings: ResMut<O::Group>,
+) {
+    for (interaction, option) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            option.cycle(&mut settings);
+        }
+    }
+}
+
+fn update_settings_labels<O: SettingsOption>(
+    settings: Res<O::Group>,
+    locale: Res<Locale>,
+    buttons: Query<(&O, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !locale.is_changed() {
+        return;
+    }
+    for (option, children) in buttons.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = option.label(&settings, &locale);
+            }
+        }
+    }
+}
+
+fn apply_graphics_settings(
+    settings: Res<GraphicsSettings>,
+    msaa: Option<ResMut<Msaa>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for mut window in windows.iter_mut() {
+        let (width, height) = settings.resolution;
+        window.resolution.set(width as f32, height as f32);
+        window.mode = match settings.display_mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        };
+        window.present_mode = if settings.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+    if let Some(mut msaa) = msaa {
+        *msaa = match settings.msaa {
+            2 => Msaa::Sample2,
+            4 => Msaa::Sample4,
+            8 => Msaa::Sample8,
+            _ => Msaa::Off,
+        };
+    }
+}
+
+// The loaded settings count as a change on the first frame, so skip that one
+fn save_settings(
+    gameplay: Res<GameplaySettings>,
+    graphics: Res<GraphicsSettings>,
+    controls: Res<ControlsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    interface: Res<InterfaceSettings>,
+) {
+    let changed = gameplay.is_changed()
+        || graphics.is_changed()
+        || controls.is_changed()
+        || accessibility.is_changed()
+        || interface.is_changed();
+    let loaded = gameplay.is_added()
+        || graphics.is_added()
+        || controls.is_added()
+        || accessibility.is_added()
+        || interface.is_added();
+    if changed && !loaded {
+        SettingsFile {
+            gameplay: gameplay.clone(),
+            graphics: graphics.clone(),
+            controls: controls.clone(),
+            accessibility: accessibility.clone(),
+            interface: interface.clone(),
+        }
+        .save();
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+use super::despawn::{DespawnReason, DespawnSkillExt};
+
+// Keeps an entity at a fixed offset from another one without parenting it, so
+// the attached entity keeps its own rotation and scale
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct AttachedTo {
+    pub target: Entity,
+    pub offset: Vec3,
+}
+
+pub fn follow_attachments(
+    mut commands: Commands,
+    targets: Query<&GlobalTransform, Without<AttachedTo>>,
+    mut attached: Query<(Entity, &AttachedTo, &mut Transform)>,
+) {
+    for (entity, attachment, mut transform) in attached.iter_mut() {
+        match targets.get(attachment.target) {
+            Ok(target) => transform.translation = target.translation() + attachment.offset,
+            Err(_) => {
+                commands
+                    .entity(entity)
+                    .despawn_skill(DespawnReason::OwnerDied);
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::math::Affine2;
+use bevy::prelude::*;
+
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use super::{
+    frame_uv_transform, RegisterSkillBehavior, SkillBehavior, SkillDefinition, SkillSpawnContext,
+    WaterSkill,
+};
+use crate::combat::{Hurtbox, ManualHitDetection, SkillHitEvent};
+use crate::hit_volume::closest_point_on_segment;
+use crate::schedule::GameSet;
+use crate::Enemy;
+
+pub struct BeamBehaviorPlugin;
+
+impl Plugin for BeamBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BeamSkill>()
+            .register_type::<BeamCap>()
+            .register_skill_behavior("beam", BeamBehavior)
+            .add_systems(
+                Update,
+                (
+                    init_beams,
+                    update_beams,
+                    beam_damage_ticks,
+                    cleanup_beam_caps,
+                )
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// Definition params: `max_range`, `width`, `height`, `tile_length`, `scroll_speed`,
+// `tick_interval`, `start_frame`, `middle_frame`, `end_frame`. A `beam` texture is
+// tiled and scrolled along the middle segment; without one the middle frame of the
+// sheet is stretched instead.
+pub struct BeamBehavior;
+
+impl SkillBehavior for BeamBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        let tick_interval = definition.param("tick_interval", 0.2).max(0.05);
+        skill
+            .remove::<TextureAtlas>()
+            .insert(ManualHitDetection)
+            .insert(BeamSkill {
+                caster: ctx.caster,
+                max_range: definition.param("max_range", 8.0),
+                width: definition.param("width", 0.5),
+                height: definition.param("height", 0.5),
+                tile_length: definition.param("tile_length", 1.0),
+                scroll_speed: definition.param("scroll_speed", 2.0),
+                start_frame: definition.param("start_frame", 0.0) as usize,
+                middle_frame: definition.param("middle_frame", 1.0) as usize,
+                end_frame: definition.param("end_frame", 2.0) as usize,
+                tick: Timer::from_seconds(tick_interval, TimerMode::Repeating),
+                scroll: 0.0,
+                tiled: definition.extra_image("beam").is_some(),
+                start: Vec3::ZERO,
+                end: Vec3::ZERO,
+                caps: None,
+            });
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct BeamSkill {
+    pub caster: Entity,
+    pub max_range: f32,
+    pub width: f32,
+    pub height: f32,
+    pub tile_length: f32,
+    pub scroll_speed: f32,
+    pub start_frame: usize,
+    pub middle_frame: usize,
+    pub end_frame: usize,
+    pub tick: Timer,
+    pub scroll: f32,
+    pub tiled: bool,
+    pub start: Vec3,
+    pub end: Vec3,
+    pub caps: Option<(Entity, Entity)>,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct BeamCap {
+    beam: Entity,
+}
+
+// Transform for a unit quad stretched from `from` to `to`, kept as flat to the
+// ground as its direction allows so it reads well from the gameplay camera
+pub fn beam_transform(from: Vec3, to: Vec3, width: f32) -> Transform {
+    let delta = to - from;
+    let length = delta.length();
+    let Some(direction) = delta.try_normalize() else {
+        return Transform::from_translation(from);
+    };
+    let normal = (Vec3::Y - direction * direction.y)
+        .try_normalize()
+        .unwrap_or(Vec3::Z);
+    let up = normal.cross(direction);
+    Transform {
+        translation: from + delta * 0.5,
+        rotation: Quat::from_mat3(&Mat3::from_cols(direction, up, normal)),
+        scale: Vec3::new(length, width, 1.0),
+    }
+}
+
+// Caps and the middle material need asset access, so they are set up on the
+// first frame rather than in `SkillBehavior::spawn`
+fn init_beams(
+    mut commands: Commands,
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut beams: Query<
+        (
+            Entity,
+            &WaterSkill,
+            &Handle<StandardMaterial>,
+            &mut BeamSkill,
+        ),
+        Added<BeamSkill>,
+    >,
+) {
+    for (entity, skill, material_handle, mut beam) in beams.iter_mut() {
+        let Some(definition) = definitions.get(&skill.definition) else {
+            continue;
+        };
+        let layout = layouts.get(&definition.atlas_layout);
+        let frame_transform = |index: usize| {
+            layout.and_then(|layout| frame_uv_transform(layout, index, definition.frame_inset))
+        };
+
+        if let Some(material) = materials.get_mut(material_handle) {
+            material.cull_mode = None;
+            match definition.extra_image("beam") {
+                Some(image) => material.base_color_texture = Some(image),
+                None => {
+                    material.uv_transform = frame_transform(beam.middle_frame).unwrap_or_default()
+                }
+            }
+        }
+
+        let quad = meshes.add(Mesh::from(Rectangle::new(1.0, 1.0)));
+        let mut spawn_cap = |frame: usize| {
+            let material = materials.add(StandardMaterial {
+                base_color_texture: Some(definition.image.clone()),
+                uv_transform: frame_transform(frame).unwrap_or_default(),
+                alpha_mode: definition.alpha_mode(),
+                unlit: true,
+                cull_mode: None,
+                ..default()
+            });
+            commands
+                .spawn((
+                    PbrBundle {
+                        mesh: quad.clone(),
+                        material,
+                        ..default()
+                    },
+                    BeamCap { beam: entity },
+                ))
+                .id()
+        };
+        beam.caps = Some((spawn_cap(beam.start_frame), spawn_cap(beam.end_frame)));
+    }
+}
+
+fn update_beams(
+    mut commands: Commands,
+    time: Res<Time>,
+    casters: Query<&Transform, (Without<BeamSkill>, Without<BeamCap>)>,
+    enemies: Query<&Transform, (With<Enemy>, Without<BeamSkill>, Without<BeamCap>)>,
+    mut beams: Query<(
+        Entity,
+        &mut Transform,
+        &Handle<StandardMaterial>,
+        &mut BeamSkill,
+    )>,
+    mut caps: Query<&mut Transform, (With<BeamCap>, Without<BeamSkill>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, mut transform, material_handle, mut beam) in beams.iter_mut() {
+        let Ok(caster) = casters.get(beam.caster) else {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::OwnerDied);
+            continue;
+        };
+
+        let origin = caster.translation + Vec3::Y * beam.height;
+        let target = enemies
+            .iter()
+            .map(|enemy| enemy.translation)
+            .filter(|position| position.distance(origin) <= beam.max_range)
+            .min_by(|a, b| a.distance(origin).total_cmp(&b.distance(origin)));
+        let end = match target {
+            Some(position) => position,
+            None => origin + *caster.forward() * beam.max_range,
+        };
+        beam.start = origin;
+        beam.end = end;
+
+        // The caps take up one beam width at each end, the middle fills the rest
+        let direction = (end - origin).normalize_or_zero();
+        let cap = beam.width.min(origin.distance(end) * 0.5);
+        let middle_start = origin + direction * cap;
+        let middle_end = end - direction * cap;
+        *transform = beam_transform(middle_start, middle_end, beam.width);
+
+        if beam.tiled {
+            beam.scroll += beam.scroll_speed * time.delta_seconds();
+            if let Some(material) = materials.get_mut(material_handle) {
+                let repeats = middle_start.distance(middle_end) / beam.tile_length.max(0.01);
+                material.uv_transform = Affine2::from_scale_angle_translation(
+                    Vec2::new(repeats, 1.0),
+                    0.0,
+                    Vec2::new(-beam.scroll, 0.0),
+                );
+            }
+        }
+
+        if let Some((start_cap, end_cap)) = beam.caps {
+            if let Ok(mut cap_transform) = caps.get_mut(start_cap) {
+                *cap_transform = beam_transform(origin, middle_start, beam.width);
+            }
+            if let Ok(mut cap_transform) = caps.get_mut(end_cap) {
+                *cap_transform = beam_transform(middle_end, end, beam.width);
+            }
+        }
+    }
+}
+
+fn beam_damage_ticks(
+    time: Res<Time>,
+    mut beams: Query<(Entity, &mut BeamSkill)>,
+    enemies: Query<(Entity, &Transform, &Hurtbox), With<Enemy>>,
+    mut hits: EventWriter<SkillHitEvent>,
+) {
+    for (skill, mut beam) in beams.iter_mut() {
+        beam.tick.tick(time.delta());
+        if !beam.tick.just_finished() {
+            continue;
+        }
+        for (target, transform, hurtbox) in enemies.iter() {
+            let closest = closest_point_on_segment(beam.start, beam.end, transform.translation);
+            if closest.distance(transform.translation) <= hurtbox.radius + beam.width * 0.5 {
+                hits.send(SkillHitEvent { skill, target });
+            }
+        }
+    }
+}
+
+fn cleanup_beam_caps(
+    mut commands: Commands,
+    caps: Query<(Entity, &BeamCap)>,
+    beams: Query<(), With<BeamSkill>>,
+) {
+    for (entity, cap) in caps.iter() {
+        if beams.get(cap.beam).is_err() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
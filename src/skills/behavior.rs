@@ -0,0 +1,68 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::definition::SkillDefinition;
+
+pub struct SkillSpawnContext<'a> {
+    pub caster: Entity,
+    pub definition: &'a SkillDefinition,
+    pub position: Vec3,
+    // Key that triggered the cast, for behaviors that last while it is held
+    pub key: Option<KeyCode>,
+}
+
+// A behavior decides what a skill does once it exists. Behaviors are looked up
+// by the `behavior` name in the skill definition; the components inserted in
+// `spawn` are then driven by the systems the behavior's plugin adds.
+pub trait SkillBehavior: Send + Sync + 'static {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext);
+}
+
+#[derive(Resource, Default)]
+pub struct SkillBehaviors {
+    behaviors: HashMap<String, Box<dyn SkillBehavior>>,
+}
+
+impl SkillBehaviors {
+    pub fn register(&mut self, name: impl Into<String>, behavior: impl SkillBehavior) {
+        self.behaviors.insert(name.into(), Box::new(behavior));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn SkillBehavior> {
+        self.behaviors.get(name).map(|behavior| behavior.as_ref())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.behaviors.keys().map(String::as_str)
+    }
+}
+
+pub trait RegisterSkillBehavior {
+    fn register_skill_behavior(
+        &mut self,
+        name: impl Into<String>,
+        behavior: impl SkillBehavior,
+    ) -> &mut Self;
+}
+
+impl RegisterSkillBehavior for App {
+    fn register_skill_behavior(
+        &mut self,
+        name: impl Into<String>,
+        behavior: impl SkillBehavior,
+    ) -> &mut Self {
+        self.init_resource::<SkillBehaviors>();
+        self.world_mut()
+            .resource_mut::<SkillBehaviors>()
+            .register(name, behavior);
+        self
+    }
+}
+
+// The skill stays where it was spawned and only plays its animation
+pub struct StaticBehavior;
+
+impl SkillBehavior for StaticBehavior {
+    fn spawn(&self, _skill: &mut EntityCommands, _ctx: &SkillSpawnContext) {}
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use crate::combat::{Health, Mana};
+use crate::settings::AccessibilitySettings;
+
+// How a cast interacts with other casts from the same caster. A new cast can
+// go off while a channel is active only if its priority is at least the
+// channel's, and it interrupts the channel when it does:
+// - instant casts wait for the channel to end
+// - starting another channel replaces the current one
+// - dashes always go off, cutting channels short
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Reflect)]
+pub enum CastKind {
+    #[default]
+    Instant,
+    // Lasts while its key is held, like beams
+    Channeled,
+    Dash,
+}
+
+impl CastKind {
+    pub fn priority(self) -> u8 {
+        match self {
+            CastKind::Instant => 1,
+            CastKind::Channeled => 2,
+            CastKind::Dash => 3,
+        }
+    }
+}
+
+// On a skill that is being channeled by `caster`. It lasts while `key` is
+// held (or until it is pressed again with toggled channels) and the caster
+// can pay `mana_per_second`, and ends when the caster takes damage.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Channel {
+    pub caster: Entity,
+    pub kind: CastKind,
+    pub key: Option<KeyCode>,
+    pub mana_per_second: f32,
+    // Caster health last frame, any drop interrupts the channel
+    pub last_health: Option<f32>,
+}
+
+pub fn update_channels(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    accessibility: Res<AccessibilitySettings>,
+    mut channels: Query<(Entity, &mut Channel)>,
+    mut casters: Query<(Option<&Health>, Option<&mut Mana>)>,
+) {
+    for (entity, mut channel) in channels.iter_mut() {
+        // In toggle mode the press that started the channel doesn't count
+        let released = channel.key.is_some_and(|key| {
+            if accessibility.hold_to_channel {
+                !keyboard_input.pressed(key)
+            } else {
+                keyboard_input.just_pressed(key) && !channel.is_added()
+            }
+        });
+        let Ok((health, mana)) = casters.get_mut(channel.caster) else {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::OwnerDied);
+            continue;
+        };
+        if released {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::Expired);
+            continue;
+        }
+        let health = health.map(|health| health.current);
+        if channel
+            .last_health
+            .zip(health)
+            .is_some_and(|(last, now)| now < last)
+        {
+            println!("Channel interrupted by damage");
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::Interrupted);
+            continue;
+        }
+        if let Some(mut mana) = mana {
+            if !mana.try_spend(channel.mana_per_second * time.delta_seconds()) {
+                println!("Channel ended, out of mana");
+                commands
+                    .entity(entity)
+                    .despawn_skill(DespawnReason::Interrupted);
+                continue;
+            }
+        }
+        channel.last_health = health;
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::beam::beam_transform;
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext};
+use crate::combat::SkillHitEvent;
+use crate::schedule::GameSet;
+use crate::Enemy;
+
+pub struct ChainBehaviorPlugin;
+
+impl Plugin for ChainBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ChainSkill>()
+            .register_type::<ChainBeam>()
+            .register_skill_behavior("chain", ChainBehavior)
+            .add_systems(
+                Update,
+                (
+                    (chain_on_hit, steer_chain_skills).in_set(GameSet::Simulation),
+                    fade_chain_beams.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+// Definition params: `bounces`, `range`, `speed`, `beam_width`, `beam_duration`.
+// The optional `beam` texture is stretched between consecutive targets.
+pub struct ChainBehavior;
+
+impl SkillBehavior for ChainBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert(ChainSkill {
+            bounces_left: definition.param("bounces", 3.0) as u32,
+            range: definition.param("range", 5.0),
+            speed: definition.param("speed", 8.0),
+            beam_width: definition.param("beam_width", 0.3),
+            beam_duration: definition.param("beam_duration", 0.25).max(0.0),
+            beam_image: definition.extra_image("beam"),
+            beam_alpha_mode: definition.alpha_mode(),
+            target: None,
+            hit: Vec::new(),
+        });
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ChainSkill {
+    pub bounces_left: u32,
+    pub range: f32,
+    pub speed: f32,
+    pub beam_width: f32,
+    pub beam_duration: f32,
+    pub beam_image: Option<Handle<Image>>,
+    pub beam_alpha_mode: AlphaMode,
+    pub target: Option<Entity>,
+    pub hit: Vec<Entity>,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ChainBeam {
+    lifetime: Timer,
+}
+
+fn nearest_unhit_enemy(
+    enemies: &Query<(Entity, &Transform), (With<Enemy>, Without<ChainSkill>)>,
+    chain: &ChainSkill,
+    from: Vec3,
+) -> Option<(Entity, Vec3)> {
+    enemies
+        .iter()
+        .filter(|(entity, _)| !chain.hit.contains(entity))
+        .map(|(entity, transform)| (entity, transform.translation))
+        .filter(|(_, position)| position.distance(from) <= chain.range)
+        .min_by(|(_, a), (_, b)| a.distance(from).total_cmp(&b.distance(from)))
+}
+
+fn steer_chain_skills(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut skills: Query<(Entity, &mut Transform, &mut ChainSkill)>,
+    enemies: Query<(Entity, &Transform), (With<Enemy>, Without<ChainSkill>)>,
+) {
+    for (entity, mut transform, mut chain) in skills.iter_mut() {
+        let current_target = chain
+            .target
+            .and_then(|target| enemies.get(target).ok())
+            .map(|(_, target_transform)| target_transform.translation);
+        let target_position = match current_target {
+            Some(position) => position,
+            None => match nearest_unhit_enemy(&enemies, &chain, transform.translation) {
+                Some((target, position)) => {
+                    chain.target = Some(target);
+                    position
+                }
+                None => {
+                    // Nothing (left) in range, the chain fizzles out
+                    commands
+                        .entity(entity)
+                        .despawn_skill(DespawnReason::Expired);
+                    continue;
+                }
+            },
+        };
+
+        let step = chain.speed * time.delta_seconds();
+        let to_target = target_position - transform.translation;
+        transform.translation += to_target.clamp_length_max(step);
+    }
+}
+
+fn chain_on_hit(
+    mut commands: Commands,
+    mut hits: EventReader<SkillHitEvent>,
+    mut skills: Query<(Entity, &mut Transform, &mut ChainSkill)>,
+    enemies: Query<(Entity, &Transform), (With<Enemy>, Without<ChainSkill>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut finished = Vec::new();
+    for hit in hits.read() {
+        if finished.contains(&hit.skill) {
+            continue;
+        }
+        let Ok((entity, mut transform, mut chain)) = skills.get_mut(hit.skill) else {
+            continue;
+        };
+        if chain.hit.contains(&hit.target) {
+            continue;
+        }
+        let Ok((_, target_transform)) = enemies.get(hit.target) else {
+            continue;
+        };
+        chain.hit.push(hit.target);
+
+        let from = target_transform.translation;
+        let next = if chain.bounces_left > 0 {
+            nearest_unhit_enemy(&enemies, &chain, from)
+        } else {
+            None
+        };
+        let Some((next_target, to)) = next else {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::Consumed);
+            finished.push(entity);
+            continue;
+        };
+
+        chain.bounces_left -= 1;
+        chain.target = Some(next_target);
+        transform.translation = to;
+
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.7, 0.9, 1.0),
+            base_color_texture: chain.beam_image.clone(),
+            alpha_mode: chain.beam_alpha_mode,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        });
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Rectangle::new(1.0, 1.0))),
+                material,
+                transform: beam_transform(from, to, chain.beam_width),
+                ..default()
+            },
+            ChainBeam {
+                lifetime: Timer::from_seconds(chain.beam_duration, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn fade_chain_beams(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ChainBeam)>,
+) {
+    for (entity, mut beam) in query.iter_mut() {
+        beam.lifetime.tick(time.delta());
+        if beam.lifetime.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
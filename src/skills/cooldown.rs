@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::SkillDefinition;
+use crate::time_scale::TimeScale;
+
+// Per-caster cooldowns, keyed by skill definition, plus the global cooldown
+// every cast starts unless its definition opts out with `off_global_cooldown`
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillCooldowns {
+    skills: HashMap<AssetId<SkillDefinition>, Timer>,
+    global: Option<Timer>,
+    pub global_duration: f32,
+}
+
+impl Default for SkillCooldowns {
+    fn default() -> Self {
+        Self {
+            skills: HashMap::default(),
+            global: None,
+            global_duration: 0.4,
+        }
+    }
+}
+
+impl SkillCooldowns {
+    pub fn is_ready(&self, skill: AssetId<SkillDefinition>) -> bool {
+        self.skills.get(&skill).is_none_or(Timer::finished)
+    }
+
+    pub fn remaining(&self, skill: AssetId<SkillDefinition>) -> f32 {
+        self.skills
+            .get(&skill)
+            .map_or(0.0, |timer| timer.remaining_secs())
+    }
+
+    pub fn start(&mut self, skill: AssetId<SkillDefinition>, seconds: f32) {
+        if seconds > 0.0 {
+            self.skills
+                .insert(skill, Timer::from_seconds(seconds, TimerMode::Once));
+        }
+    }
+
+    pub fn global_remaining(&self) -> f32 {
+        self.global
+            .as_ref()
+            .map_or(0.0, |timer| timer.remaining_secs())
+    }
+
+    // `multiplier` is the caster's cooldown reduction
+    pub fn start_global(&mut self, multiplier: f32) {
+        let seconds = self.global_duration * multiplier;
+        if seconds > 0.0 {
+            self.global = Some(Timer::from_seconds(seconds, TimerMode::Once));
+        }
+    }
+}
+
+// Casters slowed by a time zone recover slower too
+pub fn tick_cooldowns(
+    time: Res<Time>,
+    mut query: Query<(&mut SkillCooldowns, Option<&TimeScale>)>,
+) {
+    for (mut cooldowns, time_scale) in query.iter_mut() {
+        let delta = time.delta().mul_f32(TimeScale::of(time_scale));
+        for timer in cooldowns.skills.values_mut() {
+            timer.tick(delta);
+        }
+        if let Some(global) = cooldowns.global.as_mut() {
+            global.tick(delta);
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::render::texture::ImageAddressMode;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::cast::CastKind;
+use super::light::SkillLight;
+use super::on_hit::OnHitEffect;
+use super::orientation::SkillOrientation;
+use super::pattern::SpawnPattern;
+use super::phase::SkillClips;
+use super::rank::RankModifier;
+use super::texture::{full_mip_levels, load_frame, load_texture, TextureImport, TextureSampling};
+use crate::animation::{Animator, ClipFrames, FrameRange};
+use crate::cinematic::CameraSequence;
+use crate::hit_volume::HitShape;
+
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct SkillDefinition {
+    pub name: String,
+    // Shown in the spellbook and its tooltips
+    #[serde(default)]
+    pub description: String,
+    pub texture: String,
+    // Image shown on the hotbar and in the spellbook. Without one, the icon is
+    // cut from the sheet at `icon_frame`, by default the middle of the loop.
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub icon_frame: Option<usize>,
+    pub frame_size: u32,
+    pub columns: u32,
+    pub rows: u32,
+    // Pixels between frames and around the sheet, for sheets exported with
+    // padding or extruded edges
+    #[serde(default)]
+    pub frame_padding: u32,
+    #[serde(default)]
+    pub sheet_margin: u32,
+    // Texels trimmed off each side of a frame when sampling so filtering never
+    // reaches into the neighbouring frame
+    #[serde(default = "default_frame_inset")]
+    pub frame_inset: f32,
+    // Pixel rects `(x, y, width, height)` of each frame for sheets packed
+    // without a grid, used instead of `columns` and `rows` when set. Frames
+    // may differ in size; the sheet is assumed to end at the outermost rect
+    // unless `sheet_size` says otherwise.
+    #[serde(default)]
+    pub frame_rects: Vec<(u32, u32, u32, u32)>,
+    #[serde(default)]
+    pub sheet_size: Option<(u32, u32)>,
+    // Seconds per frame at normal speed, an instance's `AnimationSpeed` scales it
+    pub frame_duration: f32,
+    // Cells the sheet animates through, for sheets with blank cells at either
+    // end. `frames` lists them explicitly instead, in play order.
+    #[serde(default)]
+    pub start_frame: usize,
+    #[serde(default)]
+    pub end_frame: Option<usize>,
+    #[serde(default)]
+    pub frames: Option<Vec<usize>>,
+    #[serde(default)]
+    pub clips: SkillClips,
+    pub lifetime: f32,
+    pub scale: f32,
+    pub spawn_offset: (f32, f32, f32),
+    #[serde(default)]
+    pub orientation: SkillOrientation,
+    // Caster socket the effect spawns on and follows, `spawn_offset` then
+    // being relative to the socket. Casters without it spawn as usual.
+    #[serde(default)]
+    pub socket: Option<String>,
+    #[serde(default = "default_hit_radius")]
+    pub hit_radius: f32,
+    // Anything other than a sphere of `hit_radius`, e.g. cones for slashes
+    #[serde(default)]
+    pub hit_shape: Option<HitShape>,
+    // Seconds before the same skill can hit the same target again
+    #[serde(default = "default_hit_interval")]
+    pub hit_interval: f32,
+    #[serde(default)]
+    pub damage: f32,
+    #[serde(default)]
+    pub cooldown: f32,
+    #[serde(default)]
+    pub cast: CastKind,
+    // Drained from the caster's `Mana` while channeling, the channel ending
+    // when it runs out
+    #[serde(default)]
+    pub mana_per_second: f32,
+    // Several instances per cast, e.g. a fan of projectiles
+    #[serde(default)]
+    pub pattern: SpawnPattern,
+    // Neither blocked by nor starting the global cooldown
+    #[serde(default)]
+    pub off_global_cooldown: bool,
+    // Shade the sprite with scene lighting instead of drawing it unlit
+    #[serde(default)]
+    pub lit: bool,
+    // Draw with `SkillMaterial`, which keeps non-square and variable-size
+    // frames at their own proportions instead of stretching them to the quad
+    #[serde(default)]
+    pub custom_shader: bool,
+    // Follow the terrain height instead of keeping the height it was cast at
+    #[serde(default)]
+    pub grounded: bool,
+    // Upgrades unlocked one at a time with skill points
+    #[serde(default)]
+    pub ranks: Vec<RankModifier>,
+    #[serde(default)]
+    pub on_hit: Vec<OnHitEffect>,
+    // Elements the skill carries, for reactions with other skills' tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Name of a behavior registered in `SkillBehaviors`
+    #[serde(default = "default_behavior")]
+    pub behavior: String,
+    // Free-form numeric parameters read by the behavior
+    #[serde(default)]
+    pub params: HashMap<String, f32>,
+    // Extra named textures used by behaviors, e.g. the beam drawn by chains.
+    // These are sampled with repeat addressing so they can be tiled.
+    #[serde(default)]
+    pub textures: HashMap<String, String>,
+    // How the sheet and extra textures are filtered, `Nearest` keeps pixel art crisp
+    #[serde(default)]
+    pub sampling: TextureSampling,
+    // Convert the textures to premultiplied alpha on load and blend them as
+    // such, which avoids dark fringes around soft edges
+    #[serde(default)]
+    pub premultiply_alpha: bool,
+    // Point light the skill carries, making it illuminate the scene
+    #[serde(default)]
+    pub light: Option<SkillLight>,
+    // Played when the player casts the skill, meant for ultimates
+    #[serde(default)]
+    pub camera: Option<CameraSequence>,
+    // Outlives the level it was cast in, for auras and the like. Every skill
+    // still ends on game over.
+    #[serde(default)]
+    pub persistent: bool,
+    #[serde(skip)]
+    pub image: Handle<Image>,
+    #[serde(skip)]
+    pub images: HashMap<String, Handle<Image>>,
+    #[serde(skip)]
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+    // Default for definitions made at runtime, which show their sheet instead
+    #[serde(skip)]
+    pub icon_image: Handle<Image>,
+}
+
+fn default_behavior() -> String {
+    "static".to_string()
+}
+
+fn default_hit_radius() -> f32 {
+    0.5
+}
+
+fn default_hit_interval() -> f32 {
+    0.5
+}
+
+// Half a texel keeps bilinear filtering inside the frame
+fn default_frame_inset() -> f32 {
+    0.5
+}
+
+impl SkillDefinition {
+    pub fn spawn_offset(&self) -> Vec3 {
+        Vec3::new(
+            self.spawn_offset.0,
+            self.spawn_offset.1,
+            self.spawn_offset.2,
+        )
+    }
+
+    pub fn hit_shape(&self) -> HitShape {
+        self.hit_shape.unwrap_or(HitShape::Sphere {
+            radius: self.hit_radius,
+        })
+    }
+
+    pub fn param(&self, name: &str, default: f32) -> f32 {
+        self.params.get(name).copied().unwrap_or(default)
+    }
+
+    pub fn extra_image(&self, name: &str) -> Option<Handle<Image>> {
+        self.images.get(name).cloned()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        if self.frame_rects.is_empty() {
+            (self.columns * self.rows) as usize
+        } else {
+            self.frame_rects.len()
+        }
+    }
+
+    pub fn atlas_layout(&self) -> TextureAtlasLayout {
+        if self.frame_rects.is_empty() {
+            return TextureAtlasLayout::from_grid(
+                UVec2::splat(self.frame_size),
+                self.columns,
+                self.rows,
+                Some(UVec2::splat(self.frame_padding)),
+                Some(UVec2::splat(self.sheet_margin)),
+            );
+        }
+        let rects: Vec<URect> = self
+            .frame_rects
+            .iter()
+            .map(|&(x, y, width, height)| URect::new(x, y, x + width, y + height))
+            .collect();
+        let size = self.sheet_size.map_or_else(
+            || {
+                rects
+                    .iter()
+                    .fold(UVec2::ZERO, |size, rect| size.max(rect.max))
+            },
+            |(width, height)| UVec2::new(width, height),
+        );
+        let mut layout = TextureAtlasLayout::new_empty(size);
+        for rect in rects {
+            layout.add_texture(rect);
+        }
+        layout
+    }
+
+    // Frames played when the clips don't say otherwise
+    pub fn sheet_frames(&self) -> ClipFrames {
+        match &self.frames {
+            Some(frames) => ClipFrames::List(frames.clone()),
+            None => ClipFrames::Range(FrameRange {
+                start: self.start_frame,
+                end: self
+                    .end_frame
+                    .unwrap_or(self.frame_count().saturating_sub(1)),
+            }),
+        }
+    }
+
+    // Frame the icon is cut from, the middle of the loop unless set, which is
+    // where most effects are at their fullest
+    pub fn representative_frame(&self) -> usize {
+        let last = self.frame_count().saturating_sub(1);
+        if let Some(frame) = self.icon_frame {
+            return frame.min(last);
+        }
+        let looping = self.clips.looping.clone();
+        let frames = looping.unwrap_or_else(|| self.sheet_frames()).resolve(last);
+        frames[frames.len() / 2]
+    }
+
+    pub fn animator(&self) -> Animator {
+        self.clips.animator(
+            self.frame_count(),
+            &self.sheet_frames(),
+            self.frame_duration,
+        )
+    }
+
+    // Blend state matching how the textures were imported
+    pub fn alpha_mode(&self) -> AlphaMode {
+        if self.premultiply_alpha {
+            AlphaMode::Premultiplied
+        } else {
+            AlphaMode::Blend
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SkillDefinitionLoader;
+
+#[derive(Debug, Error)]
+pub enum SkillDefinitionLoaderError {
+    #[error("could not read skill definition: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse skill definition: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("camera keyframe time_scale must be finite and not negative, got {0}")]
+    InvalidTimeScale(f32),
+    #[error("frame_duration must be finite and positive, got {0}")]
+    InvalidFrameDuration(f32),
+    #[error("{field} must be finite and not negative, got {value}")]
+    InvalidDuration { field: &'static str, value: f32 },
+}
+
+impl AssetLoader for SkillDefinitionLoader {
+    type Asset = SkillDefinition;
+    type Settings = ();
+    type Error = SkillDefinitionLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<SkillDefinition, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut definition: SkillDefinition = ron::de::from_bytes(&bytes)?;
+        // Goes straight into `Time<Virtual>`, which panics on these
+        let keyframes = definition
+            .camera
+            .iter()
+            .flat_map(|camera| &camera.keyframes);
+        for keyframe in keyframes {
+            if !keyframe.time_scale.is_finite() || keyframe.time_scale < 0.0 {
+                return Err(SkillDefinitionLoaderError::InvalidTimeScale(
+                    keyframe.time_scale,
+                ));
+            }
+        }
+        // These become timers, a zero frame time would also never advance
+        // the animation
+        if !definition.frame_duration.is_finite() || definition.frame_duration <= 0.0 {
+            return Err(SkillDefinitionLoaderError::InvalidFrameDuration(
+                definition.frame_duration,
+            ));
+        }
+        for (field, value) in [
+            ("lifetime", definition.lifetime),
+            ("cooldown", definition.cooldown),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(SkillDefinitionLoaderError::InvalidDuration { field, value });
+            }
+        }
+
+        let sampling = definition.sampling;
+        let mipmaps = |levels: u32| if sampling.mipmaps { levels } else { 1 };
+        // Stop while a frame is still a few texels wide, smaller levels would
+        // blend neighbouring frames together
+        let sheet_levels = full_mip_levels(definition.frame_size / 4, definition.frame_size / 4);
+        definition.image = load_texture(
+            load_context,
+            &definition.texture,
+            "sheet".to_string(),
+            TextureImport {
+                sampler: sampling.descriptor(ImageAddressMode::ClampToEdge),
+                premultiply_alpha: definition.premultiply_alpha,
+                mip_levels: mipmaps(sheet_levels),
+            },
+        )
+        .await;
+        for (name, path) in definition.textures.iter() {
+            let handle = load_texture(
+                load_context,
+                path,
+                format!("texture/{}", name),
+                TextureImport {
+                    sampler: sampling.descriptor(ImageAddressMode::Repeat),
+                    premultiply_alpha: definition.premultiply_alpha,
+                    mip_levels: mipmaps(u32::MAX),
+                },
+            )
+            .await;
+            definition.images.insert(name.clone(), handle);
+        }
+        for effect in definition.on_hit.iter_mut() {
+            if let OnHitEffect::SpawnSkill { skill, handle } = effect {
+                *handle = load_context.load(skill.clone());
+            }
+        }
+        let layout = definition.atlas_layout();
+        definition.icon_image = match &definition.icon {
+            Some(path) => load_context.load(path.clone()),
+            None => {
+                let frame = layout.textures.get(definition.representative_frame());
+                let icon = match frame {
+                    Some(&rect) => {
+                        load_frame(
+                            load_context,
+                            &definition.texture,
+                            rect,
+                            "icon".to_string(),
+                            sampling.descriptor(ImageAddressMode::ClampToEdge),
+                        )
+                        .await
+                    }
+                    None => None,
+                };
+                icon.unwrap_or_default()
+            }
+        };
+        definition.atlas_layout = load_context.add_labeled_asset("atlas".to_string(), layout);
+
+        Ok(definition)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["skill.ron"]
+    }
+}
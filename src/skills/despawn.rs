@@ -0,0 +1,156 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::cast::Channel;
+use super::phase::DESPAWN_CLIP;
+use super::{SkillDefinition, WaterSkill};
+use crate::animation::Animator;
+use crate::respawn::PlayerDied;
+
+// Why a skill ended, so outros, refunds and statistics can tell a skill that
+// ran its course from one that was cut short
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DespawnReason {
+    // Its lifetime ran out, or it finished on its own
+    #[default]
+    Expired,
+    // Used up by what it did, e.g. a projectile's last pierce or a reaction
+    Consumed,
+    // Removed by something else, e.g. a cleanse
+    Dispelled,
+    // Its caster, or what it was attached to, died or is gone
+    OwnerDied,
+    // Ran into a wall or the ground with nothing left to bounce with
+    Blocked,
+    // Cut off by damage, running out of mana or another cast
+    Interrupted,
+    // Made room for a newer one, e.g. past a summon cap
+    Replaced,
+    // The game left the state or level it belonged to
+    StateChanged,
+}
+
+impl DespawnReason {
+    // Anything other than the skill running its course
+    pub fn is_cancelled(self) -> bool {
+        !matches!(self, DespawnReason::Expired | DespawnReason::Consumed)
+    }
+}
+
+// Marks a skill for removal at the end of the frame. Every system that ends a
+// skill goes through this instead of despawning it directly.
+#[derive(Component)]
+pub struct DespawnSkill(pub DespawnReason);
+
+// Left on a skill playing its despawn clip after being cancelled, so the
+// reason survives until the clip ends
+#[derive(Component)]
+pub struct Cancelled(pub DespawnReason);
+
+// Sent right before a skill and its children are despawned, for anything that
+// needs to release what it handed out to the skill
+#[derive(Event)]
+pub struct SkillDespawned {
+    pub skill: Entity,
+    pub definition: Option<Handle<SkillDefinition>>,
+    pub position: Vec3,
+    pub reason: DespawnReason,
+}
+
+// Ends a skill early. Skills with a despawn clip play it out first, the rest
+// are removed at the end of the frame like any other.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CancelSkill {
+    pub skill: Entity,
+    pub reason: DespawnReason,
+}
+
+pub trait DespawnSkillExt {
+    fn despawn_skill(&mut self, reason: DespawnReason) -> &mut Self;
+}
+
+impl DespawnSkillExt for EntityCommands<'_> {
+    fn despawn_skill(&mut self, reason: DespawnReason) -> &mut Self {
+        // The skill may already be gone if its owner despawned it this frame,
+        // and the first system to end it in a frame gives the reason
+        self.add(move |entity: Entity, world: &mut World| {
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                if !entity.contains::<DespawnSkill>() {
+                    entity.insert(DespawnSkill(reason));
+                }
+            }
+        })
+    }
+}
+
+pub fn cancel_skills(
+    mut commands: Commands,
+    mut cancels: EventReader<CancelSkill>,
+    mut skills: Query<Option<&mut Animator>, (With<WaterSkill>, Without<Cancelled>)>,
+) {
+    for cancel in cancels.read() {
+        let Ok(animator) = skills.get_mut(cancel.skill) else {
+            continue;
+        };
+        match animator {
+            Some(mut animator) if animator.has_clip(DESPAWN_CLIP) => {
+                animator.play(DESPAWN_CLIP);
+                commands
+                    .entity(cancel.skill)
+                    .insert(Cancelled(cancel.reason));
+            }
+            _ => {
+                commands.entity(cancel.skill).despawn_skill(cancel.reason);
+            }
+        }
+    }
+}
+
+// Channels end with their caster; other skills are left to finish
+pub fn cancel_dead_players_channels(
+    mut died: EventReader<PlayerDied>,
+    channels: Query<(Entity, &Channel)>,
+    mut cancels: EventWriter<CancelSkill>,
+) {
+    for death in died.read() {
+        for (skill, _) in channels
+            .iter()
+            .filter(|(_, channel)| channel.caster == death.player)
+        {
+            cancels.send(CancelSkill {
+                skill,
+                reason: DespawnReason::OwnerDied,
+            });
+        }
+    }
+}
+
+pub fn despawn_marked_skills(
+    mut commands: Commands,
+    skills: Query<(
+        Entity,
+        &DespawnSkill,
+        Option<&WaterSkill>,
+        Option<&GlobalTransform>,
+    )>,
+    children: Query<&Children>,
+    sinks: Query<&AudioSink>,
+    mut despawned: EventWriter<SkillDespawned>,
+) {
+    for (entity, despawn, skill, transform) in skills.iter() {
+        // Sinks keep playing until their entity is dropped, stop them right away
+        for part in std::iter::once(entity).chain(children.iter_descendants(entity)) {
+            if let Ok(sink) = sinks.get(part) {
+                sink.stop();
+            }
+        }
+        despawned.send(SkillDespawned {
+            skill: entity,
+            definition: skill.map(|skill| skill.definition.clone()),
+            position: transform.map_or(Vec3::ZERO, GlobalTransform::translation),
+            reason: despawn.0,
+        });
+        commands.entity(entity).despawn_recursive();
+        trace!("Skill despawned ({:?})", despawn.0);
+    }
+}
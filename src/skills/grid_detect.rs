@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+
+// Pixels at or below this alpha count as empty, so faint export noise in a
+// gutter doesn't join two frames
+const ALPHA_THRESHOLD: u8 = 8;
+
+// Guesses the columns and rows of a sheet from the fully transparent lines
+// between its frames. Frames are assumed to be laid out on an even grid with
+// each one's drawing inside its own cell; a frame with gaps in it is fine as
+// long as the pieces stay in the cell. Returns `None` when the sheet has no
+// gutters to go by, or isn't 8-bit RGBA.
+pub fn detect_grid(image: &Image) -> Option<(u32, u32)> {
+    let size = image.size();
+    let (width, height) = (size.x as usize, size.y as usize);
+    let alpha = alpha_channel(image)?;
+    if width == 0 || height == 0 || alpha.len() < width * height {
+        return None;
+    }
+    let filled = |x: usize, y: usize| alpha[y * width + x] > ALPHA_THRESHOLD;
+    let columns_filled: Vec<bool> = (0..width)
+        .map(|x| (0..height).any(|y| filled(x, y)))
+        .collect();
+    let rows_filled: Vec<bool> = (0..height)
+        .map(|y| (0..width).any(|x| filled(x, y)))
+        .collect();
+    let grid = (cell_count(&columns_filled)?, cell_count(&rows_filled)?);
+    (grid != (1, 1)).then_some(grid)
+}
+
+fn alpha_channel(image: &Image) -> Option<Vec<u8>> {
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            Some(image.data.chunks_exact(4).map(|pixel| pixel[3]).collect())
+        }
+        _ => None,
+    }
+}
+
+// Start and end (exclusive) of each run of filled lines
+fn runs(filled: &[bool]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (index, filled) in filled.iter().enumerate() {
+        match (start, *filled) {
+            (None, true) => start = Some(index),
+            (Some(begin), false) => {
+                runs.push((begin, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        runs.push((begin, filled.len()));
+    }
+    runs
+}
+
+// The most cells the line can be split into evenly with every run of drawing
+// inside a single cell, so a frame split into pieces by its own gaps just
+// lowers the count until they share one. Empty cells are allowed before and
+// after the drawn ones, e.g. a blank first frame, but not between them, and
+// there have to be fewer empty cells than drawn ones, or a lone small drawing
+// could be cut into any number of cells.
+fn cell_count(filled: &[bool]) -> Option<u32> {
+    let runs = runs(filled);
+    let length = filled.len();
+    (1..=length)
+        .rev()
+        .filter(|cells| length.is_multiple_of(*cells))
+        .find(|cells| {
+            let cell = length / cells;
+            let cell_of = |index: usize| index / cell;
+            if !runs
+                .iter()
+                .all(|(start, end)| cell_of(*start) == cell_of(end - 1))
+            {
+                return false;
+            }
+            let (Some((first, _)), Some((_, last))) = (runs.first(), runs.last()) else {
+                return false;
+            };
+            let (first, last) = (cell_of(*first), cell_of(last - 1));
+            let drawn = last - first + 1;
+            let contiguous =
+                (first..=last).all(|index| runs.iter().any(|(start, _)| cell_of(*start) == index));
+            contiguous && cells - drawn < drawn
+        })
+        .map(|cells| cells as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_asset::RenderAssetUsages;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    // Transparent sheet with the given `(x, y, width, height)` rects drawn opaque
+    fn sheet(width: u32, height: u32, rects: &[(u32, u32, u32, u32)]) -> Image {
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for &(left, top, rect_width, rect_height) in rects {
+            for y in top..top + rect_height {
+                for x in left..left + rect_width {
+                    let pixel = ((y * width + x) * 4) as usize;
+                    data[pixel..pixel + 4].copy_from_slice(&[255, 255, 255, 255]);
+                }
+            }
+        }
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+
+    // A `columns` by `rows` grid of `cell` sized frames, each drawn `inset`
+    // pixels inside its cell
+    fn grid(columns: u32, rows: u32, cell: u32, inset: u32) -> Vec<(u32, u32, u32, u32)> {
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let size = cell - inset * 2;
+                (column * cell + inset, row * cell + inset, size, size)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gutters_give_the_grid() {
+        let image = sheet(64, 32, &grid(4, 2, 16, 2));
+        assert_eq!(detect_grid(&image), Some((4, 2)));
+    }
+
+    #[test]
+    fn an_empty_first_cell_still_counts() {
+        let frames = grid(4, 1, 16, 2);
+        let image = sheet(64, 16, &frames[1..]);
+        assert_eq!(detect_grid(&image), Some((4, 1)));
+    }
+
+    #[test]
+    fn frames_split_by_transparent_columns_stay_whole() {
+        // Each 20 pixel frame is a thin stroke, a gap, then a wider one
+        let rects: Vec<_> = (0..3)
+            .flat_map(|frame| [(frame * 20 + 2, 2, 3, 16), (frame * 20 + 8, 2, 9, 16)])
+            .collect();
+        let image = sheet(60, 20, &rects);
+        assert_eq!(detect_grid(&image), Some((3, 1)));
+    }
+
+    #[test]
+    fn sheets_without_gutters_are_not_guessed() {
+        let image = sheet(64, 32, &[(0, 0, 64, 32)]);
+        assert_eq!(detect_grid(&image), None);
+    }
+
+    #[test]
+    fn a_small_lone_drawing_is_one_frame() {
+        let image = sheet(64, 64, &[(2, 2, 4, 4)]);
+        assert_eq!(detect_grid(&image), None);
+    }
+
+    #[test]
+    fn runs_that_straddle_cells_lower_the_count() {
+        // Four runs, but the third crosses the middle of the line
+        let mut filled = vec![false; 16];
+        for index in [1, 2, 5, 6, 7, 8, 12, 13] {
+            filled[index] = true;
+        }
+        assert_eq!(cell_count(&filled), Some(1));
+        assert_eq!(cell_count(&[true; 8]), Some(1));
+    }
+}
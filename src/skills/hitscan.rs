@@ -0,0 +1,212 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::beam::beam_transform;
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use super::pattern::SkillFacing;
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext};
+use crate::combat::{Hurtbox, ManualHitDetection, SkillHitEvent};
+use crate::cursor::CursorGrab;
+use crate::destructible::Destructible;
+use crate::hit_volume::ray_sphere;
+use crate::schedule::GameSet;
+use crate::screen_space::ScreenProjection;
+use crate::{Enemy, MainCamera, Player};
+
+pub struct HitscanBehaviorPlugin;
+
+impl Plugin for HitscanBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HitscanSkill>()
+            .register_type::<HitscanTracer>()
+            .register_skill_behavior("hitscan", HitscanBehavior)
+            .add_systems(
+                Update,
+                (
+                    fire_hitscans.in_set(GameSet::Simulation),
+                    fade_hitscan_tracers.in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+// Definition params: `range`, `aim_camera` (1 to aim players' shots at the
+// cursor, or the crosshair while it is grabbed, instead of along their
+// facing), `tracer_width`, `tracer_duration`. The shot lands the frame it is
+// cast; the skill itself becomes the impact sprite at the hit point, and the
+// optional `tracer` texture is stretched from the muzzle to it.
+pub struct HitscanBehavior;
+
+impl SkillBehavior for HitscanBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert(ManualHitDetection).insert(HitscanSkill {
+            caster: ctx.caster,
+            range: definition.param("range", 12.0),
+            aim_camera: definition.param("aim_camera", 0.0) > 0.0,
+            tracer_width: definition.param("tracer_width", 0.08),
+            tracer_duration: definition.param("tracer_duration", 0.1),
+            tracer_image: definition.extra_image("tracer"),
+            tracer_alpha_mode: definition.alpha_mode(),
+            target: None,
+        });
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct HitscanSkill {
+    pub caster: Entity,
+    pub range: f32,
+    pub aim_camera: bool,
+    pub tracer_width: f32,
+    pub tracer_duration: f32,
+    pub tracer_image: Option<Handle<Image>>,
+    pub tracer_alpha_mode: AlphaMode,
+    // What the shot hit, once fired
+    pub target: Option<Entity>,
+}
+
+// Thins out over its lifetime, then goes away
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct HitscanTracer {
+    width: f32,
+    lifetime: Timer,
+}
+
+// Where a player's camera-aimed shot goes: toward the point under the cursor
+// at the muzzle's height, so the shot stays level like the facing-aimed ones
+fn camera_aim(
+    origin: Vec3,
+    grab: Option<&CursorGrab>,
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) -> Option<Vec3> {
+    let window = windows.get_single().ok()?;
+    let (camera, camera_transform) = cameras.get_single().ok()?;
+    let cursor = if grab.is_some_and(|grab| grab.grabbed) {
+        Some(window.size() / 2.0)
+    } else {
+        window.cursor_position()
+    }?;
+    let aimed =
+        ScreenProjection::new(camera, camera_transform)?.screen_to_ground(cursor, origin.y)?;
+    (aimed - origin).with_y(0.0).try_normalize()
+}
+
+// Casts each new shot's ray once, damages the nearest target along it and
+// leaves a tracer behind
+fn fire_hitscans(
+    mut commands: Commands,
+    grab: Option<Res<CursorGrab>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    casters: Query<&GlobalTransform>,
+    players: Query<(), With<Player>>,
+    mut shots: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut HitscanSkill,
+            Option<&SkillFacing>,
+        ),
+        Added<HitscanSkill>,
+    >,
+    targets: Query<(Entity, &GlobalTransform, &Hurtbox), Or<(With<Enemy>, With<Destructible>)>>,
+    mut hits: EventWriter<SkillHitEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, mut transform, mut shot, spawn_facing) in shots.iter_mut() {
+        let origin = transform.translation;
+        let aimed = if shot.aim_camera && players.contains(shot.caster) {
+            camera_aim(origin, grab.as_deref(), &windows, &cameras)
+        } else {
+            None
+        };
+        let caster_forward = casters
+            .get(shot.caster)
+            .ok()
+            .and_then(|caster| caster.forward().with_y(0.0).try_normalize());
+        // Fan and ring shots keep their spread around the aimed line, turned
+        // off it by as much as their pattern turned them off the caster's facing
+        let aimed = aimed.map(|aimed| match (spawn_facing, caster_forward) {
+            (Some(facing), Some(forward)) => {
+                let yaw = forward.cross(facing.0).y.atan2(forward.dot(facing.0));
+                Quat::from_rotation_y(yaw) * aimed
+            }
+            _ => aimed,
+        });
+        let direction = aimed
+            .or_else(|| spawn_facing.map(|facing| facing.0))
+            .or(caster_forward)
+            .unwrap_or(Vec3::NEG_Z);
+
+        let ray = Ray3d::new(origin, direction);
+        let hit = targets
+            .iter()
+            .filter(|(target, ..)| *target != shot.caster)
+            .filter_map(|(target, target_transform, hurtbox)| {
+                ray_sphere(ray, target_transform.translation(), hurtbox.radius)
+                    .map(|distance| (target, distance))
+            })
+            .filter(|(_, distance)| *distance <= shot.range)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let end = ray.get_point(hit.map_or(shot.range, |(_, distance)| distance));
+
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.8, 0.95, 1.0),
+            base_color_texture: shot.tracer_image.clone(),
+            alpha_mode: shot.tracer_alpha_mode,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        });
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(Rectangle::new(1.0, 1.0))),
+                material,
+                transform: beam_transform(origin, end, shot.tracer_width),
+                ..default()
+            },
+            HitscanTracer {
+                width: shot.tracer_width,
+                lifetime: Timer::from_seconds(shot.tracer_duration, TimerMode::Once),
+            },
+        ));
+
+        match hit {
+            Some((target, _)) => {
+                shot.target = Some(target);
+                transform.translation = end;
+                hits.send(SkillHitEvent {
+                    skill: entity,
+                    target,
+                });
+            }
+            // Nothing to show an impact on
+            None => {
+                commands
+                    .entity(entity)
+                    .despawn_skill(DespawnReason::Expired);
+            }
+        }
+    }
+}
+
+fn fade_hitscan_tracers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut tracers: Query<(Entity, &mut Transform, &mut HitscanTracer)>,
+) {
+    for (entity, mut transform, mut tracer) in tracers.iter_mut() {
+        tracer.lifetime.tick(time.delta());
+        if tracer.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.scale.y = tracer.width * tracer.lifetime.fraction_remaining();
+    }
+}
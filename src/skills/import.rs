@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::texture::{CompressedImageFormats, ImageAddressMode, ImageSampler, ImageType};
+
+use super::grid_detect::detect_grid;
+use super::texture::TextureSampling;
+use super::{SkillBindings, SkillDefinition, SkillRegistry};
+use crate::console::{Console, RegisterConsoleCommand};
+use crate::schedule::GameSet;
+
+// Casts whatever sheet was last dropped onto the window
+pub const AUDITION_KEY: KeyCode = KeyCode::Digit9;
+// Registry ID of the auditioned skill, so `cast dropped` works too
+const AUDITION_ID: &str = "dropped";
+// Square frames smaller than this are more likely a wrong guess than a sheet
+const MIN_GUESSED_FRAME: u32 = 16;
+const DEFAULT_FRAME_DURATION: f32 = 0.08;
+
+// The sheet last dropped onto the window, kept so it can be sliced again
+// with the `sheet` console command
+#[derive(Resource)]
+struct DroppedSheet {
+    name: String,
+    size: UVec2,
+    image: Handle<Image>,
+    handle: Handle<SkillDefinition>,
+}
+
+// Drop a PNG onto the window to try it out as a skill: the grid is detected
+// from the gaps between frames or else guessed, the console opens to say how to correct it, and `AUDITION_KEY` casts it
+pub struct SheetImportPlugin;
+
+impl Plugin for SheetImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_console_command(
+            "sheet",
+            "sheet <columns> <rows> [frame seconds]",
+            slice_dropped_sheet,
+        )
+        .register_console_command("grid", "grid <skill>", detect_skill_grid)
+        .add_systems(Update, import_dropped_sheets.in_set(GameSet::Input));
+    }
+}
+
+// For sheets without gutters: square frames as tall as the sheet or as wide
+// as it, else the largest square that tiles it both ways
+fn guess_grid(size: UVec2) -> (u32, u32) {
+    if size.y > 0 && size.x.is_multiple_of(size.y) {
+        return (size.x / size.y, 1);
+    }
+    if size.x > 0 && size.y.is_multiple_of(size.x) {
+        return (1, size.y / size.x);
+    }
+    let (mut a, mut b) = (size.x, size.y);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a >= MIN_GUESSED_FRAME {
+        (size.x / a, size.y / a)
+    } else {
+        (1, 1)
+    }
+}
+
+// Built from RON like the files are, so every other field gets the same
+// default a hand-written definition would
+fn sheet_definition(
+    name: &str,
+    size: UVec2,
+    columns: u32,
+    rows: u32,
+    frame_duration: f32,
+) -> Result<SkillDefinition, String> {
+    if columns == 0 || rows == 0 || !size.x.is_multiple_of(columns) || !size.y.is_multiple_of(rows)
+    {
+        return Err(format!(
+            "{}x{} doesn't divide a {}x{} sheet evenly",
+            columns, rows, size.x, size.y
+        ));
+    }
+    let frame = UVec2::new(size.x / columns, size.y / rows);
+    // Non-square frames are listed as rects and drawn with `SkillMaterial`,
+    // which keeps their proportions
+    let layout = if frame.x == frame.y {
+        format!(
+            "frame_size: {}, columns: {}, rows: {}",
+            frame.x, columns, rows
+        )
+    } else {
+        let rects: Vec<String> = (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    format!(
+                        "({}, {}, {}, {})",
+                        column * frame.x,
+                        row * frame.y,
+                        frame.x,
+                        frame.y
+                    )
+                })
+            })
+            .collect();
+        format!(
+            "frame_size: {}, columns: {}, rows: {}, frame_rects: [{}], \
+             sheet_size: Some(({}, {})), custom_shader: true",
+            frame.x.max(frame.y),
+            columns,
+            rows,
+            rects.join(", "),
+            size.x,
+            size.y
+        )
+    };
+    let ron = format!(
+        "(name: {:?}, texture: \"\", {}, frame_duration: {}, lifetime: {}, \
+         scale: 1.5, spawn_offset: (0.0, 1.0, -1.0))",
+        name,
+        layout,
+        frame_duration,
+        frame_duration * (columns * rows) as f32,
+    );
+    ron::de::from_str(&ron).map_err(|error| error.to_string())
+}
+
+fn import_dropped_sheets(
+    mut commands: Commands,
+    mut drops: EventReader<FileDragAndDrop>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut definitions: ResMut<Assets<SkillDefinition>>,
+    mut registry: ResMut<SkillRegistry>,
+    mut bindings: ResMut<SkillBindings>,
+    mut console: ResMut<Console>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        match import_sheet(path_buf, &mut images) {
+            Ok((name, size, image)) => {
+                let (columns, rows) = images
+                    .get(&image)
+                    .and_then(detect_grid)
+                    .unwrap_or_else(|| guess_grid(size));
+                let definition =
+                    match sheet_definition(&name, size, columns, rows, DEFAULT_FRAME_DURATION) {
+                        Ok(definition) => definition,
+                        Err(error) => {
+                            console.print(format!("Could not import {}: {}", name, error));
+                            continue;
+                        }
+                    };
+                let handle = definitions.add(with_sheet(definition, &image, &mut layouts));
+                registry.register(AUDITION_ID, handle.clone());
+                bindings.0.retain(|(key, _)| *key != AUDITION_KEY);
+                bindings.0.push((AUDITION_KEY, handle.clone()));
+                console.print(format!(
+                    "Imported {} as {}x{} frames, press {:?} to cast it",
+                    name, columns, rows, AUDITION_KEY
+                ));
+                console.print("Wrong grid? Enter sheet <columns> <rows> [frame seconds]");
+                console.open = true;
+                commands.insert_resource(DroppedSheet {
+                    name,
+                    size,
+                    image,
+                    handle,
+                });
+            }
+            Err(error) => console.print(format!(
+                "Could not import {}: {}",
+                path_buf.display(),
+                error
+            )),
+        }
+    }
+}
+
+fn import_sheet(
+    path: &Path,
+    images: &mut Assets<Image>,
+) -> Result<(String, UVec2, Handle<Image>), String> {
+    let is_png = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+    if !is_png {
+        return Err("only PNG sheets can be dropped".to_string());
+    }
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    let image = Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Descriptor(
+            TextureSampling::default().descriptor(ImageAddressMode::ClampToEdge),
+        ),
+        RenderAssetUsages::default(),
+    )
+    .map_err(|error| error.to_string())?;
+    let name = path.file_stem().map_or_else(
+        || "dropped".to_string(),
+        |stem| stem.to_string_lossy().into(),
+    );
+    let size = image.size();
+    Ok((name, size, images.add(image)))
+}
+
+fn with_sheet(
+    mut definition: SkillDefinition,
+    image: &Handle<Image>,
+    layouts: &mut Assets<TextureAtlasLayout>,
+) -> SkillDefinition {
+    definition.image = image.clone();
+    definition.atlas_layout = layouts.add(definition.atlas_layout());
+    definition
+}
+
+// Slices the dropped sheet again; replacing the definition in place lets the
+// registry and the binding pick it up without rebinding
+fn slice_dropped_sheet(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let [columns, rows, rest @ ..] = args else {
+        return Err("expected columns and rows".to_string());
+    };
+    let parse = |value: &str| value.parse::<u32>().map_err(|error| error.to_string());
+    let (columns, rows) = (parse(columns)?, parse(rows)?);
+    let frame_duration = match rest.first() {
+        Some(seconds) => seconds.parse::<f32>().map_err(|error| error.to_string())?,
+        None => DEFAULT_FRAME_DURATION,
+    };
+    let sheet = world
+        .get_resource::<DroppedSheet>()
+        .ok_or("no sheet has been dropped")?;
+    let definition = sheet_definition(&sheet.name, sheet.size, columns, rows, frame_duration)?;
+    let (image, handle) = (sheet.image.clone(), sheet.handle.clone());
+    let definition = with_sheet(
+        definition,
+        &image,
+        &mut world.resource_mut::<Assets<TextureAtlasLayout>>(),
+    );
+    world
+        .resource_mut::<Assets<SkillDefinition>>()
+        .insert(&handle, definition);
+    Ok(format!("Sliced into {}x{} frames", columns, rows))
+}
+
+// Checks a loaded skill's sheet against the grid its definition declares
+fn detect_skill_grid(world: &mut World, args: &[&str]) -> Result<String, String> {
+    if args.is_empty() {
+        return Err("expected a skill name".to_string());
+    }
+    world.run_system_once_with(args.join(" "), detect_named_grid)
+}
+
+fn detect_named_grid(
+    In(name): In<String>,
+    registry: Res<SkillRegistry>,
+    definitions: Res<Assets<SkillDefinition>>,
+    images: Res<Assets<Image>>,
+) -> Result<String, String> {
+    let definition = registry
+        .iter()
+        .filter_map(|(id, skill)| Some((id, definitions.get(&skill.handle)?)))
+        .find(|(id, definition)| *id == name || definition.name.eq_ignore_ascii_case(&name))
+        .map(|(_, definition)| definition)
+        .ok_or_else(|| format!("no skill {:?}", name))?;
+    let image = images
+        .get(&definition.image)
+        .ok_or("its sheet hasn't loaded")?;
+    let (columns, rows) = detect_grid(image).ok_or("no gutters to detect a grid from")?;
+    let size = image.size();
+    Ok(format!(
+        "{}: looks like {}x{} frames of {}x{}, defined as {}x{}",
+        definition.name,
+        columns,
+        rows,
+        size.x / columns,
+        size.y / rows,
+        definition.columns,
+        definition.rows
+    ))
+}
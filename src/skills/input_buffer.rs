@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use super::SkillDefinition;
+
+// Remembers a cast that was pressed slightly too early, e.g. just before its
+// cooldown ended, and retries it every frame until it goes off or `window`
+// seconds have passed
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillInputBuffer {
+    pub window: f32,
+    queued: Option<BufferedCast>,
+}
+
+#[derive(Reflect)]
+struct BufferedCast {
+    key: KeyCode,
+    skill: Handle<SkillDefinition>,
+    age: f32,
+}
+
+impl Default for SkillInputBuffer {
+    fn default() -> Self {
+        Self {
+            window: 0.2,
+            queued: None,
+        }
+    }
+}
+
+impl SkillInputBuffer {
+    // A newer press replaces whatever was queued
+    pub fn queue(&mut self, key: KeyCode, skill: Handle<SkillDefinition>) {
+        self.queued = Some(BufferedCast {
+            key,
+            skill,
+            age: 0.0,
+        });
+    }
+
+    pub fn queued(&self) -> Option<(KeyCode, Handle<SkillDefinition>)> {
+        self.queued
+            .as_ref()
+            .map(|cast| (cast.key, cast.skill.clone()))
+    }
+
+    pub fn clear(&mut self) {
+        self.queued = None;
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if let Some(cast) = self.queued.as_mut() {
+            cast.age += delta;
+            if cast.age > self.window {
+                self.queued = None;
+            }
+        }
+    }
+}
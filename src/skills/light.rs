@@ -0,0 +1,103 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::WaterSkill;
+use crate::settings::AccessibilitySettings;
+
+// With reduced flashing, lights never go above their base intensity and take
+// at least this long to swing across it
+const REDUCED_FLASH_SWING: f32 = 0.4;
+
+// Optional `light` block of a definition: a point light carried by the skill
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub struct SkillLight {
+    pub color: (f32, f32, f32),
+    pub intensity: f32,
+    #[serde(default = "default_range")]
+    pub range: f32,
+    #[serde(default)]
+    pub offset: (f32, f32, f32),
+    // Intensity multiplier per sprite frame, wrapping around
+    #[serde(default)]
+    pub frames: Vec<f32>,
+    // Intensity multiplier sampled over the lifetime, used when `frames` is empty
+    #[serde(default)]
+    pub curve: Vec<f32>,
+}
+
+fn default_range() -> f32 {
+    5.0
+}
+
+impl SkillLight {
+    pub fn multiplier(&self, frame: Option<usize>, progress: f32) -> f32 {
+        if let (Some(frame), false) = (frame, self.frames.is_empty()) {
+            return self.frames[frame % self.frames.len()];
+        }
+        match self.curve.len() {
+            0 => 1.0,
+            1 => self.curve[0],
+            len => {
+                let position = progress.clamp(0.0, 1.0) * (len - 1) as f32;
+                let index = (position as usize).min(len - 2);
+                let t = position - index as f32;
+                self.curve[index] + (self.curve[index + 1] - self.curve[index]) * t
+            }
+        }
+    }
+}
+
+// Child light of a skill, despawned with it
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillLightEmitter {
+    pub light: SkillLight,
+}
+
+pub fn light_bundle(light: &SkillLight) -> impl Bundle {
+    let (r, g, b) = light.color;
+    let (x, y, z) = light.offset;
+    (
+        PointLightBundle {
+            point_light: PointLight {
+                color: Color::srgb(r, g, b),
+                intensity: light.intensity * light.multiplier(None, 0.0),
+                range: light.range,
+                ..default()
+            },
+            transform: Transform::from_xyz(x, y, z),
+            ..default()
+        },
+        NotShadowCaster,
+        SkillLightEmitter {
+            light: light.clone(),
+        },
+    )
+}
+
+pub fn animate_skill_lights(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    skills: Query<(&WaterSkill, Option<&TextureAtlas>)>,
+    mut lights: Query<(&SkillLightEmitter, &Parent, &mut PointLight)>,
+) {
+    for (emitter, parent, mut point_light) in lights.iter_mut() {
+        let Ok((skill, atlas)) = skills.get(parent.get()) else {
+            continue;
+        };
+        let progress = skill.lifetime.fraction();
+        let multiplier = emitter
+            .light
+            .multiplier(atlas.map(|atlas| atlas.index), progress);
+        let target = emitter.light.intensity * multiplier;
+        if !accessibility.reduce_flashing {
+            point_light.intensity = target;
+            continue;
+        }
+        let base = emitter.light.intensity;
+        let step = base * time.delta_seconds() / REDUCED_FLASH_SWING;
+        let current = point_light.intensity.min(base);
+        point_light.intensity = current + (target.min(base) - current).clamp(-step, step);
+    }
+}
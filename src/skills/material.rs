@@ -0,0 +1,115 @@
+// The size checks generated by `ShaderType` are never called
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+
+use super::{SkillDefinition, WaterSkill};
+
+const SHADER_PATH: &str = "shaders/skill_material.wgsl";
+
+// Where the current frame sits in the sheet and on the quad, all in UVs
+#[derive(Debug, Clone, Copy, Default, ShaderType)]
+pub struct FrameData {
+    // xy is the frame's offset in the sheet, zw its size
+    pub frame: Vec4,
+    // x is the inset in texels kept between sampling and the frame edge
+    pub inset: Vec4,
+    // xy is the share of the quad the frame covers, zw where it starts
+    pub fit: Vec4,
+    // `frame` and `fit` of the frame shown before a clip switch
+    pub previous_frame: Vec4,
+    pub previous_fit: Vec4,
+    // x is how much of the previous frame is still blended in, 0 for none
+    pub blend: Vec4,
+}
+
+impl FrameData {
+    // The largest frame of the layout fills the quad and smaller or
+    // non-square ones are centered at the same texel scale, so packed sheets
+    // with frames trimmed to their content don't stretch or jump in size
+    pub fn new(layout: &TextureAtlasLayout, index: usize, inset: f32) -> Option<Self> {
+        let rect = layout.textures.get(index)?.as_rect();
+        let sheet = layout.size.as_vec2();
+        let largest = layout
+            .textures
+            .iter()
+            .map(|frame| frame.as_rect().size().max_element())
+            .fold(1.0, f32::max);
+        let (offset, size, fit) = (rect.min / sheet, rect.size() / sheet, rect.size() / largest);
+        Some(Self {
+            frame: Vec4::new(offset.x, offset.y, size.x, size.y),
+            inset: Vec4::new(inset, 0.0, 0.0, 0.0),
+            fit: Vec4::new(fit.x, fit.y, (1.0 - fit.x) / 2.0, (1.0 - fit.y) / 2.0),
+            ..default()
+        })
+    }
+
+    // Blends `previous` in over this frame by `weight`, for crossfades
+    pub fn with_previous(self, previous: &FrameData, weight: f32) -> Self {
+        Self {
+            previous_frame: previous.frame,
+            previous_fit: previous.fit,
+            blend: Vec4::new(weight, 0.0, 0.0, 0.0),
+            ..self
+        }
+    }
+}
+
+// Skill quad drawn with `skill_material.wgsl` instead of a StandardMaterial,
+// for definitions with `custom_shader: true`
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct SkillMaterial {
+    #[uniform(0)]
+    pub frame_data: FrameData,
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Handle<Image>,
+    #[uniform(3)]
+    pub tint: LinearRgba,
+    pub alpha_mode: AlphaMode,
+}
+
+impl SkillMaterial {
+    pub fn new(definition: &SkillDefinition, tint: Color) -> Self {
+        Self {
+            frame_data: FrameData::default(),
+            texture: definition.image.clone(),
+            tint: tint.into(),
+            alpha_mode: definition.alpha_mode(),
+        }
+    }
+}
+
+impl Material for SkillMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+}
+
+// Same as `sync_skill_frames`, for skills on the custom shader
+pub(super) fn sync_skill_material_frames(
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<SkillMaterial>>,
+    query: Query<(&WaterSkill, &TextureAtlas, &Handle<SkillMaterial>), Changed<TextureAtlas>>,
+) {
+    for (skill, atlas, material_handle) in query.iter() {
+        let inset = definitions
+            .get(&skill.definition)
+            .map_or(0.0, |definition| definition.frame_inset);
+        let (Some(frame_data), Some(material)) = (
+            layouts
+                .get(&atlas.layout)
+                .and_then(|layout| FrameData::new(layout, atlas.index, inset)),
+            materials.get_mut(material_handle),
+        ) else {
+            continue;
+        };
+        material.frame_data = frame_data;
+    }
+}
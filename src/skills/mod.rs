@@ -0,0 +1,744 @@
+use std::time::Duration;
+
+use bevy::ecs::system::{RunSystemOnce, SystemParam};
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_real_timer;
+use bevy::utils::HashSet;
+use thiserror::Error;
+
+use crate::cinematic::PlayCameraSequence;
+use crate::combat::{Hitbox, ImmunityFrames, Mana};
+use crate::console::RegisterConsoleCommand;
+use crate::equipment::Equipment;
+use crate::ground::Grounded;
+use crate::pause::GameState;
+use crate::respawn::Respawning;
+use crate::save::SaveFile;
+use crate::schedule::{simulation_running, GameSet};
+use crate::settings::AccessibilitySettings;
+use crate::socket::{Socket, Sockets};
+use crate::state_cleanup::{DespawnOnEnter, LevelScoped};
+use crate::stats::Stats;
+use crate::Player;
+use attachment::AttachedTo;
+use cast::{CastKind, Channel};
+use cooldown::SkillCooldowns;
+use despawn::DespawnSkillExt;
+use input_buffer::SkillInputBuffer;
+use on_hit::OnHitEffects;
+use rank::{upgrade_modifier_held, SkillPoints, SkillRanks};
+
+pub mod attachment;
+pub mod beam;
+pub mod behavior;
+pub mod cast;
+pub mod chain;
+pub mod cooldown;
+pub mod definition;
+pub mod despawn;
+pub mod grid_detect;
+pub mod hitscan;
+pub mod import;
+pub mod input_buffer;
+pub mod light;
+pub mod material;
+pub mod on_hit;
+pub mod orbit;
+pub mod orientation;
+pub mod pattern;
+pub mod phase;
+pub mod projectile;
+pub mod rank;
+pub mod reaction;
+pub mod registry;
+pub mod summon;
+pub mod texture;
+pub mod time_zone;
+pub mod trap;
+
+pub use behavior::{
+    RegisterSkillBehavior, SkillBehavior, SkillBehaviors, SkillSpawnContext, StaticBehavior,
+};
+pub use definition::{SkillDefinition, SkillDefinitionLoader};
+pub use despawn::{CancelSkill, DespawnReason};
+pub use material::SkillMaterial;
+pub use registry::{SkillLoadState, SkillRegistry};
+
+// How often the registry looks for skills it can let go of
+const RELEASE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct WaterSkill {
+    pub animation_timer: Timer,
+    pub lifetime: Timer,
+    pub definition: Handle<SkillDefinition>,
+}
+
+impl WaterSkill {
+    pub fn new(handle: Handle<SkillDefinition>, definition: &SkillDefinition) -> Self {
+        Self {
+            animation_timer: Timer::from_seconds(definition.frame_duration, TimerMode::Repeating),
+            lifetime: Timer::from_seconds(definition.lifetime, TimerMode::Once),
+            definition: handle,
+        }
+    }
+}
+
+// Which key casts which skill definition
+#[derive(Resource)]
+pub struct SkillBindings(pub Vec<(KeyCode, Handle<SkillDefinition>)>);
+
+impl SkillBindings {
+    pub fn get(&self, key: KeyCode) -> Option<&Handle<SkillDefinition>> {
+        self.0
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, skill)| skill)
+    }
+
+    // Binds `skill` to `key` in place of what it had, or unbinds it
+    pub fn set(&mut self, key: KeyCode, skill: Option<Handle<SkillDefinition>>) {
+        match (self.0.iter().position(|(bound, _)| *bound == key), skill) {
+            (Some(index), Some(skill)) => self.0[index].1 = skill,
+            (Some(index), None) => {
+                self.0.remove(index);
+            }
+            (None, Some(skill)) => self.0.push((key, skill)),
+            (None, None) => {}
+        }
+    }
+}
+
+pub struct SkillsPlugin;
+
+impl Plugin for SkillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WaterSkill>()
+            .register_type::<SkillOwner>()
+            .register_type::<SkillRanks>()
+            .register_type::<SkillPoints>()
+            .register_type::<SkillCooldowns>()
+            .register_type::<SkillInputBuffer>()
+            .register_type::<Channel>()
+            .register_type::<OnHitEffects>()
+            .register_type::<attachment::AttachedTo>()
+            .register_type::<light::SkillLightEmitter>()
+            .register_type::<orientation::MatchCasterFacing>()
+            .register_type::<pattern::SkillFacing>()
+            .init_asset::<SkillDefinition>()
+            .init_asset_loader::<SkillDefinitionLoader>()
+            .add_plugins(MaterialPlugin::<SkillMaterial>::default())
+            .init_resource::<SkillBehaviors>()
+            .init_resource::<SkillRegistry>()
+            .init_resource::<pattern::PendingSkillSpawns>()
+            .register_skill_behavior("static", StaticBehavior)
+            .register_console_command("cast", "cast <skill>", cast_command)
+            .register_console_command("skills", "skills", list_skills_command)
+            .add_plugins((
+                beam::BeamBehaviorPlugin,
+                chain::ChainBehaviorPlugin,
+                hitscan::HitscanBehaviorPlugin,
+                import::SheetImportPlugin,
+                orbit::OrbitBehaviorPlugin,
+                projectile::ProjectileBehaviorPlugin,
+                reaction::ReactionPlugin,
+                summon::SummonBehaviorPlugin,
+                time_zone::TimeZoneBehaviorPlugin,
+                trap::TrapBehaviorPlugin,
+            ))
+            .add_event::<SkillCastEvent>()
+            .add_event::<despawn::SkillDespawned>()
+            .add_event::<despawn::CancelSkill>()
+            .add_systems(Startup, load_skill_definitions)
+            .add_systems(
+                Update,
+                (
+                    (
+                        (
+                            registry::update_skill_registry,
+                            registry::update_load_states,
+                            registry::track_skill_usage,
+                            registry::release_unused_skills
+                                .run_if(on_real_timer(RELEASE_CHECK_INTERVAL)),
+                        )
+                            .chain(),
+                        rank::spend_skill_points,
+                        (
+                            cooldown::tick_cooldowns,
+                            read_skill_input.run_if(not(in_state(GameState::GameOver))),
+                        )
+                            .run_if(simulation_running),
+                    )
+                        .in_set(GameSet::Input),
+                    (
+                        (cast_skills, pattern::spawn_pending_skills),
+                        cast::update_channels,
+                        (on_hit::resolve_on_hit_effects, on_hit::apply_lifesteal),
+                        attachment::follow_attachments,
+                        orientation::match_caster_facing,
+                        (
+                            despawn::cancel_dead_players_channels,
+                            despawn::cancel_skills,
+                        )
+                            .chain(),
+                        phase::end_skill_lifetimes,
+                    )
+                        .chain()
+                        .in_set(GameSet::Simulation),
+                    // Crossfades need the clip switch made by the animator this frame
+                    (
+                        phase::fade_crossfades,
+                        phase::despawn_after_clip,
+                        sync_skill_frames,
+                        (
+                            phase::start_crossfades,
+                            material::sync_skill_material_frames,
+                            phase::fade_shader_crossfades,
+                        )
+                            .chain(),
+                        light::animate_skill_lights,
+                    )
+                        .in_set(GameSet::Presentation),
+                    debug_skill_info.in_set(GameSet::Debug),
+                ),
+            )
+            .add_systems(PostUpdate, despawn::despawn_marked_skills);
+    }
+}
+
+// The hotbar's slots in order, with what each holds until rearranged
+pub const HOTBAR_KEYS: [(KeyCode, &str); 9] = [
+    (KeyCode::Space, "skills/water.skill.ron"),
+    (KeyCode::Digit1, "skills/chain_lightning.skill.ron"),
+    (KeyCode::Digit2, "skills/water_beam.skill.ron"),
+    (KeyCode::Digit3, "skills/water_orbs.skill.ron"),
+    (KeyCode::Digit4, "skills/water_spirit.skill.ron"),
+    (KeyCode::Digit5, "skills/tidal_surge.skill.ron"),
+    (KeyCode::Digit6, "skills/water_bolt.skill.ron"),
+    (KeyCode::Digit7, "skills/water_mine.skill.ron"),
+    (KeyCode::Digit8, "skills/time_zone.skill.ron"),
+];
+
+// The hotbar as last saved, falling back to the defaults slot by slot
+fn load_skill_definitions(
+    mut commands: Commands,
+    mut registry: ResMut<SkillRegistry>,
+    asset_server: Res<AssetServer>,
+) {
+    let saved = SaveFile::load().hotbar;
+    let bindings = HOTBAR_KEYS
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, (key, default))| {
+            let path = match saved.get(slot) {
+                Some(path) => path.as_deref()?,
+                None => default,
+            };
+            Some((*key, registry.load(&asset_server, path)))
+        })
+        .collect();
+    commands.insert_resource(SkillBindings(bindings));
+}
+
+// Every registered skill and whether it is ready to cast
+fn list_skills_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let registry = world.resource::<SkillRegistry>();
+    let skills: Vec<String> = registry
+        .load_states()
+        .map(|(id, state)| format!("{} ({:?})", id, state))
+        .collect();
+    Ok(skills.join(", "))
+}
+
+// Casts any registered skill by ID or name from every player, ignoring
+// cooldowns and channels
+fn cast_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    if args.is_empty() {
+        return Err("expected a skill name".to_string());
+    }
+    world.run_system_once_with(args.join(" "), cast_named_skill)
+}
+
+fn cast_named_skill(
+    In(name): In<String>,
+    mut registry: ResMut<SkillRegistry>,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<SkillDefinition>>,
+    players: Query<(Entity, &Transform, Option<&SkillRanks>), With<Player>>,
+    mut spawner: SkillSpawner,
+) -> Result<String, String> {
+    if players.is_empty() {
+        return Err("no player".to_string());
+    }
+    let found = registry
+        .iter()
+        .filter_map(|(id, skill)| Some((id, &skill.handle, definitions.get(&skill.handle)?)))
+        .find(|(id, _, definition)| *id == name || definition.name.eq_ignore_ascii_case(&name))
+        .map(|(_, handle, definition)| (handle, definition));
+    let Some((handle, definition)) = found else {
+        return match registry.load_state(&name) {
+            Some(SkillLoadState::Loading) => Err(format!("{} is still loading", name)),
+            Some(SkillLoadState::Failed) => Err(format!("{} failed to load", name)),
+            // Released for going unused, it has to load before it can be cast
+            _ => match registry.acquire(&asset_server, &name) {
+                Some(_) => Err(format!("{} was unloaded, try again once it loads", name)),
+                None => Err(format!("no skill {:?}", name)),
+            },
+        };
+    };
+    for (caster, transform, ranks) in players.iter() {
+        let rank = ranks.map_or(0, |ranks| ranks.rank(handle.id()));
+        let definition = definition.resolve(rank);
+        spawner
+            .spawn(handle, &definition, caster, transform.translation, None)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(format!("Cast {}", definition.name))
+}
+
+// Entity that cast a skill, used for lifesteal, summons and the like
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillOwner(pub Entity);
+
+// Puts skill instances into the world; shared by player input and anything
+// else that needs to cast, like on-hit effects
+#[derive(SystemParam)]
+pub struct SkillSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub meshes: ResMut<'w, Assets<Mesh>>,
+    pub materials: ResMut<'w, Assets<StandardMaterial>>,
+    pub skill_materials: ResMut<'w, Assets<SkillMaterial>>,
+    pub behaviors: Res<'w, SkillBehaviors>,
+    pub registry: Res<'w, SkillRegistry>,
+    pub equipment: Query<'w, 's, &'static Equipment>,
+    pub grounded: Query<'w, 's, &'static Grounded>,
+    pub sockets: Query<'w, 's, &'static Sockets>,
+    pub socket_transforms: Query<'w, 's, &'static GlobalTransform, With<Socket>>,
+    reported: Local<'s, HashSet<String>>,
+}
+
+#[derive(Debug, Error)]
+pub enum SkillSpawnError {
+    #[error("skill {0} is not loaded")]
+    NotLoaded(String),
+    #[error("caster {0} is gone or has no transform")]
+    NoCaster(Entity),
+    #[error("skill {skill:?} uses unknown behavior {behavior:?}")]
+    UnknownBehavior { skill: String, behavior: String },
+}
+
+impl SkillSpawnError {
+    pub fn not_loaded(handle: &Handle<SkillDefinition>) -> Self {
+        Self::NotLoaded(match handle.path() {
+            Some(path) => format!("{:?}", path.to_string()),
+            None => format!("{:?}", handle.id()),
+        })
+    }
+}
+
+impl SkillSpawner<'_, '_> {
+    // `definition` is the (possibly rank-resolved) data for `handle`, `origin`
+    // is the caster position the definition's spawn offset is applied to
+    pub fn spawn(
+        &mut self,
+        handle: &Handle<SkillDefinition>,
+        definition: &SkillDefinition,
+        caster: Entity,
+        origin: Vec3,
+        key: Option<KeyCode>,
+    ) -> Result<Entity, SkillSpawnError> {
+        let _span = info_span!("spawn_skill_instance", skill = %definition.name).entered();
+        // Checked first so nothing is left half spawned
+        if self.behaviors.get(&definition.behavior).is_none() {
+            return Err(SkillSpawnError::UnknownBehavior {
+                skill: definition.name.clone(),
+                behavior: definition.behavior.clone(),
+            });
+        }
+        let socket = definition
+            .socket
+            .as_deref()
+            .and_then(|name| self.sockets.get(caster).ok()?.get(name))
+            .and_then(|socket| Some((socket, self.socket_transforms.get(socket).ok()?)));
+        let spawn_position = match socket {
+            Some((_, socket)) => socket.translation() + definition.spawn_offset(),
+            None => origin + definition.spawn_offset(),
+        };
+        let equipment = self.equipment.get(caster).ok();
+
+        let tint = equipment.and_then(Equipment::skill_tint);
+        let quad_handle = self.meshes.add(Mesh::from(Rectangle::new(1.0, 1.0)));
+        let transform = Transform::from_translation(spawn_position)
+            .with_rotation(definition.orientation.spawn_rotation())
+            .with_scale(Vec3::splat(definition.scale));
+
+        let mut skill = if definition.custom_shader {
+            let material = SkillMaterial::new(definition, tint.unwrap_or(Color::WHITE));
+            self.commands.spawn(MaterialMeshBundle {
+                mesh: quad_handle,
+                material: self.skill_materials.add(material),
+                transform,
+                ..default()
+            })
+        } else {
+            let mut material = match self.registry.get_by_asset(handle.id()) {
+                Some(registered) => registered.material.clone(),
+                None => StandardMaterial {
+                    base_color_texture: Some(definition.image.clone()),
+                    alpha_mode: definition.alpha_mode(),
+                    unlit: !definition.lit,
+                    ..default()
+                },
+            };
+            if let Some(tint) = tint {
+                material.base_color = tint;
+            }
+            self.commands.spawn(PbrBundle {
+                mesh: quad_handle,
+                material: self.materials.add(material),
+                transform,
+                ..default()
+            })
+        };
+        skill.insert((
+            WaterSkill::new(handle.clone(), definition),
+            definition.animator(),
+            TextureAtlas {
+                layout: definition.atlas_layout.clone(),
+                index: 0,
+            },
+            Hitbox {
+                shape: definition.hit_shape(),
+                damage: definition.damage,
+            },
+            ImmunityFrames::new(definition.hit_interval),
+            SkillOwner(caster),
+            DespawnOnEnter(GameState::GameOver),
+        ));
+        if !definition.persistent {
+            skill.insert(LevelScoped);
+        }
+        definition.orientation.insert_maintainer(&mut skill, caster);
+        if let Some((socket, _)) = socket {
+            skill.insert(AttachedTo {
+                target: socket,
+                offset: definition.spawn_offset(),
+            });
+        }
+        let mut on_hit = definition.on_hit.clone();
+        if let Some(equipment) = equipment {
+            on_hit.extend(equipment.on_hit_effects().cloned());
+        }
+        if !on_hit.is_empty() {
+            skill.insert(OnHitEffects(on_hit));
+        }
+        if !definition.tags.is_empty() {
+            skill.insert(reaction::SkillTags(definition.tags.clone()));
+        }
+        // Stays at its spawn height above the ground the caster stands on
+        if definition.grounded {
+            if let Ok(caster_grounded) = self.grounded.get(caster) {
+                skill.insert(Grounded {
+                    height: caster_grounded.height + definition.spawn_offset().y,
+                });
+            }
+        }
+        if let Some(light) = &definition.light {
+            skill.with_children(|skill| {
+                skill.spawn(light::light_bundle(light));
+            });
+        }
+
+        let ctx = SkillSpawnContext {
+            caster,
+            definition,
+            position: spawn_position,
+            key,
+        };
+        if let Some(behavior) = self.behaviors.get(&definition.behavior) {
+            behavior.spawn(&mut skill, &ctx);
+        }
+        trace!("Skill spawned at {:?}", spawn_position);
+        Ok(skill.id())
+    }
+
+    // Warns once per distinct error, as failed spawns tend to be retried, e.g.
+    // every frame by AI or hazards
+    pub fn report(&mut self, error: &SkillSpawnError) {
+        let message = error.to_string();
+        if !self.reported.contains(&message) {
+            warn!("{}", message);
+            self.reported.insert(message);
+        }
+    }
+}
+
+// Asks for `caster` to cast `skill`, going through the same rank, cooldown
+// and channel rules as the player's key presses. Anything can send these, e.g.
+// AI or scripted sequences.
+#[derive(Event, Clone)]
+pub struct SkillCastEvent {
+    pub caster: Entity,
+    pub skill: Handle<SkillDefinition>,
+    // Where the skill's spawn offset is applied from, the caster if unset
+    pub target: Option<Vec3>,
+    // Key that triggered the cast, for skills that last while it is held
+    pub key: Option<KeyCode>,
+}
+
+impl SkillCastEvent {
+    pub fn new(caster: Entity, skill: Handle<SkillDefinition>) -> Self {
+        Self {
+            caster,
+            skill,
+            target: None,
+            key: None,
+        }
+    }
+}
+
+fn read_skill_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<SkillBindings>,
+    accessibility: Res<AccessibilitySettings>,
+    // Dead players can't cast until they respawn
+    players: Query<Entity, (With<Player>, Without<Respawning>)>,
+    channels: Query<&Channel>,
+    mut casts: EventWriter<SkillCastEvent>,
+) {
+    if upgrade_modifier_held(&keyboard_input) {
+        return;
+    }
+    // Every player shares the bindings, each casting as its own entity
+    for caster in players.iter() {
+        for (key, skill) in bindings.0.iter() {
+            // With toggled channels, pressing the key again ends the channel instead
+            let toggling_off = !accessibility.hold_to_channel
+                && channels
+                    .iter()
+                    .any(|channel| channel.caster == caster && channel.key == Some(*key));
+            if keyboard_input.just_pressed(*key) && !toggling_off {
+                casts.send(SkillCastEvent {
+                    key: Some(*key),
+                    ..SkillCastEvent::new(caster, skill.clone())
+                });
+            }
+        }
+    }
+}
+
+fn cast_skills(
+    time: Res<Time>,
+    mut events: EventReader<SkillCastEvent>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut casters: Query<(
+        Entity,
+        &Transform,
+        Option<&SkillRanks>,
+        Option<&mut SkillCooldowns>,
+        Option<&Stats>,
+        Option<&mut SkillInputBuffer>,
+        Has<Player>,
+    )>,
+    channels: Query<(Entity, &Channel, Option<&WaterSkill>)>,
+    manas: Query<&Mana>,
+    mut spawner: SkillSpawner,
+    mut pending: ResMut<pattern::PendingSkillSpawns>,
+    mut camera_sequences: EventWriter<PlayCameraSequence>,
+) {
+    // Fresh casts first; a buffered cast is only retried on frames its caster
+    // sent nothing
+    let mut casts: Vec<(SkillCastEvent, bool)> =
+        events.read().map(|event| (event.clone(), true)).collect();
+    for (caster, _, _, _, _, buffer, _) in casters.iter_mut() {
+        let Some(mut buffer) = buffer else {
+            continue;
+        };
+        buffer.tick(time.delta_seconds());
+        if casts.iter().any(|(cast, _)| cast.caster == caster) {
+            continue;
+        }
+        if let Some((key, skill)) = buffer.queued() {
+            let cast = SkillCastEvent {
+                key: Some(key),
+                ..SkillCastEvent::new(caster, skill)
+            };
+            casts.push((cast, false));
+        }
+    }
+
+    for (cast, fresh) in casts {
+        let Ok((caster, transform, ranks, mut cooldowns, stats, mut buffer, is_player)) =
+            casters.get_mut(cast.caster)
+        else {
+            spawner.report(&SkillSpawnError::NoCaster(cast.caster));
+            continue;
+        };
+        let Some(base_definition) = definitions.get(&cast.skill) else {
+            spawner.report(&SkillSpawnError::not_loaded(&cast.skill));
+            continue;
+        };
+        // Only player casts are worth explaining, AI retries every frame
+        let report = fresh && is_player;
+        let skill_id = cast.skill.id();
+        let rank = ranks.map_or(0, |ranks| ranks.rank(skill_id));
+        let definition = &base_definition.resolve(rank);
+        let _span = info_span!("cast_skill", skill = %definition.name, rank).entered();
+        // Channels with a higher priority than this cast block it
+        let channel = channels
+            .iter()
+            .find(|(_, channel, _)| channel.caster == caster)
+            .map(|(entity, channel, skill)| {
+                let remaining =
+                    skill.map_or(f32::INFINITY, |skill| skill.lifetime.remaining_secs());
+                (entity, channel.kind, remaining)
+            });
+        if let Some((_, kind, remaining)) = channel {
+            if definition.cast.priority() < kind.priority() {
+                // Pressed just before the channel runs out, like the cooldown
+                // case below
+                match (buffer.as_mut(), cast.key) {
+                    (Some(buffer), Some(key)) if fresh && remaining <= buffer.window => {
+                        buffer.queue(key, cast.skill.clone());
+                    }
+                    _ if report => {
+                        println!("{} blocked while channeling", definition.name);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        }
+        if definition.mana_per_second > 0.0
+            && manas.get(caster).is_ok_and(|mana| mana.current <= 0.0)
+        {
+            if report {
+                println!("Not enough mana for {}", definition.name);
+            }
+            continue;
+        }
+
+        if let Some(cooldowns) = cooldowns.as_mut() {
+            let global = if definition.off_global_cooldown {
+                0.0
+            } else {
+                cooldowns.global_remaining()
+            };
+            if !cooldowns.is_ready(skill_id) || global > 0.0 {
+                let remaining = cooldowns.remaining(skill_id).max(global);
+                match (buffer.as_mut(), cast.key) {
+                    (Some(buffer), Some(key)) if fresh && remaining <= buffer.window => {
+                        buffer.queue(key, cast.skill.clone());
+                    }
+                    _ if report => {
+                        println!("{} on cooldown ({:.1}s)", definition.name, remaining);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let multiplier = stats.map_or(1.0, Stats::cooldown_multiplier);
+            cooldowns.start(skill_id, definition.cooldown * multiplier);
+            if !definition.off_global_cooldown {
+                cooldowns.start_global(multiplier);
+            }
+        }
+        if let Some((channel, ..)) = channel {
+            spawner
+                .commands
+                .entity(channel)
+                .despawn_skill(DespawnReason::Interrupted);
+            println!("Channel interrupted by {}", definition.name);
+        }
+        if let Some(buffer) = buffer.as_mut() {
+            buffer.clear();
+        }
+        let skill = match pattern::spawn_pattern(
+            &mut spawner,
+            &mut pending,
+            &cast.skill,
+            definition,
+            caster,
+            transform,
+            cast.target,
+            cast.key,
+        ) {
+            Ok(skill) => skill,
+            Err(error) => {
+                spawner.report(&error);
+                continue;
+            }
+        };
+        if definition.cast == CastKind::Channeled {
+            spawner.commands.entity(skill).insert(Channel {
+                caster,
+                kind: definition.cast,
+                key: cast.key,
+                mana_per_second: definition.mana_per_second,
+                last_health: None,
+            });
+        }
+        // Camera sequences are for the player's own ultimates
+        if let Some(sequence) = definition.camera.as_ref().filter(|_| is_player) {
+            camera_sequences.send(PlayCameraSequence {
+                sequence: sequence.clone(),
+                focus: caster,
+            });
+        }
+    }
+}
+
+// UV transform selecting one cell of an atlas on a full-texture quad
+// `inset` shrinks the cell by that many texels on each side
+pub fn frame_uv_transform(
+    layout: &TextureAtlasLayout,
+    index: usize,
+    inset: f32,
+) -> Option<Affine2> {
+    let rect = layout.textures.get(index)?.as_rect();
+    let inset = inset.clamp(0.0, rect.size().min_element() / 2.0);
+    let rect = rect.inflate(-inset);
+    let size = layout.size.as_vec2();
+    Some(Affine2::from_scale_angle_translation(
+        rect.size() / size,
+        0.0,
+        rect.min / size,
+    ))
+}
+
+// Skills are drawn with a StandardMaterial, so the current atlas cell is applied
+// through the material's UV transform
+fn sync_skill_frames(
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<(&WaterSkill, &TextureAtlas, &Handle<StandardMaterial>), Changed<TextureAtlas>>,
+) {
+    let _span = info_span!("sync_skill_frames").entered();
+    for (skill, atlas, material_handle) in query.iter() {
+        let inset = definitions
+            .get(&skill.definition)
+            .map_or(0.0, |definition| definition.frame_inset);
+        let (Some(uv_transform), Some(material)) = (
+            layouts
+                .get(&atlas.layout)
+                .and_then(|layout| frame_uv_transform(layout, atlas.index, inset)),
+            materials.get_mut(material_handle),
+        ) else {
+            continue;
+        };
+        material.uv_transform = uv_transform;
+    }
+}
+
+fn debug_skill_info(query: Query<(&Transform, &TextureAtlas), With<WaterSkill>>) {
+    for (transform, atlas) in query.iter() {
+        println!(
+            "Skill position: {:?}, Current frame: {}",
+            transform.translation, atlas.index
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{SkillDefinition, SkillOwner, SkillSpawner};
+use crate::combat::{Health, SkillHitEvent};
+use crate::damage::DamageDealt;
+use crate::status::{StackPolicy, StatusEffect, StatusEffects, StatusKind};
+
+// Effects listed under `on_hit` in a definition, applied every time the skill hits
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub enum OnHitEffect {
+    Burn {
+        damage_per_second: f32,
+        duration: f32,
+        #[serde(default)]
+        stacking: StackPolicy,
+    },
+    Slow {
+        factor: f32,
+        duration: f32,
+        #[serde(default)]
+        stacking: StackPolicy,
+    },
+    // Heals the skill's owner by a fraction of the hit's damage
+    Lifesteal {
+        fraction: f32,
+    },
+    // Spawns another skill definition at the target
+    SpawnSkill {
+        skill: String,
+        #[serde(skip)]
+        handle: Handle<SkillDefinition>,
+    },
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct OnHitEffects(pub Vec<OnHitEffect>);
+
+pub fn resolve_on_hit_effects(
+    mut hits: EventReader<SkillHitEvent>,
+    skills: Query<(&OnHitEffects, Option<&SkillOwner>)>,
+    targets: Query<&GlobalTransform>,
+    mut statuses: Query<&mut StatusEffects>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut spawner: SkillSpawner,
+) {
+    for hit in hits.read() {
+        let Ok((effects, owner)) = skills.get(hit.skill) else {
+            continue;
+        };
+        let owner = owner.map(|owner| owner.0);
+        for effect in effects.0.iter() {
+            match effect {
+                OnHitEffect::Burn {
+                    damage_per_second,
+                    duration,
+                    stacking,
+                } => {
+                    if let Ok(mut status) = statuses.get_mut(hit.target) {
+                        let kind = StatusKind::Burn {
+                            damage_per_second: *damage_per_second,
+                        };
+                        status.apply(
+                            StatusEffect::new(kind, *duration, owner)
+                                .with_stacking(*stacking, Some(hit.skill)),
+                        );
+                    }
+                }
+                OnHitEffect::Slow {
+                    factor,
+                    duration,
+                    stacking,
+                } => {
+                    if let Ok(mut status) = statuses.get_mut(hit.target) {
+                        let kind = StatusKind::Slow { factor: *factor };
+                        status.apply(
+                            StatusEffect::new(kind, *duration, owner)
+                                .with_stacking(*stacking, Some(hit.skill)),
+                        );
+                    }
+                }
+                // Needs the final damage, handled in `apply_lifesteal`
+                OnHitEffect::Lifesteal { .. } => {}
+                OnHitEffect::SpawnSkill { handle, .. } => {
+                    let (Some(definition), Ok(target)) =
+                        (definitions.get(handle), targets.get(hit.target))
+                    else {
+                        continue;
+                    };
+                    let caster = owner.unwrap_or(hit.skill);
+                    if let Err(error) =
+                        spawner.spawn(handle, definition, caster, target.translation(), None)
+                    {
+                        spawner.report(&error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn apply_lifesteal(
+    mut damage_dealt: EventReader<DamageDealt>,
+    skills: Query<&OnHitEffects>,
+    mut healths: Query<&mut Health>,
+) {
+    for damage in damage_dealt.read() {
+        let (Ok(effects), Some(attacker)) = (skills.get(damage.source), damage.attacker) else {
+            continue;
+        };
+        let fraction: f32 = effects
+            .0
+            .iter()
+            .map(|effect| match effect {
+                OnHitEffect::Lifesteal { fraction } => *fraction,
+                _ => 0.0,
+            })
+            .sum();
+        if fraction <= 0.0 {
+            continue;
+        }
+        if let Ok(mut health) = healths.get_mut(attacker) {
+            health.current = (health.current + damage.amount * fraction).min(health.max);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::attachment::{follow_attachments, AttachedTo};
+use super::rank::SkillRanks;
+use super::{
+    RegisterSkillBehavior, SkillBehavior, SkillDefinition, SkillOwner, SkillSpawnContext,
+    SkillSpawner, WaterSkill,
+};
+use crate::schedule::GameSet;
+
+pub struct OrbitBehaviorPlugin;
+
+impl Plugin for OrbitBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<OrbitSkill>()
+            .register_skill_behavior("orbit", OrbitBehavior)
+            .add_systems(
+                Update,
+                (spawn_orbit_companions, advance_orbits)
+                    .chain()
+                    .before(follow_attachments)
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// Definition params: `count`, `radius`, `angular_speed` (radians per second), `height`.
+// The orbs last for the definition's lifetime and hit whatever they pass through.
+pub struct OrbitBehavior;
+
+impl SkillBehavior for OrbitBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert((
+            OrbitSkill {
+                count: definition.param("count", 3.0).max(1.0) as u32,
+                radius: definition.param("radius", 1.5),
+                angular_speed: definition.param("angular_speed", 3.0),
+                height: definition.param("height", 0.5),
+                angle: 0.0,
+            },
+            AttachedTo {
+                target: ctx.caster,
+                offset: Vec3::ZERO,
+            },
+        ));
+    }
+}
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct OrbitSkill {
+    pub count: u32,
+    pub radius: f32,
+    pub angular_speed: f32,
+    pub height: f32,
+    pub angle: f32,
+}
+
+// The behavior only sees the first orb; the remaining ones are spawned like
+// it, at the caster's rank, and spread evenly around the circle
+fn spawn_orbit_companions(
+    definitions: Res<Assets<SkillDefinition>>,
+    ranks: Query<&SkillRanks>,
+    mut spawner: SkillSpawner,
+    mut orbs: Query<(&WaterSkill, &mut OrbitSkill, &SkillOwner, &Transform), Added<OrbitSkill>>,
+) {
+    for (skill, mut orbit, owner, transform) in orbs.iter_mut() {
+        let Some(definition) = definitions.get(&skill.definition) else {
+            continue;
+        };
+        let rank = ranks
+            .get(owner.0)
+            .map_or(0, |ranks| ranks.rank(skill.definition.id()));
+        let definition = definition.resolve(rank);
+        let origin = transform.translation - definition.spawn_offset();
+        let count = orbit.count;
+        orbit.count = 1;
+        for index in 1..count {
+            let companion =
+                match spawner.spawn(&skill.definition, &definition, owner.0, origin, None) {
+                    Ok(companion) => companion,
+                    Err(error) => {
+                        spawner.report(&error);
+                        break;
+                    }
+                };
+            // Replaces the behavior's own, so the companion doesn't spawn more
+            spawner.commands.entity(companion).insert(OrbitSkill {
+                count: 1,
+                angle: std::f32::consts::TAU * index as f32 / count as f32,
+                ..orbit.clone()
+            });
+        }
+    }
+}
+
+fn advance_orbits(time: Res<Time>, mut orbs: Query<(&mut OrbitSkill, &mut AttachedTo)>) {
+    for (mut orbit, mut attachment) in orbs.iter_mut() {
+        orbit.angle += orbit.angular_speed * time.delta_seconds();
+        attachment.offset = Vec3::new(
+            orbit.angle.cos() * orbit.radius,
+            orbit.height,
+            orbit.angle.sin() * orbit.radius,
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::billboard::Billboard;
+
+// How a skill's quad is turned, written in the definition as e.g.
+// `orientation: Ground` or `orientation: World(yaw: 45.0)`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum SkillOrientation {
+    // Turned towards the camera every frame, or flat in the top-down view
+    FaceCamera,
+    // Lying flat, facing up
+    Ground,
+    // Facing the way the caster faces, kept up to date while it turns
+    CasterFacing,
+    // Fixed rotation in degrees around the world axes
+    World {
+        #[serde(default)]
+        yaw: f32,
+        #[serde(default)]
+        pitch: f32,
+        #[serde(default)]
+        roll: f32,
+    },
+}
+
+// Side-on to the default camera, which is how skills have always spawned
+impl Default for SkillOrientation {
+    fn default() -> Self {
+        SkillOrientation::World {
+            yaw: -90.0,
+            pitch: 0.0,
+            roll: 0.0,
+        }
+    }
+}
+
+impl SkillOrientation {
+    // Rotation the quad spawns with. Camera and caster facing are filled in
+    // by their systems before the first frame is drawn.
+    pub fn spawn_rotation(self) -> Quat {
+        match self {
+            SkillOrientation::Ground => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+            SkillOrientation::World { yaw, pitch, roll } => Quat::from_euler(
+                EulerRot::YXZ,
+                yaw.to_radians(),
+                pitch.to_radians(),
+                roll.to_radians(),
+            ),
+            SkillOrientation::FaceCamera | SkillOrientation::CasterFacing => Quat::IDENTITY,
+        }
+    }
+
+    // Adds whatever keeps the orientation up to date after spawning
+    pub fn insert_maintainer(self, skill: &mut EntityCommands, caster: Entity) {
+        match self {
+            SkillOrientation::FaceCamera => {
+                skill.insert(Billboard);
+            }
+            SkillOrientation::CasterFacing => {
+                skill.insert(MatchCasterFacing(caster));
+            }
+            SkillOrientation::Ground | SkillOrientation::World { .. } => {}
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct MatchCasterFacing(pub Entity);
+
+pub fn match_caster_facing(
+    casters: Query<&GlobalTransform, Without<MatchCasterFacing>>,
+    mut skills: Query<(&MatchCasterFacing, &mut Transform)>,
+) {
+    for (MatchCasterFacing(caster), mut transform) in skills.iter_mut() {
+        if let Ok(caster) = casters.get(*caster) {
+            transform.rotation = caster.compute_transform().rotation;
+        }
+    }
+}
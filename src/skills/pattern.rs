@@ -0,0 +1,230 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{SkillDefinition, SkillSpawnError, SkillSpawner};
+
+// How many instances one cast spawns and where, written in the definition as
+// e.g. `pattern: Fan(count: 5, spread: 60.0)`. Instances are turned relative
+// to the caster's facing, which projectiles then fly along.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum SpawnPattern {
+    #[default]
+    Single,
+    // Spread evenly over `spread` degrees, centered on the facing
+    Fan {
+        count: u32,
+        spread: f32,
+    },
+    // Evenly around the caster, `radius` out from the spawn point
+    Ring {
+        count: u32,
+        #[serde(default)]
+        radius: f32,
+    },
+    // One after another, `delay` seconds apart
+    Burst {
+        count: u32,
+        delay: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternInstance {
+    // Degrees to the left of the caster's facing
+    pub yaw: f32,
+    // Distance out along the turned facing
+    pub distance: f32,
+    pub delay: f32,
+}
+
+impl SpawnPattern {
+    // `delta` more (or fewer) instances, never fewer than one. A single spawn
+    // has no spread to fit more into, so it stays single.
+    pub fn with_extra_count(self, delta: i32) -> Self {
+        let add = |count: u32| (count as i32).saturating_add(delta).max(1) as u32;
+        match self {
+            SpawnPattern::Single => SpawnPattern::Single,
+            SpawnPattern::Fan { count, spread } => SpawnPattern::Fan {
+                count: add(count),
+                spread,
+            },
+            SpawnPattern::Ring { count, radius } => SpawnPattern::Ring {
+                count: add(count),
+                radius,
+            },
+            SpawnPattern::Burst { count, delay } => SpawnPattern::Burst {
+                count: add(count),
+                delay,
+            },
+        }
+    }
+
+    // The first instance always goes off right away
+    pub fn instances(&self) -> Vec<PatternInstance> {
+        let single = PatternInstance {
+            yaw: 0.0,
+            distance: 0.0,
+            delay: 0.0,
+        };
+        match *self {
+            SpawnPattern::Single => vec![single],
+            SpawnPattern::Fan { count, spread } => {
+                let count = count.max(1);
+                (0..count)
+                    .map(|i| {
+                        let t = if count > 1 {
+                            i as f32 / (count - 1) as f32 - 0.5
+                        } else {
+                            0.0
+                        };
+                        PatternInstance {
+                            yaw: spread * t,
+                            ..single
+                        }
+                    })
+                    .collect()
+            }
+            SpawnPattern::Ring { count, radius } => {
+                let count = count.max(1);
+                (0..count)
+                    .map(|i| PatternInstance {
+                        yaw: 360.0 * i as f32 / count as f32,
+                        distance: radius,
+                        ..single
+                    })
+                    .collect()
+            }
+            SpawnPattern::Burst { count, delay } => (0..count.max(1))
+                .map(|i| PatternInstance {
+                    delay: delay * i as f32,
+                    ..single
+                })
+                .collect(),
+        }
+    }
+}
+
+// World-space direction an instance was spawned facing
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillFacing(pub Vec3);
+
+struct PendingSpawn {
+    timer: Timer,
+    handle: Handle<SkillDefinition>,
+    definition: SkillDefinition,
+    caster: Entity,
+    target: Option<Vec3>,
+    key: Option<KeyCode>,
+    instance: PatternInstance,
+}
+
+// Burst instances waiting for their delay, spawned from wherever the caster is
+// when it runs out
+#[derive(Resource, Default)]
+pub struct PendingSkillSpawns(Vec<PendingSpawn>);
+
+// Spawns the instances of `definition`'s pattern that are due now and queues
+// the rest, returning the first
+pub fn spawn_pattern(
+    spawner: &mut SkillSpawner,
+    pending: &mut PendingSkillSpawns,
+    handle: &Handle<SkillDefinition>,
+    definition: &SkillDefinition,
+    caster: Entity,
+    caster_transform: &Transform,
+    target: Option<Vec3>,
+    key: Option<KeyCode>,
+) -> Result<Entity, SkillSpawnError> {
+    let mut first = None;
+    for instance in definition.pattern.instances() {
+        if instance.delay > 0.0 {
+            pending.0.push(PendingSpawn {
+                timer: Timer::from_seconds(instance.delay, TimerMode::Once),
+                handle: handle.clone(),
+                definition: definition.clone(),
+                caster,
+                target,
+                key,
+                instance,
+            });
+            continue;
+        }
+        let skill = spawn_instance(
+            spawner,
+            handle,
+            definition,
+            caster,
+            caster_transform,
+            target,
+            key,
+            &instance,
+        )?;
+        first.get_or_insert(skill);
+    }
+    match first {
+        Some(first) => Ok(first),
+        None => spawner.spawn(
+            handle,
+            definition,
+            caster,
+            target.unwrap_or(caster_transform.translation),
+            key,
+        ),
+    }
+}
+
+fn spawn_instance(
+    spawner: &mut SkillSpawner,
+    handle: &Handle<SkillDefinition>,
+    definition: &SkillDefinition,
+    caster: Entity,
+    caster_transform: &Transform,
+    target: Option<Vec3>,
+    key: Option<KeyCode>,
+    instance: &PatternInstance,
+) -> Result<Entity, SkillSpawnError> {
+    let forward = caster_transform
+        .forward()
+        .with_y(0.0)
+        .try_normalize()
+        .unwrap_or(Vec3::NEG_Z);
+    let facing = Quat::from_rotation_y(instance.yaw.to_radians()) * forward;
+    let origin = target.unwrap_or(caster_transform.translation) + facing * instance.distance;
+    let skill = spawner.spawn(handle, definition, caster, origin, key)?;
+    spawner.commands.entity(skill).insert(SkillFacing(facing));
+    Ok(skill)
+}
+
+pub fn spawn_pending_skills(
+    time: Res<Time>,
+    mut pending: ResMut<PendingSkillSpawns>,
+    casters: Query<&Transform>,
+    mut spawner: SkillSpawner,
+) {
+    for spawn in pending.0.iter_mut() {
+        spawn.timer.tick(time.delta());
+    }
+    let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut pending.0)
+        .into_iter()
+        .partition(|spawn| spawn.timer.finished());
+    pending.0 = waiting;
+    for spawn in due {
+        // A caster that died mid-burst takes the rest of it with it
+        let Ok(caster_transform) = casters.get(spawn.caster) else {
+            continue;
+        };
+        if let Err(error) = spawn_instance(
+            &mut spawner,
+            &spawn.handle,
+            &spawn.definition,
+            spawn.caster,
+            caster_transform,
+            spawn.target,
+            spawn.key,
+            &spawn.instance,
+        ) {
+            spawner.report(&error);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::despawn::{Cancelled, DespawnReason, DespawnSkillExt};
+use super::material::{FrameData, SkillMaterial};
+use super::{frame_uv_transform, SkillDefinition, WaterSkill};
+use crate::animation::{AnimationClip, AnimationFinished, Animator, ClipFrames};
+
+pub const SPAWN_CLIP: &str = "spawn";
+pub const LOOP_CLIP: &str = "loop";
+pub const DESPAWN_CLIP: &str = "despawn";
+pub const TRIGGER_CLIP: &str = "trigger";
+
+// Frame ranges a skill plays through: `spawn` once, `loop` until its lifetime
+// runs out, then `despawn` once before it is removed. Traps loop until set
+// off and then play `trigger` once, which is when they deal damage.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SkillClips {
+    pub spawn: Option<ClipFrames>,
+    #[serde(rename = "loop")]
+    pub looping: Option<ClipFrames>,
+    pub despawn: Option<ClipFrames>,
+    pub trigger: Option<ClipFrames>,
+    // Seconds the outgoing clip's last frame is blended over the new clip when
+    // switching, 0 cuts straight over
+    pub crossfade: f32,
+}
+
+impl SkillClips {
+    // `sheet` is what loops when there is no `loop` clip, the definition's
+    // frame list or start to end frames
+    pub fn animator(
+        &self,
+        total_frames: usize,
+        sheet: &ClipFrames,
+        frame_duration: f32,
+    ) -> Animator {
+        let last = total_frames.saturating_sub(1);
+        let looping = self.looping.as_ref().unwrap_or(sheet);
+        let mut animator = Animator::default().with_clip(
+            LOOP_CLIP,
+            AnimationClip::looping(looping.resolve(last), frame_duration),
+        );
+        if let Some(spawn) = &self.spawn {
+            animator = animator.with_clip(
+                SPAWN_CLIP,
+                AnimationClip::once(spawn.resolve(last), frame_duration),
+            );
+            animator.play(SPAWN_CLIP);
+        }
+        if let Some(despawn) = &self.despawn {
+            animator = animator.with_clip(
+                DESPAWN_CLIP,
+                AnimationClip::once(despawn.resolve(last), frame_duration),
+            );
+        }
+        if let Some(trigger) = &self.trigger {
+            animator = animator.with_clip(
+                TRIGGER_CLIP,
+                AnimationClip::once(trigger.resolve(last), frame_duration),
+            );
+        }
+        animator.queue(LOOP_CLIP);
+        animator
+    }
+}
+
+// Copy of the frame shown before a clip switch, fading out over the new clip
+#[derive(Component)]
+pub struct Crossfade {
+    timer: Timer,
+    tint: Color,
+    premultiplied: bool,
+}
+
+// The same for skills on the custom shader, which blends the two frames itself
+#[derive(Component)]
+pub struct ShaderCrossfade {
+    from: usize,
+    timer: Timer,
+}
+
+pub fn start_crossfades(
+    mut commands: Commands,
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut skills: Query<(
+        Entity,
+        &WaterSkill,
+        &mut Animator,
+        &TextureAtlas,
+        Option<&Handle<StandardMaterial>>,
+        &Handle<Mesh>,
+    )>,
+) {
+    for (entity, skill, mut animator, atlas, material, mesh) in skills.iter_mut() {
+        let Some(from) = animator.switched_from() else {
+            continue;
+        };
+        animator.clear_switched_from();
+        let (Some(definition), Some(layout)) = (
+            definitions.get(&skill.definition),
+            layouts.get(&atlas.layout),
+        ) else {
+            continue;
+        };
+        let fade = definition.clips.crossfade;
+        if fade <= 0.0 || from == animator.frame() {
+            continue;
+        }
+        let Some(material) = material else {
+            commands.entity(entity).insert(ShaderCrossfade {
+                from,
+                timer: Timer::from_seconds(fade, TimerMode::Once),
+            });
+            continue;
+        };
+        let Some(mut overlay) = materials.get(material).cloned() else {
+            continue;
+        };
+        if let Some(uv_transform) = frame_uv_transform(layout, from, definition.frame_inset) {
+            overlay.uv_transform = uv_transform;
+        }
+        let tint = overlay.base_color;
+        let overlay = materials.add(overlay);
+        commands.entity(entity).with_children(|skill| {
+            skill.spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: overlay,
+                    // Just in front of the skill's quad so the two don't sort randomly
+                    transform: Transform::from_xyz(0.0, 0.0, 0.01),
+                    ..default()
+                },
+                NotShadowCaster,
+                Crossfade {
+                    timer: Timer::from_seconds(fade, TimerMode::Once),
+                    tint,
+                    premultiplied: definition.premultiply_alpha,
+                },
+            ));
+        });
+    }
+}
+
+// Skills with a despawn clip play it out when their lifetime ends, the rest
+// are removed right away
+pub fn end_skill_lifetimes(
+    mut commands: Commands,
+    time: Res<Time>,
+    // Cancelled skills are already on their way out
+    mut query: Query<(Entity, &mut WaterSkill, Option<&mut Animator>), Without<Cancelled>>,
+) {
+    for (entity, mut skill, animator) in query.iter_mut() {
+        skill.lifetime.tick(time.delta());
+        if !skill.lifetime.just_finished() {
+            continue;
+        }
+        match animator {
+            Some(mut animator) if animator.has_clip(DESPAWN_CLIP) => {
+                animator.play(DESPAWN_CLIP);
+            }
+            _ => {
+                commands
+                    .entity(entity)
+                    .despawn_skill(DespawnReason::Expired);
+            }
+        }
+    }
+}
+
+pub fn despawn_after_clip(
+    mut commands: Commands,
+    mut finished: EventReader<AnimationFinished>,
+    skills: Query<Option<&Cancelled>, With<WaterSkill>>,
+) {
+    for event in finished.read() {
+        if event.clip != DESPAWN_CLIP {
+            continue;
+        }
+        if let Ok(cancelled) = skills.get(event.entity) {
+            let reason = cancelled.map_or(DespawnReason::Expired, |cancelled| cancelled.0);
+            commands.entity(event.entity).despawn_skill(reason);
+        }
+    }
+}
+
+pub fn fade_crossfades(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut fades: Query<(Entity, &mut Crossfade, &Handle<StandardMaterial>)>,
+) {
+    for (entity, mut fade, material) in fades.iter_mut() {
+        fade.timer.tick(time.delta());
+        if fade.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+        let weight = 1.0 - fade.timer.fraction();
+        // Premultiplied colors carry their alpha, so the whole color fades
+        material.base_color = if fade.premultiplied {
+            (fade.tint.to_linear() * weight).into()
+        } else {
+            fade.tint.with_alpha(fade.tint.alpha() * weight)
+        };
+    }
+}
+
+// Runs after the frame sync, which resets the blend whenever the frame changes
+pub fn fade_shader_crossfades(
+    mut commands: Commands,
+    time: Res<Time>,
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<SkillMaterial>>,
+    mut fades: Query<(
+        Entity,
+        &WaterSkill,
+        &TextureAtlas,
+        &Handle<SkillMaterial>,
+        &mut ShaderCrossfade,
+    )>,
+) {
+    for (entity, skill, atlas, material, mut fade) in fades.iter_mut() {
+        fade.timer.tick(time.delta());
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+        if fade.timer.finished() {
+            material.frame_data.blend = Vec4::ZERO;
+            commands.entity(entity).remove::<ShaderCrossfade>();
+            continue;
+        }
+        let inset = definitions
+            .get(&skill.definition)
+            .map_or(0.0, |definition| definition.frame_inset);
+        let Some(previous) = layouts
+            .get(&atlas.layout)
+            .and_then(|layout| FrameData::new(layout, fade.from, inset))
+        else {
+            continue;
+        };
+        let weight = 1.0 - fade.timer.fraction();
+        material.frame_data = material.frame_data.with_previous(&previous, weight);
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use super::pattern::SkillFacing;
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext};
+use crate::combat::SkillHitEvent;
+use crate::ground::GroundProbe;
+use crate::schedule::GameSet;
+
+// How far above a projectile the ground is probed from, so rising terrain in
+// its way is found as well as the floor under it
+const PROBE_HEIGHT: f32 = 20.0;
+
+pub struct ProjectileBehaviorPlugin;
+
+impl Plugin for ProjectileBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ProjectileSkill>()
+            .register_skill_behavior("projectile", ProjectileBehavior)
+            .add_systems(
+                Update,
+                (launch_projectiles, move_projectiles, pierce_on_hit)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// Definition params: `speed`, `arc` (launch angle in degrees above the
+// caster's facing), `gravity`, `pierce_count` (extra enemies it passes
+// through), `ricochet_count` (bounces off walls, and off the floor when it
+// falls).
+pub struct ProjectileBehavior;
+
+impl SkillBehavior for ProjectileBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert(ProjectileSkill {
+            caster: ctx.caster,
+            speed: definition.param("speed", 8.0),
+            arc: definition.param("arc", 0.0),
+            gravity: definition.param("gravity", 0.0),
+            pierce_left: definition.param("pierce_count", 0.0).max(0.0) as u32,
+            ricochets_left: definition.param("ricochet_count", 0.0).max(0.0) as u32,
+            velocity: Vec3::ZERO,
+            pierced: Vec::new(),
+        });
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ProjectileSkill {
+    pub caster: Entity,
+    pub speed: f32,
+    pub arc: f32,
+    pub gravity: f32,
+    pub pierce_left: u32,
+    pub ricochets_left: u32,
+    pub velocity: Vec3,
+    // Enemies already hit, which don't use up another pierce when hit again
+    pub pierced: Vec<Entity>,
+}
+
+// Sets off along the facing it was spawned with, or else the caster's, tilted
+// up by the arc
+fn launch_projectiles(
+    casters: Query<&GlobalTransform>,
+    mut projectiles: Query<(&mut ProjectileSkill, Option<&SkillFacing>), Added<ProjectileSkill>>,
+) {
+    for (mut projectile, spawn_facing) in projectiles.iter_mut() {
+        let facing = spawn_facing
+            .map(|facing| facing.0)
+            .or_else(|| {
+                let caster = casters.get(projectile.caster).ok()?;
+                caster.forward().with_y(0.0).try_normalize()
+            })
+            .unwrap_or(Vec3::NEG_Z);
+        let (sin, cos) = projectile.arc.to_radians().sin_cos();
+        projectile.velocity = (facing * cos + Vec3::Y * sin) * projectile.speed;
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    probe: GroundProbe,
+    mut projectiles: Query<(Entity, &mut Transform, &mut ProjectileSkill)>,
+) {
+    let dt = time.delta_seconds();
+    // Solid if the ground there reaches `height`; no ground at all is open air
+    let solid = |position: Vec3, height: f32| {
+        probe
+            .height_below(position.with_y(height + PROBE_HEIGHT))
+            .is_some_and(|ground| ground >= height)
+    };
+    for (entity, mut transform, mut projectile) in projectiles.iter_mut() {
+        projectile.velocity.y -= projectile.gravity * dt;
+        let current = transform.translation;
+        let next = current + projectile.velocity * dt;
+        if !solid(next, next.y) {
+            transform.translation = next;
+            continue;
+        }
+        if projectile.ricochets_left == 0 {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::Blocked);
+            continue;
+        }
+        projectile.ricochets_left -= 1;
+        // Ground higher than the projectile already is makes a wall; otherwise
+        // it came down onto the floor
+        if solid(next, current.y) {
+            let blocked_x = solid(current.with_x(next.x), current.y);
+            let blocked_z = solid(current.with_z(next.z), current.y);
+            if blocked_x || !blocked_z {
+                projectile.velocity.x = -projectile.velocity.x;
+            }
+            if blocked_z || !blocked_x {
+                projectile.velocity.z = -projectile.velocity.z;
+            }
+        } else {
+            projectile.velocity.y = projectile.velocity.y.abs();
+        }
+    }
+}
+
+// Each new enemy hit uses up a pierce, the one after the last ends the projectile
+fn pierce_on_hit(
+    mut commands: Commands,
+    mut hits: EventReader<SkillHitEvent>,
+    mut projectiles: Query<&mut ProjectileSkill>,
+) {
+    for hit in hits.read() {
+        let Ok(mut projectile) = projectiles.get_mut(hit.skill) else {
+            continue;
+        };
+        if projectile.pierced.contains(&hit.target) {
+            continue;
+        }
+        projectile.pierced.push(hit.target);
+        match projectile.pierce_left.checked_sub(1) {
+            Some(left) => projectile.pierce_left = left,
+            None => {
+                commands
+                    .entity(hit.skill)
+                    .despawn_skill(DespawnReason::Consumed);
+            }
+        }
+    }
+}
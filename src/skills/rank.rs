@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use super::{SkillBindings, SkillDefinition};
+use crate::locale::Locale;
+use crate::toast::ToastEvent;
+use crate::Player;
+
+// One upgrade step of a skill. Fractions stack additively across ranks, so two
+// ranks of `damage: 0.2` give +40% damage.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RankModifier {
+    pub damage: f32,
+    pub cooldown: f32,
+    pub lifetime: f32,
+    // More instances in the definition's fan, ring or burst pattern
+    pub projectiles: i32,
+    // Added to the definition's params, e.g. `{"count": 1.0}` for one more orb
+    pub params: HashMap<String, f32>,
+}
+
+impl SkillDefinition {
+    pub fn max_rank(&self) -> u32 {
+        self.ranks.len() as u32
+    }
+
+    // The definition as it applies to a caster with `rank` upgrades
+    pub fn resolve(&self, rank: u32) -> SkillDefinition {
+        let mut resolved = self.clone();
+        let mut damage = 0.0;
+        let mut cooldown = 0.0;
+        let mut lifetime = 0.0;
+        let mut projectiles = 0;
+        for modifier in self.ranks.iter().take(rank as usize) {
+            damage += modifier.damage;
+            cooldown += modifier.cooldown;
+            lifetime += modifier.lifetime;
+            projectiles += modifier.projectiles;
+            for (name, delta) in modifier.params.iter() {
+                *resolved.params.entry(name.clone()).or_insert(0.0) += delta;
+            }
+        }
+        resolved.damage *= (1.0 + damage).max(0.0);
+        resolved.cooldown *= (1.0 + cooldown).max(0.0);
+        resolved.lifetime *= (1.0 + lifetime).max(0.0);
+        resolved.pattern = self.pattern.with_extra_count(projectiles);
+        resolved
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct SkillRanks(HashMap<AssetId<SkillDefinition>, u32>);
+
+impl SkillRanks {
+    pub fn rank(&self, skill: AssetId<SkillDefinition>) -> u32 {
+        self.0.get(&skill).copied().unwrap_or(0)
+    }
+
+    pub fn upgrade(&mut self, skill: AssetId<SkillDefinition>) -> u32 {
+        let rank = self.0.entry(skill).or_insert(0);
+        *rank += 1;
+        *rank
+    }
+}
+
+// Unspent points earned by levelling up
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct SkillPoints(pub u32);
+
+pub fn upgrade_modifier_held(keyboard_input: &ButtonInput<KeyCode>) -> bool {
+    keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+// Shift + a skill key spends a point on that skill
+pub fn spend_skill_points(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<SkillBindings>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut players: Query<(&mut SkillRanks, &mut SkillPoints), With<Player>>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    if !upgrade_modifier_held(&keyboard_input) {
+        return;
+    }
+    // Each player spends its own points
+    for (mut ranks, mut points) in players.iter_mut() {
+        for (key, handle) in bindings.0.iter() {
+            if !keyboard_input.just_pressed(*key) {
+                continue;
+            }
+            let Some(definition) = definitions.get(handle) else {
+                continue;
+            };
+            if points.0 == 0 {
+                println!("No skill points to spend");
+            } else if ranks.rank(handle.id()) >= definition.max_rank() {
+                println!("{} is already at max rank", definition.name);
+            } else {
+                points.0 -= 1;
+                let rank = ranks.upgrade(handle.id());
+                println!("{} upgraded to rank {}", definition.name, rank);
+                toasts.send(ToastEvent::new(locale.format(
+                    "toast.skill_upgraded",
+                    &[("skill", &definition.name), ("rank", &rank)],
+                )));
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::despawn::{DespawnReason, DespawnSkill, DespawnSkillExt};
+use super::{SkillDefinition, SkillOwner, SkillSpawner};
+use crate::combat::{Health, Hitbox};
+use crate::damage::{mitigate, DamageDealt};
+use crate::schedule::GameSet;
+use crate::stats::Stats;
+use crate::Enemy;
+
+const REACTIONS_PATH: &str = "elements.reactions.ron";
+
+// Element tags from a skill's definition, e.g. `tags: ["water"]`
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SkillTags(pub Vec<String>);
+
+impl SkillTags {
+    pub fn has(&self, tag: &str) -> bool {
+        self.0.iter().any(|own| own == tag)
+    }
+}
+
+// What happens when a skill tagged with one of `tags` overlaps a skill tagged
+// with the other: both are used up, `skill` is spawned where they met and
+// enemies within `radius` take `bonus_damage`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reaction {
+    pub tags: (String, String),
+    #[serde(default)]
+    pub skill: Option<String>,
+    #[serde(default)]
+    pub bonus_damage: f32,
+    #[serde(default = "default_reaction_radius")]
+    pub radius: f32,
+    #[serde(skip)]
+    pub handle: Option<Handle<SkillDefinition>>,
+}
+
+fn default_reaction_radius() -> f32 {
+    2.0
+}
+
+impl Reaction {
+    pub fn matches(&self, a: &SkillTags, b: &SkillTags) -> bool {
+        let (first, second) = &self.tags;
+        (a.has(first) && b.has(second)) || (a.has(second) && b.has(first))
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct ReactionTable {
+    pub reactions: Vec<Reaction>,
+}
+
+#[derive(Default)]
+pub struct ReactionTableLoader;
+
+#[derive(Debug, Error)]
+pub enum ReactionTableLoaderError {
+    #[error("could not read reaction table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse reaction table: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for ReactionTableLoader {
+    type Asset = ReactionTable;
+    type Settings = ();
+    type Error = ReactionTableLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<ReactionTable, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut table: ReactionTable = ron::de::from_bytes(&bytes)?;
+        for reaction in table.reactions.iter_mut() {
+            reaction.handle = reaction
+                .skill
+                .as_ref()
+                .map(|skill| load_context.load(skill.clone()));
+        }
+        Ok(table)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["reactions.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct Reactions(pub Handle<ReactionTable>);
+
+pub struct ReactionPlugin;
+
+impl Plugin for ReactionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SkillTags>()
+            .init_asset::<ReactionTable>()
+            .init_asset_loader::<ReactionTableLoader>()
+            .add_systems(Startup, load_reactions)
+            .add_systems(Update, trigger_reactions.in_set(GameSet::Simulation));
+    }
+}
+
+fn load_reactions(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Reactions(asset_server.load(REACTIONS_PATH)));
+}
+
+fn trigger_reactions(
+    reactions: Res<Reactions>,
+    tables: Res<Assets<ReactionTable>>,
+    definitions: Res<Assets<SkillDefinition>>,
+    skills: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &SkillTags,
+            Option<&Hitbox>,
+            Option<&SkillOwner>,
+        ),
+        Without<DespawnSkill>,
+    >,
+    mut enemies: Query<(Entity, &GlobalTransform, &mut Health, Option<&Stats>), With<Enemy>>,
+    mut damage_dealt: EventWriter<DamageDealt>,
+    mut spawner: SkillSpawner,
+) {
+    let Some(table) = tables.get(&reactions.0) else {
+        return;
+    };
+    let skills: Vec<_> = skills.iter().collect();
+    // Each skill takes part in one reaction at most, it is used up by it
+    let mut used = Vec::new();
+    for (i, &(a_entity, a_transform, a_tags, a_hitbox, a_owner)) in skills.iter().enumerate() {
+        for &(b_entity, b_transform, b_tags, b_hitbox, _) in &skills[i + 1..] {
+            if used.contains(&a_entity) || used.contains(&b_entity) {
+                continue;
+            }
+            let Some(reaction) = table
+                .reactions
+                .iter()
+                .find(|reaction| reaction.matches(a_tags, b_tags))
+            else {
+                continue;
+            };
+            let b_reach = b_hitbox.map_or(0.0, |hitbox| hitbox.shape.reach());
+            let touching = match a_hitbox {
+                Some(hitbox) => {
+                    hitbox
+                        .shape
+                        .overlaps_sphere(a_transform, b_transform.translation(), b_reach)
+                }
+                None => {
+                    a_transform
+                        .translation()
+                        .distance(b_transform.translation())
+                        <= b_reach
+                }
+            };
+            if !touching {
+                continue;
+            }
+            used.extend([a_entity, b_entity]);
+            spawner
+                .commands
+                .entity(a_entity)
+                .despawn_skill(DespawnReason::Consumed);
+            spawner
+                .commands
+                .entity(b_entity)
+                .despawn_skill(DespawnReason::Consumed);
+
+            let position = (a_transform.translation() + b_transform.translation()) / 2.0;
+            let attacker = a_owner.map(|owner| owner.0);
+            let combined = reaction
+                .handle
+                .as_ref()
+                .and_then(|handle| Some((handle, definitions.get(handle)?)));
+            // Bonus damage is credited to the combined effect when there is one
+            let spawned = combined.and_then(|(handle, definition)| {
+                let caster = attacker.unwrap_or(a_entity);
+                match spawner.spawn(handle, definition, caster, position, None) {
+                    Ok(skill) => Some(skill),
+                    Err(error) => {
+                        spawner.report(&error);
+                        None
+                    }
+                }
+            });
+            let source = spawned.unwrap_or(a_entity);
+            println!("Reaction: {} + {}", reaction.tags.0, reaction.tags.1);
+            if reaction.bonus_damage <= 0.0 {
+                continue;
+            }
+            for (target, transform, mut health, stats) in enemies.iter_mut() {
+                if transform.translation().distance(position) > reaction.radius {
+                    continue;
+                }
+                let resistance = stats.map_or(0.0, Stats::resistance);
+                let amount = mitigate(reaction.bonus_damage, resistance);
+                health.current = (health.current - amount).max(0.0);
+                damage_dealt.send(DamageDealt {
+                    source,
+                    attacker,
+                    target,
+                    amount,
+                    is_crit: false,
+                });
+            }
+        }
+    }
+}
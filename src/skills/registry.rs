@@ -0,0 +1,324 @@
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use super::{SkillDefinition, WaterSkill};
+
+// Seconds without a live instance before the registry lets go of a skill
+pub const UNUSED_UNLOAD_SECONDS: f32 = 300.0;
+
+// Everything a skill instance is built from, prepared once per definition
+pub struct RegisteredSkill {
+    pub handle: Handle<SkillDefinition>,
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+    // Copied for each instance, which then gets its own tint and frame UVs
+    pub material: StandardMaterial,
+}
+
+impl RegisteredSkill {
+    fn new(handle: Handle<SkillDefinition>, definition: &SkillDefinition) -> Self {
+        Self {
+            handle,
+            atlas_layout: definition.atlas_layout.clone(),
+            material: StandardMaterial {
+                base_color_texture: Some(definition.image.clone()),
+                alpha_mode: definition.alpha_mode(),
+                unlit: !definition.lit,
+                ..default()
+            },
+        }
+    }
+}
+
+// Where a registered skill is at. A definition loads together with its
+// sheet, extra textures and on-hit sub-skills, and only counts as ready once
+// all of them have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillLoadState {
+    Loading,
+    Ready,
+    // The definition or something it references couldn't be loaded
+    Failed,
+    // Let go of for going unused, see `SkillRegistry::acquire`
+    Released,
+}
+
+// Skills by stable string ID, the asset path without `skills/` and the
+// extension: "water" for `skills/water.skill.ron`, "frost/ice" for a content
+// pack's `packs/frost/skills/ice.skill.ron`. Every definition that loads is
+// registered, including ones only referenced by bosses or on-hit effects.
+// Entries are prepared once everything the definition references is ready,
+// rebuilt when it is hot-reloaded and dropped when it is removed, so `get`
+// never hands out stale handles or half-loaded skills.
+//
+// The registry's own hold on a definition is let go once no instance of it
+// has been alive for `UNUSED_UNLOAD_SECONDS`, so its textures are freed
+// unless something else, like a key binding or a boss, still holds it.
+// `acquire` loads a released skill back.
+#[derive(Resource, Default)]
+pub struct SkillRegistry {
+    // In registration order, so listings are stable
+    entries: Vec<RegistryEntry>,
+    prepared: HashMap<AssetId<SkillDefinition>, RegisteredSkill>,
+}
+
+struct RegistryEntry {
+    id: String,
+    // Weak once released
+    handle: Handle<SkillDefinition>,
+    // Where to load it back from, `None` for definitions made at runtime,
+    // which are never released since they couldn't come back
+    path: Option<String>,
+    // Real seconds, when an instance of it was last alive
+    last_used: Option<f32>,
+    state: SkillLoadState,
+}
+
+impl RegistryEntry {
+    fn released(&self) -> bool {
+        !self.handle.is_strong()
+    }
+}
+
+pub fn skill_id(path: &str) -> String {
+    let path = path.strip_prefix("packs/").unwrap_or(path);
+    let path = path.strip_suffix(".skill.ron").unwrap_or(path);
+    path.replacen("skills/", "", 1)
+}
+
+impl SkillRegistry {
+    // Loads the definition at `path` and registers it under its ID
+    pub fn load(&mut self, asset_server: &AssetServer, path: &str) -> Handle<SkillDefinition> {
+        let handle = asset_server.load(path.to_string());
+        self.insert(skill_id(path), handle.clone(), Some(path.to_string()));
+        handle
+    }
+
+    pub fn register(&mut self, id: impl Into<String>, handle: Handle<SkillDefinition>) {
+        self.insert(id.into(), handle, None);
+    }
+
+    fn insert(&mut self, id: String, handle: Handle<SkillDefinition>, path: Option<String>) {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                // Nothing refers to the replaced definition through the
+                // registry anymore, so its prepared material can go
+                if entry.handle.id() != handle.id() {
+                    self.prepared.remove(&entry.handle.id());
+                }
+                entry.handle = handle;
+                entry.path = path.or(entry.path.take());
+            }
+            None => self.entries.push(RegistryEntry {
+                id,
+                handle,
+                path,
+                last_used: None,
+                state: SkillLoadState::Loading,
+            }),
+        }
+    }
+
+    // Handle for the ID, whether or not it has finished loading. Weak if the
+    // skill has been released, see `acquire`.
+    pub fn handle(&self, id: &str) -> Option<&Handle<SkillDefinition>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| &entry.handle)
+    }
+
+    // Strong handle for the ID, loading it back first if it was released
+    pub fn acquire(
+        &mut self,
+        asset_server: &AssetServer,
+        id: &str,
+    ) -> Option<Handle<SkillDefinition>> {
+        let entry = self.entries.iter_mut().find(|entry| entry.id == id)?;
+        if entry.released() {
+            // Still loaded if something else kept hold of it
+            entry.handle = match asset_server.get_id_handle(entry.handle.id()) {
+                Some(handle) => handle,
+                None => asset_server.load(entry.path.clone()?),
+            };
+            entry.last_used = None;
+            entry.state = SkillLoadState::Loading;
+        }
+        Some(entry.handle.clone())
+    }
+
+    // Every registered ID with where it is at, in registration order
+    pub fn load_states(&self) -> impl Iterator<Item = (&str, SkillLoadState)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry.state))
+    }
+
+    pub fn load_state(&self, id: &str) -> Option<SkillLoadState> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.state)
+    }
+
+    // Prepared entry for the ID, once it is ready
+    pub fn get(&self, id: &str) -> Option<&RegisteredSkill> {
+        self.prepared.get(&self.handle(id)?.id())
+    }
+
+    pub fn get_by_asset(&self, asset: AssetId<SkillDefinition>) -> Option<&RegisteredSkill> {
+        self.prepared.get(&asset)
+    }
+
+    pub fn id_of(&self, asset: AssetId<SkillDefinition>) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.handle.id() == asset)
+            .map(|entry| entry.id.as_str())
+    }
+
+    // Ready skills in registration order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &RegisteredSkill)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((entry.id.as_str(), self.prepared.get(&entry.handle.id())?)))
+    }
+
+    pub fn prepared_count(&self) -> usize {
+        self.prepared.len()
+    }
+
+    pub fn released_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.released()).count()
+    }
+}
+
+pub(super) fn update_skill_registry(
+    mut events: EventReader<AssetEvent<SkillDefinition>>,
+    mut registry: ResMut<SkillRegistry>,
+    definitions: Res<Assets<SkillDefinition>>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => {
+                let Some(definition) = definitions.get(id) else {
+                    continue;
+                };
+                let registered = registry
+                    .entries
+                    .iter()
+                    .find(|entry| entry.handle.id() == id)
+                    .map(|entry| entry.handle.clone());
+                let handle = match registered {
+                    Some(handle) => handle,
+                    None => {
+                        let (Some(path), Some(handle)) =
+                            (asset_server.get_path(id), asset_server.get_id_handle(id))
+                        else {
+                            continue;
+                        };
+                        let path = path.path().to_string_lossy().to_string();
+                        registry.insert(skill_id(&path), handle.clone(), Some(path));
+                        handle
+                    }
+                };
+                // Its textures and sub-skills may still be on their way
+                if matches!(event, AssetEvent::Added { .. }) {
+                    continue;
+                }
+                registry
+                    .prepared
+                    .insert(id, RegisteredSkill::new(handle, definition));
+            }
+            AssetEvent::Removed { id } => {
+                registry.prepared.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn update_load_states(
+    asset_server: Res<AssetServer>,
+    mut registry: ResMut<SkillRegistry>,
+) {
+    for entry in registry.entries.iter_mut() {
+        let state = if entry.released() {
+            SkillLoadState::Released
+        } else {
+            match asset_server.recursive_dependency_load_state(entry.handle.id()) {
+                RecursiveDependencyLoadState::Loaded => SkillLoadState::Ready,
+                RecursiveDependencyLoadState::Failed => SkillLoadState::Failed,
+                RecursiveDependencyLoadState::NotLoaded | RecursiveDependencyLoadState::Loading => {
+                    // Made at runtime rather than loaded, so it is ready as is
+                    if entry.path.is_none() {
+                        SkillLoadState::Ready
+                    } else {
+                        SkillLoadState::Loading
+                    }
+                }
+            }
+        };
+        if entry.state != state {
+            entry.state = state;
+        }
+    }
+}
+
+// Skills with an instance in the world count as used. Released skills cast
+// through a handle held elsewhere are taken back, and acquired ones are
+// prepared again once loaded.
+pub(super) fn track_skill_usage(
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut registry: ResMut<SkillRegistry>,
+    skills: Query<&WaterSkill>,
+) {
+    let now = time.elapsed_seconds();
+    let in_use: HashSet<AssetId<SkillDefinition>> =
+        skills.iter().map(|skill| skill.definition.id()).collect();
+    let SkillRegistry { entries, prepared } = &mut *registry;
+    for entry in entries.iter_mut() {
+        let id = entry.handle.id();
+        if entry.last_used.is_none() || in_use.contains(&id) {
+            entry.last_used = Some(now);
+            if entry.released() {
+                if let Some(handle) = asset_server.get_id_handle(id) {
+                    entry.handle = handle;
+                }
+            }
+        }
+        if entry.state == SkillLoadState::Ready && !prepared.contains_key(&id) {
+            if let Some(definition) = definitions.get(id) {
+                prepared.insert(id, RegisteredSkill::new(entry.handle.clone(), definition));
+            }
+        }
+    }
+}
+
+// Downgrades the handles of skills unused for too long and drops their
+// prepared materials, which hold the sheet texture. Prepared entries no
+// registry entry points at, like the old definition after a hot reload
+// registered a new one, go too.
+pub(super) fn release_unused_skills(time: Res<Time<Real>>, mut registry: ResMut<SkillRegistry>) {
+    let now = time.elapsed_seconds();
+    let SkillRegistry { entries, prepared } = &mut *registry;
+    for entry in entries.iter_mut() {
+        let unused = entry
+            .last_used
+            .is_some_and(|last_used| now - last_used > UNUSED_UNLOAD_SECONDS);
+        if unused && !entry.released() && entry.path.is_some() {
+            prepared.remove(&entry.handle.id());
+            entry.handle = entry.handle.clone_weak();
+        }
+    }
+    prepared.retain(|id, _| {
+        entries
+            .iter()
+            .any(|entry| !entry.released() && entry.handle.id() == *id)
+    });
+}
@@ -0,0 +1,172 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::despawn::{DespawnReason, DespawnSkillExt};
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext};
+use crate::billboard::Billboard;
+use crate::combat::{ManualHitDetection, SkillHitEvent};
+use crate::schedule::GameSet;
+use crate::threat::{Taunt, ThreatTarget};
+use crate::Enemy;
+
+pub struct SummonBehaviorPlugin;
+
+impl Plugin for SummonBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Summon>()
+            .register_skill_behavior("summon", SummonBehavior)
+            .add_systems(
+                Update,
+                (enforce_summon_cap, taunt_on_summon, summon_ai)
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+// The skill itself becomes an allied minion that lives for the definition's
+// lifetime. Definition params: `max_summons`, `speed`, `aggro_range`,
+// `attack_range`, `attack_interval`, `follow_distance`, `taunt` (threat towards
+// the summon it gives enemies within `aggro_range` when it appears).
+pub struct SummonBehavior;
+
+impl SkillBehavior for SummonBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert((
+            Summon {
+                owner: ctx.caster,
+                max_summons: definition.param("max_summons", 3.0).max(1.0) as usize,
+                speed: definition.param("speed", 2.5),
+                aggro_range: definition.param("aggro_range", 6.0),
+                attack_range: definition.param("attack_range", 1.0),
+                follow_distance: definition.param("follow_distance", 2.0),
+                attack_timer: Timer::from_seconds(
+                    definition.param("attack_interval", 0.8).max(0.05),
+                    TimerMode::Repeating,
+                ),
+                taunt: definition.param("taunt", 0.0),
+                order: 0,
+            },
+            ThreatTarget,
+            Billboard,
+            ManualHitDetection,
+        ));
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Summon {
+    pub owner: Entity,
+    pub max_summons: usize,
+    pub speed: f32,
+    pub aggro_range: f32,
+    pub attack_range: f32,
+    pub follow_distance: f32,
+    pub attack_timer: Timer,
+    pub taunt: f32,
+    // Spawn order, used to dismiss the oldest summon when over the cap
+    order: u64,
+}
+
+fn enforce_summon_cap(
+    mut commands: Commands,
+    mut next_order: Local<u64>,
+    mut summons: Query<(Entity, &mut Summon)>,
+) {
+    let mut any_added = false;
+    for (_, mut summon) in summons.iter_mut() {
+        if summon.is_added() {
+            *next_order += 1;
+            summon.order = *next_order;
+            any_added = true;
+        }
+    }
+    if !any_added {
+        return;
+    }
+
+    let mut by_owner: Vec<(Entity, Entity, u64, usize)> = summons
+        .iter()
+        .map(|(entity, summon)| (summon.owner, entity, summon.order, summon.max_summons))
+        .collect();
+    // Newest first, so everything past the cap for an owner is the oldest
+    by_owner.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)));
+    let mut kept = 0;
+    let mut current_owner = None;
+    for (owner, entity, _, max_summons) in by_owner {
+        if current_owner != Some(owner) {
+            current_owner = Some(owner);
+            kept = 0;
+        }
+        kept += 1;
+        if kept > max_summons {
+            commands
+                .entity(entity)
+                .despawn_skill(DespawnReason::Replaced);
+            println!("Summon dismissed, cap of {} reached", max_summons);
+        }
+    }
+}
+
+fn taunt_on_summon(
+    // The global transform isn't propagated yet on the frame it's spawned
+    summons: Query<(Entity, &Transform, &Summon), Added<Summon>>,
+    mut taunts: EventWriter<Taunt>,
+) {
+    for (entity, transform, summon) in summons.iter() {
+        if summon.taunt > 0.0 {
+            taunts.send(Taunt {
+                taunter: entity,
+                position: transform.translation,
+                radius: summon.aggro_range,
+                threat: summon.taunt,
+            });
+        }
+    }
+}
+
+fn summon_ai(
+    time: Res<Time>,
+    mut summons: Query<(Entity, &mut Transform, &mut Summon)>,
+    enemies: Query<(Entity, &Transform), (With<Enemy>, Without<Summon>)>,
+    owners: Query<&Transform, (Without<Summon>, Without<Enemy>)>,
+    mut hits: EventWriter<SkillHitEvent>,
+) {
+    for (entity, mut transform, mut summon) in summons.iter_mut() {
+        summon.attack_timer.tick(time.delta());
+        let position = transform.translation;
+        let target = enemies
+            .iter()
+            .map(|(enemy, enemy_transform)| (enemy, enemy_transform.translation))
+            .filter(|(_, enemy_position)| enemy_position.distance(position) <= summon.aggro_range)
+            .min_by(|(_, a), (_, b)| a.distance(position).total_cmp(&b.distance(position)));
+
+        let (destination, stop_distance) = match target {
+            Some((enemy, enemy_position)) => {
+                if enemy_position.distance(position) <= summon.attack_range
+                    && summon.attack_timer.just_finished()
+                {
+                    hits.send(SkillHitEvent {
+                        skill: entity,
+                        target: enemy,
+                    });
+                }
+                (enemy_position, summon.attack_range * 0.8)
+            }
+            None => match owners.get(summon.owner) {
+                Ok(owner) => (owner.translation, summon.follow_distance),
+                Err(_) => continue,
+            },
+        };
+
+        let mut to_destination = destination - position;
+        to_destination.y = 0.0;
+        let distance = to_destination.length();
+        if distance > stop_distance {
+            let step = (summon.speed * time.delta_seconds()).min(distance - stop_distance);
+            transform.translation += to_destination / distance * step;
+        }
+    }
+}
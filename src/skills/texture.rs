@@ -0,0 +1,291 @@
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::{
+    ImageAddressMode, ImageFilterMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum TextureFilter {
+    #[default]
+    Linear,
+    Nearest,
+}
+
+impl From<TextureFilter> for ImageFilterMode {
+    fn from(filter: TextureFilter) -> Self {
+        match filter {
+            TextureFilter::Linear => ImageFilterMode::Linear,
+            TextureFilter::Nearest => ImageFilterMode::Nearest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct TextureSampling {
+    // Used both when magnifying and minifying
+    pub filter: TextureFilter,
+    // Blending between mip levels
+    pub mipmap_filter: TextureFilter,
+    // Clamps 1..16, 1 is off
+    pub anisotropy: u16,
+    // Build a mip chain on load, the PNGs don't carry one
+    pub mipmaps: bool,
+    // Mip levels the sampler may pick from. StandardMaterial samples without a
+    // bias, so raising `min_mip` is how to trade sharpness for stability.
+    pub min_mip: f32,
+    pub max_mip: f32,
+}
+
+impl Default for TextureSampling {
+    fn default() -> Self {
+        Self {
+            filter: TextureFilter::Linear,
+            mipmap_filter: TextureFilter::Linear,
+            anisotropy: 1,
+            mipmaps: true,
+            min_mip: 0.0,
+            max_mip: 32.0,
+        }
+    }
+}
+
+impl TextureSampling {
+    pub fn descriptor(&self, address_mode: ImageAddressMode) -> ImageSamplerDescriptor {
+        let mut descriptor = ImageSamplerDescriptor {
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            mag_filter: self.filter.into(),
+            min_filter: self.filter.into(),
+            mipmap_filter: self.mipmap_filter.into(),
+            lod_min_clamp: self.min_mip.max(0.0),
+            lod_max_clamp: self.max_mip.max(self.min_mip.max(0.0)),
+            ..default()
+        };
+        // wgpu requires every filter to be linear for anisotropic sampling
+        if self.filter == TextureFilter::Linear && self.mipmap_filter == TextureFilter::Linear {
+            descriptor.anisotropy_clamp = self.anisotropy.clamp(1, 16);
+        }
+        descriptor
+    }
+}
+
+// How a skill texture is turned into the image the materials sample
+pub struct TextureImport {
+    pub sampler: ImageSamplerDescriptor,
+    pub premultiply_alpha: bool,
+    // Mip levels to generate including the full-size one, 1 or less for none
+    pub mip_levels: u32,
+}
+
+// Textures that need processing are loaded directly and stored as a labeled
+// copy, the rest go through the regular image loader
+pub async fn load_texture(
+    load_context: &mut LoadContext<'_>,
+    path: &str,
+    label: String,
+    import: TextureImport,
+) -> Handle<Image> {
+    let sampler = import.sampler;
+    let settings = move |settings: &mut ImageLoaderSettings| {
+        settings.sampler = ImageSampler::Descriptor(sampler.clone());
+    };
+    if !import.premultiply_alpha && import.mip_levels <= 1 {
+        return load_context
+            .loader()
+            .with_settings(settings)
+            .load(path.to_string());
+    }
+    let loaded = load_context
+        .loader()
+        .with_settings(settings.clone())
+        .direct()
+        .load::<Image>(path.to_string())
+        .await;
+    let mut image = match loaded {
+        Ok(loaded) => loaded.take(),
+        // Let the regular loader report it, the definition is still usable
+        Err(error) => {
+            warn!("Could not process skill texture {}: {}", path, error);
+            return load_context
+                .loader()
+                .with_settings(settings)
+                .load(path.to_string());
+        }
+    };
+    if import.premultiply_alpha {
+        premultiply_alpha(&mut image);
+    }
+    generate_mipmaps(&mut image, import.mip_levels, import.premultiply_alpha);
+    load_context.add_labeled_asset(label, image)
+}
+
+// Cuts one frame out of a sheet into an image of its own, e.g. for an icon.
+// Loaded as is, straight alpha and without mips, the way UI images are drawn.
+pub async fn load_frame(
+    load_context: &mut LoadContext<'_>,
+    path: &str,
+    rect: URect,
+    label: String,
+    sampler: ImageSamplerDescriptor,
+) -> Option<Handle<Image>> {
+    let sheet = match load_context
+        .loader()
+        .direct()
+        .load::<Image>(path.to_string())
+        .await
+    {
+        Ok(loaded) => loaded.take(),
+        Err(error) => {
+            warn!("Could not cut a frame from {}: {}", path, error);
+            return None;
+        }
+    };
+    let mut frame = crop(&sheet, rect)?;
+    frame.sampler = ImageSampler::Descriptor(sampler);
+    Some(load_context.add_labeled_asset(label, frame))
+}
+
+fn crop(image: &Image, rect: URect) -> Option<Image> {
+    rgba8_encoding(image)?;
+    let size = image.texture_descriptor.size;
+    let rect = rect.intersect(URect::new(0, 0, size.width, size.height));
+    if rect.is_empty() {
+        return None;
+    }
+    let row = (rect.width() * 4) as usize;
+    let mut data = Vec::with_capacity(row * rect.height() as usize);
+    for y in rect.min.y..rect.max.y {
+        let start = ((y * size.width + rect.min.x) * 4) as usize;
+        data.extend_from_slice(&image.data[start..start + row]);
+    }
+    Some(Image::new(
+        Extent3d {
+            width: rect.width(),
+            height: rect.height(),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+        RenderAssetUsages::default(),
+    ))
+}
+
+// Full chain down to 1x1 for a texture of this size
+pub fn full_mip_levels(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+// sRGB or not, or None for formats the processing can't handle
+fn rgba8_encoding(image: &Image) -> Option<bool> {
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb => Some(true),
+        TextureFormat::Rgba8Unorm => Some(false),
+        format => {
+            warn!("Cannot process {:?} skill textures", format);
+            None
+        }
+    }
+}
+
+fn decode(value: u8, srgb: bool) -> f32 {
+    let value = value as f32 / 255.0;
+    if srgb {
+        Srgba::gamma_function(value)
+    } else {
+        value
+    }
+}
+
+fn encode(value: f32, srgb: bool) -> u8 {
+    let value = if srgb {
+        Srgba::gamma_function_inverse(value)
+    } else {
+        value
+    };
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// Scales color by alpha. sRGB textures are converted in linear space so the
+// result blends the same as the straight-alpha original.
+fn premultiply_alpha(image: &mut Image) {
+    let Some(srgb) = rgba8_encoding(image) else {
+        return;
+    };
+    for pixel in image.data.chunks_exact_mut(4) {
+        let alpha = pixel[3] as f32 / 255.0;
+        for channel in &mut pixel[..3] {
+            *channel = encode(decode(*channel, srgb) * alpha, srgb);
+        }
+    }
+}
+
+// 2x2 box filter in linear space. Straight-alpha colors are weighted by alpha
+// so transparent texels don't darken the edges of the smaller levels.
+fn generate_mipmaps(image: &mut Image, max_levels: u32, premultiplied: bool) {
+    let size = image.texture_descriptor.size;
+    if max_levels <= 1
+        || image.texture_descriptor.mip_level_count > 1
+        || size.depth_or_array_layers != 1
+    {
+        return;
+    }
+    let Some(srgb) = rgba8_encoding(image) else {
+        return;
+    };
+    let (mut width, mut height) = (size.width, size.height);
+    let mut level: Vec<[f32; 4]> = image
+        .data
+        .chunks_exact(4)
+        .map(|p| {
+            [
+                decode(p[0], srgb),
+                decode(p[1], srgb),
+                decode(p[2], srgb),
+                p[3] as f32 / 255.0,
+            ]
+        })
+        .collect();
+    let mut levels = 1;
+    while levels < max_levels && (width > 1 || height > 1) {
+        let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+        let mut next = Vec::with_capacity((next_width * next_height) as usize);
+        for y in 0..next_height {
+            for x in 0..next_width {
+                let mut color = Vec3::ZERO;
+                let mut weights = 0.0;
+                let mut alpha = 0.0;
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (x * 2 + dx).min(width - 1);
+                    let sy = (y * 2 + dy).min(height - 1);
+                    let [r, g, b, a] = level[(sy * width + sx) as usize];
+                    let weight = if premultiplied { 1.0 } else { a };
+                    color += Vec3::new(r, g, b) * weight;
+                    weights += weight;
+                    alpha += a / 4.0;
+                }
+                if weights > 0.0 {
+                    color /= weights;
+                }
+                next.push([color.x, color.y, color.z, alpha]);
+            }
+        }
+        image.data.extend(next.iter().flat_map(|p| {
+            [
+                encode(p[0], srgb),
+                encode(p[1], srgb),
+                encode(p[2], srgb),
+                (p[3] * 255.0).round() as u8,
+            ]
+        }));
+        level = next;
+        width = next_width;
+        height = next_height;
+        levels += 1;
+    }
+    image.texture_descriptor.mip_level_count = levels;
+}
@@ -0,0 +1,71 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext};
+use crate::combat::Health;
+use crate::schedule::GameSet;
+use crate::time_scale::TimeScale;
+
+pub struct TimeZoneBehaviorPlugin;
+
+impl Plugin for TimeZoneBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TimeZoneSkill>()
+            .register_skill_behavior("time_zone", TimeZoneBehavior)
+            .add_systems(Update, apply_time_zones.in_set(GameSet::Simulation));
+    }
+}
+
+// Definition params: `radius` (defaults to the hit radius), `time_scale` (how
+// fast time runs for everyone inside but the caster, 0.5 = half speed).
+// Overlapping zones don't stack, the slowest one wins.
+pub struct TimeZoneBehavior;
+
+impl SkillBehavior for TimeZoneBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        skill.insert(TimeZoneSkill {
+            caster: ctx.caster,
+            radius: definition.param("radius", definition.hit_radius),
+            time_scale: TimeScale::clamp(definition.param("time_scale", 0.5)),
+        });
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct TimeZoneSkill {
+    pub caster: Entity,
+    pub radius: f32,
+    pub time_scale: f32,
+}
+
+// Characters leaving every zone, or outliving them, go back to normal speed
+fn apply_time_zones(
+    mut commands: Commands,
+    zones: Query<(&GlobalTransform, &TimeZoneSkill)>,
+    characters: Query<(Entity, &GlobalTransform, Option<&TimeScale>), With<Health>>,
+) {
+    for (entity, transform, current) in characters.iter() {
+        let scale = zones
+            .iter()
+            .filter(|(zone_transform, zone)| {
+                zone.caster != entity
+                    && zone_transform
+                        .translation()
+                        .distance(transform.translation())
+                        <= zone.radius
+            })
+            .map(|(_, zone)| zone.time_scale)
+            .reduce(f32::min);
+        match scale {
+            Some(scale) if current != Some(&TimeScale(scale)) => {
+                commands.entity(entity).insert(TimeScale(scale));
+            }
+            None if current.is_some() => {
+                commands.entity(entity).remove::<TimeScale>();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::phase::TRIGGER_CLIP;
+use super::{RegisterSkillBehavior, SkillBehavior, SkillSpawnContext, WaterSkill};
+use crate::animation::Animator;
+use crate::combat::{Hitbox, Hurtbox};
+use crate::hit_volume::HitShape;
+use crate::schedule::GameSet;
+use crate::Enemy;
+
+// How long a trap without a `trigger` clip keeps its hitbox once set off
+const DETONATION_TIME: f32 = 0.25;
+
+pub struct TrapBehaviorPlugin;
+
+impl Plugin for TrapBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TrapSkill>()
+            .register_skill_behavior("trap", TrapBehavior)
+            .add_systems(Update, trigger_traps.in_set(GameSet::Simulation));
+    }
+}
+
+// Definition params: `trigger_radius` (defaults to the hit radius), `fuse`
+// (seconds until it goes off by itself, 0 waits for an enemy). The trap loops
+// harmlessly until set off, then plays its `trigger` clip with the hitbox
+// active and ends the way skills do when their lifetime runs out.
+pub struct TrapBehavior;
+
+impl SkillBehavior for TrapBehavior {
+    fn spawn(&self, skill: &mut EntityCommands, ctx: &SkillSpawnContext) {
+        let definition = ctx.definition;
+        let fuse = definition.param("fuse", 0.0);
+        let detonation = match &definition.clips.trigger {
+            Some(trigger) => {
+                let frames = trigger.resolve(definition.frame_count().saturating_sub(1));
+                frames.len() as f32 * definition.frame_duration
+            }
+            None => DETONATION_TIME,
+        };
+        skill.remove::<Hitbox>().insert(TrapSkill {
+            trigger_radius: definition.param("trigger_radius", definition.hit_radius),
+            fuse: (fuse > 0.0).then(|| Timer::from_seconds(fuse, TimerMode::Once)),
+            detonation,
+            shape: definition.hit_shape(),
+            damage: definition.damage,
+        });
+    }
+}
+
+// Holds the hitbox back until the trap is set off
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct TrapSkill {
+    pub trigger_radius: f32,
+    pub fuse: Option<Timer>,
+    pub detonation: f32,
+    pub shape: HitShape,
+    pub damage: f32,
+}
+
+fn trigger_traps(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut traps: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut TrapSkill,
+        &mut WaterSkill,
+        Option<&mut Animator>,
+    )>,
+    enemies: Query<(&GlobalTransform, &Hurtbox), With<Enemy>>,
+) {
+    for (entity, transform, mut trap, mut skill, animator) in traps.iter_mut() {
+        let fuse_done = trap
+            .fuse
+            .as_mut()
+            .is_some_and(|fuse| fuse.tick(time.delta()).finished());
+        let stepped_on = enemies.iter().any(|(enemy, hurtbox)| {
+            let offset = enemy.translation() - transform.translation();
+            offset.with_y(0.0).length() <= trap.trigger_radius + hurtbox.radius
+        });
+        if !fuse_done && !stepped_on {
+            continue;
+        }
+        if let Some(mut animator) = animator {
+            animator.play(TRIGGER_CLIP);
+        }
+        // Ending the lifetime when the trigger clip does hands the rest over to
+        // the despawn clip, as for any other skill
+        skill.lifetime = Timer::from_seconds(trap.detonation, TimerMode::Once);
+        commands
+            .entity(entity)
+            .remove::<TrapSkill>()
+            .insert(Hitbox {
+                shape: trap.shape,
+                damage: trap.damage,
+            });
+    }
+}
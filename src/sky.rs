@@ -0,0 +1,171 @@
+use bevy::core_pipeline::Skybox;
+use bevy::pbr::environment_map::EnvironmentMapLight;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension,
+};
+use serde::Deserialize;
+
+use crate::schedule::GameSet;
+use crate::MainCamera;
+
+const GRADIENT_FACE_SIZE: u32 = 32;
+
+// Backdrop and image-based lighting for the current level. Levels replace the
+// resource to change the sky.
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Sky {
+    // Cubemap, either a .ktx2 cube or a PNG with the six faces stacked
+    // vertically (+X, -X, +Y, -Y, +Z, -Z). Without one, a gradient is
+    // generated from the colors below.
+    pub texture: Option<String>,
+    pub zenith: (f32, f32, f32),
+    pub horizon: (f32, f32, f32),
+    pub ground: (f32, f32, f32),
+    pub brightness: f32,
+    // Prefiltered .ktx2 cubemaps for environment lighting, falling back to the
+    // sky texture itself
+    pub diffuse_map: Option<String>,
+    pub specular_map: Option<String>,
+    // 0 turns environment lighting off
+    pub environment_intensity: f32,
+}
+
+impl Default for Sky {
+    fn default() -> Self {
+        Self {
+            texture: None,
+            zenith: (0.25, 0.45, 0.85),
+            horizon: (0.75, 0.85, 0.95),
+            ground: (0.3, 0.3, 0.28),
+            brightness: 1000.0,
+            diffuse_map: None,
+            specular_map: None,
+            environment_intensity: 250.0,
+        }
+    }
+}
+
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Sky>().add_systems(
+            Update,
+            (apply_sky, convert_stacked_cubemaps)
+                .chain()
+                .in_set(GameSet::Presentation),
+        );
+    }
+}
+
+fn apply_sky(
+    mut commands: Commands,
+    sky: Res<Sky>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<(Entity, Has<Skybox>), With<MainCamera>>,
+) {
+    for (camera, has_skybox) in cameras.iter() {
+        if has_skybox && !sky.is_changed() {
+            continue;
+        }
+        let image = match &sky.texture {
+            Some(path) => asset_server.load(path.clone()),
+            None => images.add(gradient_cubemap(&sky)),
+        };
+        let mut camera = commands.entity(camera);
+        camera.insert(Skybox {
+            image: image.clone(),
+            brightness: sky.brightness,
+        });
+        if sky.environment_intensity > 0.0 {
+            let load = |path: &Option<String>| {
+                path.as_ref()
+                    .map_or(image.clone(), |path| asset_server.load(path.clone()))
+            };
+            camera.insert(EnvironmentMapLight {
+                diffuse_map: load(&sky.diffuse_map),
+                specular_map: load(&sky.specular_map),
+                intensity: sky.environment_intensity,
+            });
+        } else {
+            camera.remove::<EnvironmentMapLight>();
+        }
+    }
+}
+
+// Stacked PNGs load as a single tall 2D image and need to be viewed as a cube
+fn convert_stacked_cubemaps(
+    mut events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    skyboxes: Query<&Skybox>,
+) {
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+        if !skyboxes.iter().any(|skybox| skybox.image.id() == *id) {
+            continue;
+        }
+        let Some(image) = images.get_mut(*id) else {
+            continue;
+        };
+        if image.texture_descriptor.array_layer_count() == 1 {
+            image.reinterpret_stacked_2d_as_array(image.height() / image.width());
+            image.texture_view_descriptor = Some(TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::Cube),
+                ..default()
+            });
+        }
+    }
+}
+
+fn gradient_cubemap(sky: &Sky) -> Image {
+    let color = |(r, g, b): (f32, f32, f32)| Color::srgb(r, g, b).to_linear();
+    let (zenith, horizon, ground) = (color(sky.zenith), color(sky.horizon), color(sky.ground));
+    let size = GRADIENT_FACE_SIZE;
+    let mut data = Vec::with_capacity((size * size * 6 * 4) as usize);
+    for face in 0..6 {
+        for y in 0..size {
+            for x in 0..size {
+                let u = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                let v = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                // Only the height of the view direction matters for the gradient
+                let direction = match face {
+                    0 => Vec3::new(1.0, -v, -u),
+                    1 => Vec3::new(-1.0, -v, u),
+                    2 => Vec3::new(u, 1.0, v),
+                    3 => Vec3::new(u, -1.0, -v),
+                    4 => Vec3::new(u, -v, 1.0),
+                    _ => Vec3::new(-u, -v, -1.0),
+                };
+                let height = direction.normalize().y;
+                let pixel = if height >= 0.0 {
+                    horizon.mix(&zenith, height.powf(0.6))
+                } else {
+                    horizon.mix(&ground, (-height * 4.0).min(1.0))
+                };
+                data.extend_from_slice(&Color::from(pixel).to_srgba().to_u8_array());
+            }
+        }
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 6,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..default()
+    });
+    image
+}
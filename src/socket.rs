@@ -0,0 +1,36 @@
+use bevy::ecs::world::EntityWorldMut;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+// Named attachment point, a child of the character so it follows whatever
+// moves the character's body. Skills with `socket: Some("hand")` spawn on it
+// and stay attached.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Socket(pub String);
+
+// A character's sockets by name
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Sockets(pub HashMap<String, Entity>);
+
+impl Sockets {
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.0.get(name).copied()
+    }
+}
+
+// Spawns a child socket for each name at its offset from the character
+pub fn spawn_sockets(entity: &mut EntityWorldMut, offsets: &HashMap<String, Vec3>) {
+    let mut sockets = Sockets::default();
+    entity.with_children(|character| {
+        for (name, offset) in offsets.iter() {
+            let socket = character.spawn((
+                SpatialBundle::from_transform(Transform::from_translation(*offset)),
+                Socket(name.clone()),
+            ));
+            sockets.0.insert(name.clone(), socket.id());
+        }
+    });
+    entity.insert(sockets);
+}
@@ -0,0 +1,537 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::drag_drop::{DragState, Draggable};
+use crate::hud_layout::SafeAreaAnchor;
+use crate::locale::{Locale, Localized};
+use crate::render_preview::{PreviewStage, RenderPreview};
+use crate::schedule::GameSet;
+use crate::screen_space::to_ui;
+use crate::skills::phase::LOOP_CLIP;
+use crate::skills::rank::SkillRanks;
+use crate::skills::{frame_uv_transform, SkillDefinition, SkillRegistry};
+use crate::Player;
+
+// Not K, which steers the player along with I, J and L
+pub const SPELLBOOK_KEY: KeyCode = KeyCode::KeyB;
+const ICON_SIZE: f32 = 28.0;
+const PREVIEW_SIZE: f32 = 160.0;
+// World size of the previewed skill, filling most of the preview camera's view
+const PREVIEW_QUAD_SIZE: f32 = 2.8;
+const ENTRY_COLOR: Color = Color::srgba(0.1, 0.12, 0.15, 0.75);
+const SELECTED_ENTRY_COLOR: Color = Color::srgba(0.25, 0.32, 0.4, 0.9);
+// Tooltips sit this far right of and below the cursor
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+#[derive(Component)]
+struct SpellbookPanel;
+
+#[derive(Component)]
+struct SpellbookList;
+
+// A skill listed in the spellbook, selected by clicking it and dragged onto
+// the hotbar to bind it
+#[derive(Component)]
+pub struct SpellbookEntry(pub Handle<SkillDefinition>);
+
+// Plays the selected skill's loop
+#[derive(Component)]
+struct SpellbookPreview;
+
+// The selected skill's quad on the preview stage
+#[derive(Component)]
+struct PreviewSprite(Handle<SkillDefinition>);
+
+#[derive(Resource)]
+struct PreviewQuad(Handle<Mesh>);
+
+#[derive(Component)]
+struct SpellbookDetails;
+
+#[derive(Component)]
+struct SpellbookTooltip;
+
+// The skill shown in the preview pane, the first listed until one is clicked
+#[derive(Resource, Default)]
+struct SpellbookSelection(Option<Handle<SkillDefinition>>);
+
+pub struct SpellbookPlugin;
+
+impl Plugin for SpellbookPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpellbookSelection>()
+            .add_systems(Startup, (create_preview_quad, spawn_spellbook))
+            .add_systems(
+                Update,
+                (toggle_spellbook, select_spellbook_entry).in_set(GameSet::Input),
+            )
+            .add_systems(
+                Update,
+                (
+                    list_spellbook_skills,
+                    preview_selected_skill,
+                    sync_preview_frames,
+                    describe_selected_skill,
+                    show_spellbook_tooltip,
+                )
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+// Named after the skill once it has loaded, after its ID until then
+pub fn skill_label(
+    definitions: &Assets<SkillDefinition>,
+    registry: &SkillRegistry,
+    skill: &Handle<SkillDefinition>,
+) -> String {
+    match definitions.get(skill) {
+        Some(definition) => definition.name.clone(),
+        None => registry.id_of(skill.id()).unwrap_or("...").to_string(),
+    }
+}
+
+// The definition's icon, or for skills made at runtime, which have none,
+// its representative frame shown straight from the sheet
+pub fn skill_icon(definition: &SkillDefinition) -> (UiImage, Option<TextureAtlas>) {
+    if definition.icon_image != Handle::default() {
+        return (UiImage::new(definition.icon_image.clone()), None);
+    }
+    let atlas = TextureAtlas {
+        layout: definition.atlas_layout.clone(),
+        index: definition.representative_frame(),
+    };
+    (UiImage::new(definition.image.clone()), Some(atlas))
+}
+
+// Description, cooldown, cost and rank, one per line. `definition` is
+// already resolved for `rank`.
+fn skill_details(
+    locale: &Locale,
+    definition: &SkillDefinition,
+    rank: u32,
+    max_rank: u32,
+) -> String {
+    let mut lines = Vec::new();
+    if !definition.description.is_empty() {
+        lines.push(definition.description.clone());
+    }
+    let cooldown = format!("{:.1}", definition.cooldown);
+    lines.push(locale.format("spellbook.cooldown", &[("seconds", &cooldown)]));
+    if definition.mana_per_second > 0.0 {
+        let mana = format!("{:.0}", definition.mana_per_second);
+        lines.push(locale.format("spellbook.cost", &[("mana", &mana)]));
+    } else {
+        lines.push(locale.get("spellbook.no_cost").to_string());
+    }
+    if max_rank > 0 {
+        lines.push(locale.format("spellbook.rank", &[("rank", &rank), ("max", &max_rank)]));
+    }
+    lines.join("\n")
+}
+
+fn create_preview_quad(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(PreviewQuad(meshes.add(Rectangle::new(1.0, 1.0))));
+}
+
+fn spawn_spellbook(mut commands: Commands) {
+    let text = |size: f32| TextStyle {
+        font_size: size,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(12.0),
+                    top: Val::Px(80.0),
+                    column_gap: Val::Px(12.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.07, 0.1, 0.85).into(),
+                border_radius: BorderRadius::all(Val::Px(6.0)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            SpellbookPanel,
+            SafeAreaAnchor::default(),
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(220.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|column| {
+                    column.spawn((
+                        TextBundle::from_section("", text(16.0)),
+                        Localized::new("hotbar.spellbook"),
+                    ));
+                    column.spawn((
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(4.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        SpellbookList,
+                    ));
+                });
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(PREVIEW_SIZE + 40.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|pane| {
+                    pane.spawn((
+                        ImageBundle {
+                            style: Style {
+                                width: Val::Px(PREVIEW_SIZE),
+                                height: Val::Px(PREVIEW_SIZE),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        RenderPreview::stage(UVec2::splat(PREVIEW_SIZE as u32 * 2)),
+                        SpellbookPreview,
+                    ));
+                    pane.spawn((
+                        TextBundle::from_section("", text(13.0)).with_style(Style {
+                            max_width: Val::Px(PREVIEW_SIZE + 40.0),
+                            ..default()
+                        }),
+                        SpellbookDetails,
+                    ));
+                });
+        });
+
+    // Kept outside the panel so it's drawn over everything else
+    commands.spawn((
+        TextBundle {
+            z_index: ZIndex::Global(100),
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section("", text(12.0))
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    max_width: Val::Px(220.0),
+                    padding: UiRect::all(Val::Px(6.0)),
+                    ..default()
+                })
+                .with_background_color(Color::srgba(0.02, 0.03, 0.05, 0.95))
+        },
+        SpellbookTooltip,
+    ));
+}
+
+fn toggle_spellbook(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut panels: Query<&mut Visibility, With<SpellbookPanel>>,
+) {
+    if !keyboard_input.just_pressed(SPELLBOOK_KEY) {
+        return;
+    }
+    for mut visibility in panels.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn select_spellbook_entry(
+    mut selection: ResMut<SpellbookSelection>,
+    entries: Query<(&Interaction, &SpellbookEntry), Changed<Interaction>>,
+) {
+    for (interaction, entry) in entries.iter() {
+        if *interaction == Interaction::Pressed && selection.0.as_ref() != Some(&entry.0) {
+            selection.0 = Some(entry.0.clone());
+        }
+    }
+}
+
+// Every registered skill with its icon and the player's rank in it. Listed
+// again only when that changes, so entries aren't replaced while being
+// dragged.
+fn list_spellbook_skills(
+    mut commands: Commands,
+    registry: Res<SkillRegistry>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut selection: ResMut<SpellbookSelection>,
+    players: Query<&SkillRanks, With<Player>>,
+    mut listed: Local<Vec<String>>,
+    lists: Query<Entity, With<SpellbookList>>,
+) {
+    let Ok(list) = lists.get_single() else {
+        return;
+    };
+    let ranks = players.iter().next();
+    let mut skills: Vec<_> = registry
+        .iter()
+        .map(|(_, skill)| {
+            let label = skill_label(&definitions, &registry, &skill.handle);
+            let rank = ranks.map_or(0, |ranks| ranks.rank(skill.handle.id()));
+            let max_rank = definitions.get(&skill.handle).map_or(0, |d| d.max_rank());
+            let rank = (max_rank > 0).then(|| format!("{}/{}", rank, max_rank));
+            (label, rank, skill.handle.clone())
+        })
+        .collect();
+    skills.sort_by(|a, b| a.0.cmp(&b.0));
+    let keys: Vec<String> = skills
+        .iter()
+        .map(|(label, rank, _)| format!("{} {:?}", label, rank))
+        .collect();
+    if *listed == keys {
+        return;
+    }
+    *listed = keys;
+    if selection.0.is_none() {
+        selection.0 = skills.first().map(|(.., skill)| skill.clone());
+    }
+
+    commands.entity(list).despawn_descendants();
+    commands.entity(list).with_children(|list| {
+        for (label, rank, skill) in skills {
+            let icon = definitions.get(&skill).map(skill_icon);
+            list.spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(6.0), Val::Px(4.0)),
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    background_color: ENTRY_COLOR.into(),
+                    border_radius: BorderRadius::all(Val::Px(4.0)),
+                    ..default()
+                },
+                SpellbookEntry(skill),
+                Draggable {
+                    label: label.clone(),
+                },
+            ))
+            .with_children(|entry| {
+                let mut icon_node = entry.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(ICON_SIZE),
+                        height: Val::Px(ICON_SIZE),
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                });
+                if let Some((image, atlas)) = icon {
+                    icon_node.insert(image);
+                    if let Some(atlas) = atlas {
+                        icon_node.insert(atlas);
+                    }
+                }
+                entry.spawn(
+                    TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font_size: 13.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        flex_grow: 1.0,
+                        ..default()
+                    }),
+                );
+                if let Some(rank) = rank {
+                    entry.spawn(TextBundle::from_section(
+                        rank,
+                        TextStyle {
+                            font_size: 11.0,
+                            color: Color::srgba(1.0, 0.85, 0.4, 0.9),
+                            ..default()
+                        },
+                    ));
+                }
+            });
+        }
+    });
+}
+
+// Swaps the preview stage over to a quad of the selected skill, looping it
+// from the start. Waits for the definition if it is still loading.
+fn preview_selected_skill(
+    mut commands: Commands,
+    selection: Res<SpellbookSelection>,
+    registry: Res<SkillRegistry>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mesh: Res<PreviewQuad>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shown: Local<Option<AssetId<SkillDefinition>>>,
+    previews: Query<&PreviewStage, With<SpellbookPreview>>,
+    mut entries: Query<(&SpellbookEntry, &mut BackgroundColor)>,
+) {
+    let selected = selection.0.as_ref();
+    for (entry, mut color) in entries.iter_mut() {
+        let wanted = if Some(&entry.0) == selected {
+            SELECTED_ENTRY_COLOR
+        } else {
+            ENTRY_COLOR
+        };
+        color.set_if_neq(wanted.into());
+    }
+    let Some(selected) = selected else {
+        return;
+    };
+    if *shown == Some(selected.id()) {
+        return;
+    }
+    let (Some(definition), Some(skill), Ok(stage)) = (
+        definitions.get(selected),
+        registry.get_by_asset(selected.id()),
+        previews.get_single(),
+    ) else {
+        return;
+    };
+    let mut animator = definition.animator();
+    animator.play(LOOP_CLIP);
+    commands.entity(stage.0).despawn_descendants();
+    commands.entity(stage.0).with_children(|stage| {
+        stage.spawn((
+            PbrBundle {
+                mesh: mesh.0.clone(),
+                material: materials.add(skill.material.clone()),
+                transform: Transform::from_scale(Vec3::splat(PREVIEW_QUAD_SIZE)),
+                ..default()
+            },
+            TextureAtlas {
+                layout: definition.atlas_layout.clone(),
+                index: animator.frame(),
+            },
+            animator,
+            PreviewSprite(selected.clone()),
+        ));
+    });
+    *shown = Some(selected.id());
+}
+
+// The quad is drawn with a StandardMaterial, so frames are applied through
+// its UV transform like skill instances'
+fn sync_preview_frames(
+    definitions: Res<Assets<SkillDefinition>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sprites: Query<
+        (&PreviewSprite, &TextureAtlas, &Handle<StandardMaterial>),
+        Changed<TextureAtlas>,
+    >,
+) {
+    for (sprite, atlas, material) in sprites.iter() {
+        let inset = definitions
+            .get(&sprite.0)
+            .map_or(0.0, |definition| definition.frame_inset);
+        let (Some(uv_transform), Some(material)) = (
+            layouts
+                .get(&atlas.layout)
+                .and_then(|layout| frame_uv_transform(layout, atlas.index, inset)),
+            materials.get_mut(material),
+        ) else {
+            continue;
+        };
+        material.uv_transform = uv_transform;
+    }
+}
+
+fn describe_selected_skill(
+    locale: Res<Locale>,
+    selection: Res<SpellbookSelection>,
+    definitions: Res<Assets<SkillDefinition>>,
+    players: Query<&SkillRanks, With<Player>>,
+    mut details: Query<&mut Text, With<SpellbookDetails>>,
+) {
+    let text = selection
+        .0
+        .as_ref()
+        .and_then(|skill| {
+            let definition = definitions.get(skill)?;
+            let rank = players
+                .iter()
+                .next()
+                .map_or(0, |ranks| ranks.rank(skill.id()));
+            let details = skill_details(
+                &locale,
+                &definition.resolve(rank),
+                rank,
+                definition.max_rank(),
+            );
+            Some(format!("{}\n\n{}", definition.name, details))
+        })
+        .unwrap_or_default();
+    for mut details in details.iter_mut() {
+        if details.sections[0].value != text {
+            details.sections[0].value = text.clone();
+        }
+    }
+}
+
+// Follows the cursor over a hovered entry, and stays out of the way of drags
+fn show_spellbook_tooltip(
+    locale: Res<Locale>,
+    drag: Res<DragState>,
+    ui_scale: Res<UiScale>,
+    definitions: Res<Assets<SkillDefinition>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    players: Query<&SkillRanks, With<Player>>,
+    entries: Query<(&Interaction, &SpellbookEntry, &ViewVisibility)>,
+    mut tooltips: Query<(&mut Text, &mut Style, &mut Visibility), With<SpellbookTooltip>>,
+) {
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .map(|cursor| to_ui(cursor, &ui_scale));
+    let hovered = entries
+        .iter()
+        .filter(|(.., visibility)| visibility.get())
+        .find(|(interaction, ..)| **interaction == Interaction::Hovered)
+        .and_then(|(_, entry, _)| {
+            let definition = definitions.get(&entry.0)?;
+            let rank = players
+                .iter()
+                .next()
+                .map_or(0, |ranks| ranks.rank(entry.0.id()));
+            Some(skill_details(
+                &locale,
+                &definition.resolve(rank),
+                rank,
+                definition.max_rank(),
+            ))
+        })
+        .filter(|_| drag.dragging().is_none());
+    for (mut text, mut style, mut visibility) in tooltips.iter_mut() {
+        let (Some(details), Some(cursor)) = (&hovered, cursor) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        if text.sections[0].value != *details {
+            text.sections[0].value = details.clone();
+        }
+        style.left = Val::Px(cursor.x + TOOLTIP_OFFSET.x);
+        style.top = Val::Px(cursor.y + TOOLTIP_OFFSET.y);
+    }
+}
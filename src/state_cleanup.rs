@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::pause::GameState;
+use crate::schedule::GameSet;
+use crate::skills::despawn::DespawnSkillExt;
+use crate::skills::{DespawnReason, WaterSkill};
+
+// Despawned once `S` leaves this state
+#[derive(Component)]
+pub struct DespawnOnExit<S: States>(pub S);
+
+// Despawned once `S` enters this state
+#[derive(Component)]
+pub struct DespawnOnEnter<S: States>(pub S);
+
+// Despawned when the level is torn down for the next one
+#[derive(Component)]
+pub struct LevelScoped;
+
+// Sent to tear the current level down before the next one is set up. The
+// player and anything persistent stay.
+#[derive(Event)]
+pub struct LevelTransition;
+
+pub trait StateCleanupExt {
+    // Despawns `DespawnOnExit<S>` and `DespawnOnEnter<S>` entities as `S` changes
+    fn add_state_cleanup<S: States>(&mut self) -> &mut Self;
+}
+
+impl StateCleanupExt for App {
+    fn add_state_cleanup<S: States>(&mut self) -> &mut Self {
+        // Transitions are applied before Update, so this sees them the same frame
+        self.add_systems(Update, clean_up_on_transition::<S>.in_set(GameSet::Input))
+    }
+}
+
+// What happens to skills is up to how they were spawned: every skill ends on
+// game over and only auras marked `persistent` outlive the level. Pausing
+// needs nothing here, skills freeze with the rest of the simulation.
+pub struct StateCleanupPlugin;
+
+impl Plugin for StateCleanupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LevelTransition>()
+            .add_state_cleanup::<GameState>()
+            .add_systems(Update, clean_up_level.in_set(GameSet::Input));
+    }
+}
+
+fn clean_up_on_transition<S: States>(
+    mut commands: Commands,
+    mut transitions: EventReader<StateTransitionEvent<S>>,
+    on_exit: Query<(Entity, &DespawnOnExit<S>, Has<WaterSkill>)>,
+    on_enter: Query<(Entity, &DespawnOnEnter<S>, Has<WaterSkill>)>,
+) {
+    // At most one transition per state type each frame
+    let Some(transition) = transitions.read().last() else {
+        return;
+    };
+    if transition.entered == transition.exited {
+        return;
+    }
+    let exited = on_exit
+        .iter()
+        .filter(|(_, scope, _)| transition.exited.as_ref() == Some(&scope.0))
+        .map(|(entity, _, is_skill)| (entity, is_skill));
+    let entered = on_enter
+        .iter()
+        .filter(|(_, scope, _)| transition.entered.as_ref() == Some(&scope.0))
+        .map(|(entity, _, is_skill)| (entity, is_skill));
+    for (entity, is_skill) in exited.chain(entered) {
+        despawn(&mut commands, entity, is_skill);
+    }
+}
+
+fn clean_up_level(
+    mut commands: Commands,
+    mut transitions: EventReader<LevelTransition>,
+    scoped: Query<(Entity, Has<WaterSkill>), With<LevelScoped>>,
+) {
+    if transitions.read().count() == 0 {
+        return;
+    }
+    for (entity, is_skill) in scoped.iter() {
+        despawn(&mut commands, entity, is_skill);
+    }
+}
+
+// Skills go through their own despawn so anything tracking them hears why
+fn despawn(commands: &mut Commands, entity: Entity, is_skill: bool) {
+    if is_skill {
+        commands
+            .entity(entity)
+            .despawn_skill(DespawnReason::StateChanged);
+    } else {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::damage::Offense;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Reflect)]
+pub enum Stat {
+    Attack,
+    Defense,
+    Speed,
+    CritChance,
+    CritMultiplier,
+    CooldownReduction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Reflect)]
+pub enum ModifierKind {
+    // Added to the base value
+    Flat,
+    // Fraction applied after flat modifiers, 0.1 = +10%
+    Percent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ModifierSource {
+    Buff,
+    Difficulty,
+    Gear,
+    Level,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub value: f32,
+    pub source: ModifierSource,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Deserialize, Reflect)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct Stats {
+    pub attack: f32,
+    pub defense: f32,
+    pub speed: f32,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    pub cooldown_reduction: f32,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            attack: 0.0,
+            defense: 0.0,
+            speed: 3.0,
+            crit_chance: 0.05,
+            crit_multiplier: 1.5,
+            cooldown_reduction: 0.0,
+        }
+    }
+}
+
+impl Stats {
+    pub fn get(&self, stat: Stat) -> f32 {
+        match stat {
+            Stat::Attack => self.attack,
+            Stat::Defense => self.defense,
+            Stat::Speed => self.speed,
+            Stat::CritChance => self.crit_chance,
+            Stat::CritMultiplier => self.crit_multiplier,
+            Stat::CooldownReduction => self.cooldown_reduction,
+        }
+    }
+
+    pub fn get_mut(&mut self, stat: Stat) -> &mut f32 {
+        match stat {
+            Stat::Attack => &mut self.attack,
+            Stat::Defense => &mut self.defense,
+            Stat::Speed => &mut self.speed,
+            Stat::CritChance => &mut self.crit_chance,
+            Stat::CritMultiplier => &mut self.crit_multiplier,
+            Stat::CooldownReduction => &mut self.cooldown_reduction,
+        }
+    }
+
+    pub fn offense(&self) -> Offense {
+        Offense {
+            attack: self.attack,
+            crit_chance: self.crit_chance,
+            crit_multiplier: self.crit_multiplier,
+        }
+    }
+
+    // Diminishing returns: 100 defense halves incoming damage
+    pub fn resistance(&self) -> f32 {
+        let defense = self.defense.max(0.0);
+        defense / (defense + 100.0)
+    }
+
+    pub fn cooldown_multiplier(&self) -> f32 {
+        1.0 - self.cooldown_reduction.clamp(0.0, 0.8)
+    }
+}
+
+// Unmodified values; `Stats` is recomputed from these and the modifier stack
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct BaseStats(pub Stats);
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct StatModifiers(Vec<StatModifier>);
+
+impl StatModifiers {
+    pub fn add(&mut self, modifier: StatModifier) {
+        self.0.push(modifier);
+    }
+
+    pub fn remove_source(&mut self, source: ModifierSource) {
+        self.0.retain(|modifier| modifier.source != source);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatModifier> {
+        self.0.iter()
+    }
+
+    pub fn apply(&self, base: &Stats) -> Stats {
+        let mut stats = *base;
+        for stat in [
+            Stat::Attack,
+            Stat::Defense,
+            Stat::Speed,
+            Stat::CritChance,
+            Stat::CritMultiplier,
+            Stat::CooldownReduction,
+        ] {
+            let (flat, percent) = self.0.iter().filter(|modifier| modifier.stat == stat).fold(
+                (0.0, 0.0),
+                |(flat, percent), modifier| match modifier.kind {
+                    ModifierKind::Flat => (flat + modifier.value, percent),
+                    ModifierKind::Percent => (flat, percent + modifier.value),
+                },
+            );
+            *stats.get_mut(stat) = (base.get(stat) + flat) * (1.0 + percent).max(0.0);
+        }
+        stats
+    }
+}
+
+#[derive(Bundle)]
+pub struct StatsBundle {
+    pub base: BaseStats,
+    pub modifiers: StatModifiers,
+    pub stats: Stats,
+}
+
+impl StatsBundle {
+    pub fn new(base: Stats) -> Self {
+        Self {
+            base: BaseStats(base),
+            modifiers: StatModifiers::default(),
+            stats: base,
+        }
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Stats>()
+            .register_type::<BaseStats>()
+            .register_type::<StatModifiers>()
+            .add_systems(PreUpdate, recompute_stats);
+    }
+}
+
+// Only entities whose base or modifiers changed are recomputed
+pub fn recompute_stats(
+    mut query: Query<
+        (&BaseStats, &StatModifiers, &mut Stats),
+        Or<(Changed<BaseStats>, Changed<StatModifiers>)>,
+    >,
+) {
+    for (base, modifiers, mut stats) in query.iter_mut() {
+        stats.set_if_neq(modifiers.apply(&base.0));
+    }
+}
@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::animation::{AnimationSpeed, Animator};
+use crate::combat::{Health, Invulnerable};
+use crate::console::RegisterConsoleCommand;
+use crate::damage::mitigate;
+use crate::schedule::GameSet;
+use crate::stats::Stats;
+use crate::Player;
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum StatusKind {
+    Burn { damage_per_second: f32 },
+    Slow { factor: f32 },
+    // Moves faster by `factor`, 0.3 = 30% faster
+    Haste { factor: f32 },
+    Regen { health_per_second: f32 },
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Burn { .. } => "burn",
+            StatusKind::Slow { .. } => "slow",
+            StatusKind::Haste { .. } => "haste",
+            StatusKind::Regen { .. } => "regen",
+        }
+    }
+
+    pub fn is_buff(&self) -> bool {
+        matches!(self, StatusKind::Haste { .. } | StatusKind::Regen { .. })
+    }
+}
+
+// What happens when an effect lands on a target that already has one of the
+// same kind. Written next to the effect in a definition, e.g.
+// `Burn(damage_per_second: 4.0, duration: 3.0, stacking: Stack(max: 3, falloff: 0.5))`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Reflect)]
+pub enum StackPolicy {
+    // The effect already running wins until it expires
+    NoStack,
+    // The new effect replaces the running one
+    #[default]
+    Refresh,
+    // One stack per skill instance up to `max`, the strongest at full potency
+    // and each further one multiplied by `falloff` again. A skill hitting
+    // again refreshes its own stack, a full stack replaces the one closest to
+    // expiring.
+    Stack {
+        max: u32,
+        falloff: f32,
+    },
+}
+
+impl StackPolicy {
+    // Potency of the effect ranked `rank` among its kind, strongest first
+    pub fn potency(&self, rank: usize) -> f32 {
+        match *self {
+            StackPolicy::Stack { falloff, .. } => falloff.clamp(0.0, 1.0).powi(rank as i32),
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub remaining: Timer,
+    pub source: Option<Entity>,
+    pub stacking: StackPolicy,
+    // Skill entity that applied the effect, so it refreshes its own stack
+    pub origin: Option<Entity>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, duration: f32, source: Option<Entity>) -> Self {
+        Self {
+            kind,
+            remaining: Timer::from_seconds(duration, TimerMode::Once),
+            source,
+            stacking: StackPolicy::default(),
+            origin: None,
+        }
+    }
+
+    pub fn with_stacking(mut self, stacking: StackPolicy, origin: Option<Entity>) -> Self {
+        self.stacking = stacking;
+        self.origin = origin;
+        self
+    }
+
+    fn same_kind(&self, other: &StatusEffect) -> bool {
+        std::mem::discriminant(&self.kind) == std::mem::discriminant(&other.kind)
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    // How a re-applied effect of the same kind combines is up to the new
+    // effect's `StackPolicy`
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let mut same_kind = self
+            .0
+            .iter_mut()
+            .filter(|existing| existing.same_kind(&effect))
+            .peekable();
+        match effect.stacking {
+            StackPolicy::NoStack => {
+                if same_kind.peek().is_none() {
+                    self.0.push(effect);
+                }
+            }
+            StackPolicy::Refresh => match same_kind.next() {
+                Some(existing) => *existing = effect,
+                None => self.0.push(effect),
+            },
+            StackPolicy::Stack { max, .. } => {
+                let mut stacks: Vec<_> = same_kind.collect();
+                if let Some(own) = stacks
+                    .iter_mut()
+                    .find(|existing| effect.origin.is_some() && existing.origin == effect.origin)
+                {
+                    **own = effect;
+                } else if stacks.len() < max.max(1) as usize {
+                    self.0.push(effect);
+                } else if let Some(oldest) = stacks
+                    .into_iter()
+                    .min_by(|a, b| a.remaining.remaining().cmp(&b.remaining.remaining()))
+                {
+                    *oldest = effect;
+                }
+            }
+        }
+    }
+
+    // Strength of every effect `strength` picks out, scaled by its potency
+    // once they are ranked strongest first
+    fn stacked(&self, strength: impl Fn(&StatusKind) -> Option<f32>) -> Vec<f32> {
+        let mut effects: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|effect| Some((strength(&effect.kind)?, effect.stacking)))
+            .collect();
+        effects.sort_by(|a, b| b.0.total_cmp(&a.0));
+        effects
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (strength, stacking))| strength * stacking.potency(rank))
+            .collect()
+    }
+
+    pub fn burn_per_second(&self) -> f32 {
+        self.stacked(|kind| match *kind {
+            StatusKind::Burn { damage_per_second } => Some(damage_per_second),
+            _ => None,
+        })
+        .into_iter()
+        .sum()
+    }
+
+    pub fn regen_per_second(&self) -> f32 {
+        self.stacked(|kind| match *kind {
+            StatusKind::Regen { health_per_second } => Some(health_per_second),
+            _ => None,
+        })
+        .into_iter()
+        .sum()
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        let slowed = self
+            .stacked(|kind| match *kind {
+                StatusKind::Slow { factor } => Some(1.0 - factor.clamp(0.0, 1.0)),
+                _ => None,
+            })
+            .into_iter()
+            .fold(1.0, |speed, reduction| speed * (1.0 - reduction));
+        let hasted = self
+            .stacked(|kind| match *kind {
+                StatusKind::Haste { factor } => Some(factor.max(0.0)),
+                _ => None,
+            })
+            .into_iter()
+            .fold(1.0, |speed, bonus| speed * (1.0 + bonus));
+        slowed * hasted
+    }
+}
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusEffects>()
+            .add_systems(
+                Update,
+                (
+                    apply_burn,
+                    apply_regen,
+                    expire_status_effects,
+                    slow_animations,
+                )
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            )
+            .register_console_command("status", "status <kind> [seconds]", status_command);
+    }
+}
+
+fn apply_burn(
+    time: Res<Time>,
+    mut query: Query<(&StatusEffects, &mut Health, Option<&Stats>), Without<Invulnerable>>,
+) {
+    for (effects, mut health, stats) in query.iter_mut() {
+        let burn = effects.burn_per_second();
+        if burn > 0.0 {
+            let resistance = stats.map_or(0.0, Stats::resistance);
+            let amount = mitigate(burn * time.delta_seconds(), resistance);
+            health.current = (health.current - amount).max(0.0);
+        }
+    }
+}
+
+fn apply_regen(time: Res<Time>, mut query: Query<(&StatusEffects, &mut Health)>) {
+    for (effects, mut health) in query.iter_mut() {
+        let regen = effects.regen_per_second();
+        if regen > 0.0 && health.current > 0.0 {
+            health.current = (health.current + regen * time.delta_seconds()).min(health.max);
+        }
+    }
+}
+
+fn expire_status_effects(time: Res<Time>, mut query: Query<&mut StatusEffects>) {
+    for mut effects in query.iter_mut() {
+        effects.0.retain_mut(|effect| {
+            effect.remaining.tick(time.delta());
+            !effect.remaining.finished()
+        });
+    }
+}
+
+// Slowed characters animate slower as well as moving slower
+fn slow_animations(
+    mut commands: Commands,
+    mut slowed: Query<
+        (Entity, &StatusEffects, Option<&mut AnimationSpeed>),
+        (With<Animator>, Changed<StatusEffects>),
+    >,
+) {
+    for (entity, status, speed) in slowed.iter_mut() {
+        let multiplier = AnimationSpeed(status.speed_multiplier());
+        match speed {
+            Some(mut speed) => {
+                speed.set_if_neq(multiplier);
+            }
+            None if multiplier != AnimationSpeed::default() => {
+                commands.entity(entity).insert(multiplier);
+            }
+            None => {}
+        }
+    }
+}
+
+// Puts an effect of each kind's usual strength on every player, for trying
+// effects and their visuals out
+fn status_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let (kind, seconds): (&str, f32) = match args {
+        [kind] => (*kind, 10.0),
+        [kind, seconds] => (
+            *kind,
+            seconds.parse().map_err(|_| "seconds must be a number")?,
+        ),
+        _ => return Err("expected status <kind> [seconds]".to_string()),
+    };
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err("seconds must be a finite number, not negative".to_string());
+    }
+    let kind = match kind {
+        "burn" => StatusKind::Burn {
+            damage_per_second: 2.0,
+        },
+        "slow" => StatusKind::Slow { factor: 0.4 },
+        "haste" => StatusKind::Haste { factor: 0.3 },
+        "regen" => StatusKind::Regen {
+            health_per_second: 5.0,
+        },
+        _ => return Err(format!("unknown status {:?}", kind)),
+    };
+    let mut players = world.query_filtered::<&mut StatusEffects, With<Player>>();
+    let mut count = 0;
+    for mut effects in players.iter_mut(world) {
+        effects.apply(StatusEffect::new(kind, seconds, None));
+        count += 1;
+    }
+    if count == 0 {
+        return Err("no player".to_string());
+    }
+    Ok(format!("Applied {} for {}s", kind.name(), seconds))
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::combat::Hitbox;
+use crate::schedule::GameSet;
+use crate::skills::despawn::DespawnSkillExt;
+use crate::skills::{DespawnReason, SkillDefinition, SkillSpawner, WaterSkill};
+use crate::status::{StatusEffects, StatusKind};
+
+// Most effect sprites shown around one entity, the highest priority kinds
+// winning when more are active
+const MAX_VISUALS: usize = 3;
+// Height above the entity's origin the sprites hover at
+const HOVER_HEIGHT: f32 = 1.1;
+// Several sprites circle the entity at this radius and speed, in radians per second
+const ORBIT_RADIUS: f32 = 0.6;
+const ORBIT_SPEED: f32 = 2.0;
+// Slight bob so a single hovering sprite doesn't look pinned in place
+const BOB_HEIGHT: f32 = 0.06;
+
+// Which kinds get a slot first: buffs before debuffs, then by how much they
+// matter to someone watching
+fn priority(kind: &StatusKind) -> u32 {
+    match kind {
+        StatusKind::Haste { .. } => 4,
+        StatusKind::Regen { .. } => 3,
+        StatusKind::Burn { .. } => 2,
+        StatusKind::Slow { .. } => 1,
+    }
+}
+
+// The looping sprite for each kind, `skills/status_<kind>.skill.ron`
+fn visual_path(kind: &StatusKind) -> String {
+    format!("skills/status_{}.skill.ron", kind.name())
+}
+
+// Sprites shown for an entity's status effects, by kind name
+#[derive(Component, Default)]
+struct StatusVisuals(Vec<(&'static str, Entity)>);
+
+// On a sprite, the entity whose status effect it shows
+#[derive(Component)]
+struct StatusVisual {
+    owner: Entity,
+}
+
+#[derive(Resource, Default)]
+struct StatusVisualSkills(HashMap<&'static str, Handle<SkillDefinition>>);
+
+pub struct StatusVisualsPlugin;
+
+impl Plugin for StatusVisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatusVisualSkills>().add_systems(
+            Update,
+            (
+                sync_status_visuals,
+                place_status_visuals,
+                clear_orphaned_visuals,
+            )
+                .chain()
+                .in_set(GameSet::Animation),
+        );
+    }
+}
+
+// Adds and removes sprites as effects come and go. Sprites whose lifetime ran
+// out are spawned again while their effect lasts.
+fn sync_status_visuals(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut skills: ResMut<StatusVisualSkills>,
+    mut entities: Query<(
+        Entity,
+        &StatusEffects,
+        &GlobalTransform,
+        Option<&mut StatusVisuals>,
+    )>,
+    skill_instances: Query<(), With<WaterSkill>>,
+    mut spawner: SkillSpawner,
+) {
+    for (entity, effects, transform, visuals) in entities.iter_mut() {
+        if effects.0.is_empty() && visuals.is_none() {
+            continue;
+        }
+        let mut kinds: Vec<&StatusKind> = Vec::new();
+        for effect in effects.0.iter() {
+            if !kinds.iter().any(|kind| kind.name() == effect.kind.name()) {
+                kinds.push(&effect.kind);
+            }
+        }
+        kinds.sort_by_key(|kind| std::cmp::Reverse(priority(kind)));
+        kinds.truncate(MAX_VISUALS);
+
+        let mut shown = visuals
+            .as_ref()
+            .map_or_else(Vec::new, |visuals| visuals.0.clone());
+        shown.retain(|(name, sprite)| {
+            let wanted = kinds.iter().any(|kind| kind.name() == *name);
+            if !wanted {
+                commands
+                    .entity(*sprite)
+                    .despawn_skill(DespawnReason::Expired);
+            }
+            wanted && skill_instances.contains(*sprite)
+        });
+        for kind in kinds {
+            if shown.iter().any(|(name, _)| *name == kind.name()) {
+                continue;
+            }
+            let handle = skills
+                .0
+                .entry(kind.name())
+                .or_insert_with(|| asset_server.load(visual_path(kind)))
+                .clone();
+            let Some(definition) = definitions.get(&handle) else {
+                continue;
+            };
+            let sprite =
+                match spawner.spawn(&handle, definition, entity, transform.translation(), None) {
+                    Ok(sprite) => sprite,
+                    Err(error) => {
+                        spawner.report(&error);
+                        continue;
+                    }
+                };
+            // Only for show, a buff's sprite shouldn't hit what it passes
+            spawner
+                .commands
+                .entity(sprite)
+                .remove::<Hitbox>()
+                .insert(StatusVisual { owner: entity });
+            shown.push((kind.name(), sprite));
+        }
+        // Sorted so the sprites keep their places around the entity
+        shown.sort_by_key(|(name, _)| *name);
+        match visuals {
+            _ if shown.is_empty() => {
+                commands.entity(entity).remove::<StatusVisuals>();
+            }
+            Some(mut visuals) => {
+                if visuals.0 != shown {
+                    visuals.0 = shown;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(StatusVisuals(shown));
+            }
+        }
+    }
+}
+
+// One sprite hovers overhead, several circle the entity evenly spaced
+fn place_status_visuals(
+    time: Res<Time>,
+    entities: Query<(&GlobalTransform, &StatusVisuals)>,
+    mut sprites: Query<&mut Transform, With<WaterSkill>>,
+) {
+    let elapsed = time.elapsed_seconds();
+    for (transform, visuals) in entities.iter() {
+        let center = transform.translation() + Vec3::Y * HOVER_HEIGHT;
+        let count = visuals.0.len();
+        for (i, (_, sprite)) in visuals.0.iter().enumerate() {
+            let Ok(mut sprite) = sprites.get_mut(*sprite) else {
+                continue;
+            };
+            let bob = Vec3::Y * (elapsed * 3.0 + i as f32).sin() * BOB_HEIGHT;
+            sprite.translation = if count == 1 {
+                center + bob
+            } else {
+                let angle = elapsed * ORBIT_SPEED + std::f32::consts::TAU * i as f32 / count as f32;
+                center + Vec3::new(angle.cos(), 0.0, angle.sin()) * ORBIT_RADIUS + bob
+            };
+        }
+    }
+}
+
+// Sprites go with the entity they belong to
+fn clear_orphaned_visuals(
+    mut commands: Commands,
+    sprites: Query<(Entity, &StatusVisual)>,
+    owners: Query<(), With<StatusEffects>>,
+) {
+    for (sprite, visual) in sprites.iter() {
+        if !owners.contains(visual.owner) {
+            commands
+                .entity(sprite)
+                .despawn_skill(DespawnReason::OwnerDied);
+        }
+    }
+}
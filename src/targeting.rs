@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::combat::Hurtbox;
+use crate::cursor::CursorGrab;
+use crate::hit_volume::ray_sphere;
+use crate::outline::Outline;
+use crate::schedule::{simulation_running, GameSet};
+use crate::screen_space::ScreenProjection;
+use crate::settings::AccessibilitySettings;
+use crate::{Enemy, MainCamera, Player};
+
+pub const TARGET_LOCK_KEY: KeyCode = KeyCode::Tab;
+// Locking with nothing under the cursor picks the nearest enemy in this range
+const LOCK_RANGE: f32 = 15.0;
+
+#[derive(Resource, Debug, Default)]
+pub struct Targeting {
+    // Enemy under the mouse cursor, or under the crosshair while it is grabbed
+    pub hovered: Option<Entity>,
+    pub locked: Option<Entity>,
+}
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Targeting>().add_systems(
+            Update,
+            (
+                (update_hovered_enemy, lock_target)
+                    .chain()
+                    .run_if(simulation_running)
+                    .in_set(GameSet::Input),
+                outline_targets.in_set(GameSet::Presentation),
+            ),
+        );
+    }
+}
+
+fn update_hovered_enemy(
+    mut targeting: ResMut<Targeting>,
+    grab: Option<Res<CursorGrab>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    enemies: Query<(Entity, &GlobalTransform, &Hurtbox), With<Enemy>>,
+) {
+    targeting.hovered = None;
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let cursor = if grab.is_some_and(|grab| grab.grabbed) {
+        Some(window.size() / 2.0)
+    } else {
+        window.cursor_position()
+    };
+    let Some(ray) = cursor
+        .and_then(|cursor| ScreenProjection::new(camera, camera_transform)?.screen_to_ray(cursor))
+    else {
+        return;
+    };
+    targeting.hovered = enemies
+        .iter()
+        .filter_map(|(entity, transform, hurtbox)| {
+            ray_sphere(ray, transform.translation(), hurtbox.radius).map(|t| (entity, t))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity);
+}
+
+fn lock_target(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut targeting: ResMut<Targeting>,
+    players: Query<&GlobalTransform, With<Player>>,
+    enemies: Query<(Entity, &GlobalTransform), With<Enemy>>,
+) {
+    // Drop targets that died or despawned
+    if targeting
+        .locked
+        .is_some_and(|locked| !enemies.contains(locked))
+    {
+        targeting.locked = None;
+    }
+    if !keyboard_input.just_pressed(TARGET_LOCK_KEY) {
+        return;
+    }
+    // Lock what is hovered, otherwise unlock, otherwise lock the nearest enemy
+    targeting.locked = match targeting.hovered {
+        Some(hovered) if targeting.locked != Some(hovered) => Some(hovered),
+        _ if targeting.locked.is_some() => None,
+        // Nearest to whichever player it is closest to
+        _ => enemies
+            .iter()
+            .filter_map(|(entity, transform)| {
+                players
+                    .iter()
+                    .map(|player| transform.translation().distance(player.translation()))
+                    .reduce(f32::min)
+                    .map(|distance| (entity, distance))
+            })
+            .filter(|(_, distance)| *distance <= LOCK_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity),
+    };
+}
+
+fn outline_targets(
+    mut commands: Commands,
+    targeting: Res<Targeting>,
+    accessibility: Res<AccessibilitySettings>,
+    mut enemies: Query<(Entity, Option<&mut Outline>), With<Enemy>>,
+) {
+    let palette = accessibility.palette;
+    for (entity, outline) in enemies.iter_mut() {
+        let color = if targeting.locked == Some(entity) {
+            Some(palette.locked())
+        } else if targeting.hovered == Some(entity) {
+            Some(palette.hovered())
+        } else {
+            None
+        };
+        match (color, outline) {
+            (Some(color), Some(mut outline)) => {
+                if outline.color != color {
+                    outline.color = color;
+                }
+            }
+            (Some(color), None) => {
+                commands.entity(entity).insert(Outline::new(color));
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<Outline>();
+            }
+            (None, None) => {}
+        }
+    }
+}
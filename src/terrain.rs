@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::ground::Ground;
+use crate::prefab::PrefabSpawner;
+use crate::settings::GameplaySettings;
+use crate::sky::Sky;
+use crate::state_cleanup::LevelScoped;
+
+// Generates a noise heightmap level with props and enemies instead of the flat
+// demo plane. Only used when this resource exists at startup.
+#[derive(Resource, Debug, Clone)]
+pub struct ProceduralLevel {
+    pub seed: u64,
+    // Side length of the square terrain
+    pub size: f32,
+    // Quads along each side
+    pub resolution: u32,
+    pub height: f32,
+    // World units per noise cell of the first octave
+    pub feature_size: f32,
+    // Flat area around the origin where the player and props start
+    pub clearing_radius: f32,
+    pub props: usize,
+    pub enemies: usize,
+    pub sky: Sky,
+}
+
+impl Default for ProceduralLevel {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            size: 40.0,
+            resolution: 64,
+            height: 3.0,
+            feature_size: 10.0,
+            clearing_radius: 7.0,
+            props: 30,
+            enemies: 6,
+            // Late afternoon haze to tell it apart from the demo level
+            sky: Sky {
+                zenith: (0.35, 0.4, 0.7),
+                horizon: (0.95, 0.75, 0.55),
+                ground: (0.35, 0.3, 0.25),
+                ..default()
+            },
+        }
+    }
+}
+
+impl ProceduralLevel {
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        let mut height = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0 / self.feature_size;
+        for octave in 0..4 {
+            height += amplitude * value_noise(self.seed + octave, x * frequency, z * frequency);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        // Ease from flat in the clearing to full height a few units past it
+        let distance = Vec2::new(x, z).length();
+        let blend = ((distance - self.clearing_radius) / 4.0).clamp(0.0, 1.0);
+        let blend = blend * blend * (3.0 - 2.0 * blend);
+        // 1.875 is the sum of the octave amplitudes
+        height / 1.875 * self.height * blend
+    }
+
+    fn mesh(&self) -> Mesh {
+        let cells = self.resolution.max(1);
+        let step = self.size / cells as f32;
+        let half = self.size / 2.0;
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        for row in 0..=cells {
+            for column in 0..=cells {
+                let x = column as f32 * step - half;
+                let z = row as f32 * step - half;
+                positions.push([x, self.height_at(x, z), z]);
+                uvs.push([column as f32 / cells as f32, row as f32 / cells as f32]);
+            }
+        }
+        let mut indices = Vec::new();
+        for row in 0..cells {
+            for column in 0..cells {
+                let i = row * (cells + 1) + column;
+                let below = i + cells + 1;
+                indices.extend_from_slice(&[i, below, i + 1, i + 1, below, below + 1]);
+            }
+        }
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+        .with_computed_smooth_normals()
+    }
+
+    // Random point on the terrain outside the clearing
+    fn scatter(&self, rng: &mut StdRng) -> Vec2 {
+        let half = self.size / 2.0 - 1.0;
+        loop {
+            let point = Vec2::new(rng.gen_range(-half..half), rng.gen_range(-half..half));
+            if point.length() > self.clearing_radius {
+                return point;
+            }
+        }
+    }
+}
+
+// Smoothly interpolated random values on an integer lattice, in -1..1
+fn value_noise(seed: u64, x: f32, z: f32) -> f32 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let (tx, tz) = (x - x0, z - z0);
+    let (tx, tz) = (tx * tx * (3.0 - 2.0 * tx), tz * tz * (3.0 - 2.0 * tz));
+    let (ix, iz) = (x0 as i64, z0 as i64);
+    let corner = |dx: i64, dz: i64| lattice_value(seed, ix + dx, iz + dz);
+    let top = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * tx;
+    let bottom = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * tx;
+    top + (bottom - top) * tz
+}
+
+fn lattice_value(seed: u64, x: i64, z: i64) -> f32 {
+    let mut hash = seed
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add((x as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9))
+        .wrapping_add((z as u64).wrapping_mul(0x94D0_49BB_1331_11EB));
+    hash ^= hash >> 31;
+    hash = hash.wrapping_mul(0xD6E8_FEB8_6659_FD93);
+    hash ^= hash >> 32;
+    (hash >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Startup,
+            generate_level.run_if(resource_exists::<ProceduralLevel>),
+        );
+    }
+}
+
+fn generate_level(
+    mut commands: Commands,
+    level: Res<ProceduralLevel>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut prefabs: PrefabSpawner,
+    gameplay: Res<GameplaySettings>,
+) {
+    let mut rng = StdRng::seed_from_u64(level.seed);
+    commands.insert_resource(level.sky.clone());
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(level.mesh()),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.3, 0.5, 0.3),
+                perceptual_roughness: 0.9,
+                ..default()
+            }),
+            ..default()
+        },
+        Ground,
+        LevelScoped,
+    ));
+
+    // Rocks, sunk a little so they sit in slopes
+    let rock = meshes.add(Sphere::new(0.5).mesh().ico(1).unwrap());
+    let rock_material = materials.add(Color::srgb(0.45, 0.43, 0.4));
+    for _ in 0..level.props {
+        let point = level.scatter(&mut rng);
+        let scale = Vec3::new(
+            rng.gen_range(0.6..1.8),
+            rng.gen_range(0.4..1.2),
+            rng.gen_range(0.6..1.8),
+        );
+        let position = Vec3::new(point.x, level.height_at(point.x, point.y), point.y);
+        commands.spawn((
+            PbrBundle {
+                mesh: rock.clone(),
+                material: rock_material.clone(),
+                transform: Transform::from_translation(position)
+                    .with_rotation(Quat::from_rotation_y(
+                        rng.gen_range(0.0..std::f32::consts::TAU),
+                    ))
+                    .with_scale(scale),
+                ..default()
+            },
+            LevelScoped,
+        ));
+    }
+
+    for _ in 0..gameplay.multipliers().scale_count(level.enemies as u32) {
+        let point = level.scatter(&mut rng);
+        let height = level.height_at(point.x, point.y) + 0.5;
+        match prefabs.spawn("enemy", Transform::from_xyz(point.x, height, point.y)) {
+            Ok(mut enemy) => {
+                enemy.insert(LevelScoped);
+            }
+            Err(error) => warn!("{}", error),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::controller::MovementIntent;
+use crate::damage::DamageDealt;
+use crate::schedule::GameSet;
+
+// Threat a target gets just for being seen, enough to be picked when nothing
+// has done any damage yet
+const NOTICE_THREAT: f32 = 1.0;
+// Enemies stop chasing this close to their target
+const CHASE_STOP_DISTANCE: f32 = 1.5;
+
+// Something enemies can pick as their target, like players and summons
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ThreatTarget;
+
+// How much each target has angered an enemy. The enemy goes after whoever has
+// the most, so anything dealing damage or taunting can pull it away.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ThreatTable {
+    // Targets closer than this are noticed without having to attack
+    pub aggro_range: f32,
+    // Lost every second, so targets that stop attacking are forgotten
+    pub decay_per_second: f32,
+    pub threat: HashMap<Entity, f32>,
+}
+
+impl ThreatTable {
+    pub fn new(aggro_range: f32) -> Self {
+        Self {
+            aggro_range,
+            decay_per_second: 2.0,
+            threat: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, target: Entity, amount: f32) {
+        *self.threat.entry(target).or_insert(0.0) += amount;
+    }
+
+    pub fn target(&self) -> Option<Entity> {
+        self.threat
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(target, _)| *target)
+    }
+
+    // Highest threat first
+    pub fn sorted(&self) -> Vec<(Entity, f32)> {
+        let mut entries: Vec<_> = self.threat.iter().map(|(e, t)| (*e, *t)).collect();
+        entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+        entries
+    }
+}
+
+// Adds `threat` towards `taunter` to every enemy within `radius` of `position`
+#[derive(Event, Clone, Copy)]
+pub struct Taunt {
+    pub taunter: Entity,
+    pub position: Vec3,
+    pub radius: f32,
+    pub threat: f32,
+}
+
+pub struct ThreatPlugin;
+
+impl Plugin for ThreatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ThreatTarget>()
+            .register_type::<ThreatTable>()
+            .add_event::<Taunt>()
+            .add_systems(
+                Update,
+                (
+                    notice_targets,
+                    threat_from_damage,
+                    apply_taunts,
+                    decay_threat,
+                    chase_threat_target,
+                )
+                    .chain()
+                    .in_set(GameSet::Simulation),
+            );
+    }
+}
+
+fn notice_targets(
+    mut enemies: Query<(&GlobalTransform, &mut ThreatTable)>,
+    targets: Query<(Entity, &GlobalTransform), With<ThreatTarget>>,
+) {
+    for (transform, mut table) in enemies.iter_mut() {
+        for (target, target_transform) in targets.iter() {
+            let distance = target_transform
+                .translation()
+                .distance(transform.translation());
+            if distance <= table.aggro_range && !table.threat.contains_key(&target) {
+                table.add(target, NOTICE_THREAT);
+            }
+        }
+    }
+}
+
+// Damage counts against the skill itself when it can be targeted, like a
+// summon, and against whoever cast it otherwise
+fn threat_from_damage(
+    mut damage_dealt: EventReader<DamageDealt>,
+    mut enemies: Query<&mut ThreatTable>,
+    targets: Query<(), With<ThreatTarget>>,
+) {
+    for damage in damage_dealt.read() {
+        let Ok(mut table) = enemies.get_mut(damage.target) else {
+            continue;
+        };
+        let credited = [Some(damage.source), damage.attacker]
+            .into_iter()
+            .flatten()
+            .find(|entity| targets.contains(*entity));
+        if let Some(credited) = credited {
+            table.add(credited, damage.amount);
+        }
+    }
+}
+
+fn apply_taunts(
+    mut taunts: EventReader<Taunt>,
+    mut enemies: Query<(&GlobalTransform, &mut ThreatTable)>,
+) {
+    for taunt in taunts.read() {
+        for (transform, mut table) in enemies.iter_mut() {
+            if transform.translation().distance(taunt.position) <= taunt.radius {
+                table.add(taunt.taunter, taunt.threat);
+            }
+        }
+    }
+}
+
+fn decay_threat(
+    time: Res<Time>,
+    mut enemies: Query<&mut ThreatTable>,
+    targets: Query<(), With<ThreatTarget>>,
+) {
+    for mut table in enemies.iter_mut() {
+        let decay = table.decay_per_second * time.delta_seconds();
+        table.threat.retain(|target, threat| {
+            *threat -= decay;
+            *threat > 0.0 && targets.contains(*target)
+        });
+    }
+}
+
+pub fn chase_threat_target(
+    mut enemies: Query<(&GlobalTransform, &ThreatTable, &mut MovementIntent)>,
+    targets: Query<&GlobalTransform, With<ThreatTarget>>,
+) {
+    for (transform, table, mut intent) in enemies.iter_mut() {
+        let target = table.target().and_then(|target| targets.get(target).ok());
+        intent.0 = match target {
+            Some(target) => {
+                let offset = (target.translation() - transform.translation()).with_y(0.0);
+                if offset.length() > CHASE_STOP_DISTANCE {
+                    offset.normalize()
+                } else {
+                    Vec3::ZERO
+                }
+            }
+            None => Vec3::ZERO,
+        };
+    }
+}
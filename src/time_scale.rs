@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::console::RegisterConsoleCommand;
+use crate::locale::Locale;
+use crate::schedule::GameSet;
+use crate::toast::ToastEvent;
+
+// Debug keys for slowing gameplay down to inspect effect animations
+pub const TIME_SCALE_KEYS: [(KeyCode, f32); 4] = [
+    (KeyCode::F1, 0.1),
+    (KeyCode::F2, 0.5),
+    (KeyCode::F3, 1.0),
+    (KeyCode::F4, 2.0),
+];
+
+// Fastest an entity's own clock may run, anything above turns a frame's delta
+// into several seconds of movement and attacks
+pub const MAX_TIME_SCALE: f32 = 10.0;
+
+// Speeds up or slows down one entity's own clock: its movement, AI, attacks
+// and animation all advance by `delta * scale`. Entities without one run at
+// normal speed. Set and cleared by time zones.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TimeScale(pub f32);
+
+impl TimeScale {
+    pub fn of(scale: Option<&TimeScale>) -> f32 {
+        scale.map_or(1.0, |scale| Self::clamp(scale.0))
+    }
+
+    // Keeps a scale usable with `Duration::mul_f32`, which panics on
+    // negative and non-finite factors. Broken values stop the clock.
+    pub fn clamp(scale: f32) -> f32 {
+        if scale.is_finite() {
+            scale.clamp(0.0, MAX_TIME_SCALE)
+        } else {
+            0.0
+        }
+    }
+}
+
+// Scales `Time<Virtual>`, so everything reading the default `Time` slows down
+// with it. Camera and UI systems read `Time<Real>` to stay responsive.
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TimeScale>()
+            .register_console_command("timescale", "timescale <speed>", timescale_command)
+            .add_systems(Update, time_scale_keys.in_set(GameSet::Input));
+    }
+}
+
+fn time_scale_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<Time<Virtual>>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for (key, speed) in TIME_SCALE_KEYS {
+        if keyboard_input.just_pressed(key) {
+            time.set_relative_speed(speed);
+            toasts.send(ToastEvent::new(
+                locale.format("toast.time_scale", &[("speed", &speed)]),
+            ));
+        }
+    }
+}
+
+fn timescale_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let speed = args
+        .first()
+        .and_then(|speed| speed.parse::<f32>().ok())
+        .filter(|speed| speed.is_finite() && *speed >= 0.0)
+        .ok_or("expected a speed, e.g. timescale 0.25")?;
+    world
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(speed);
+    Ok(format!("Time scale set to {}x", speed))
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::schedule::GameSet;
+
+const MAX_VISIBLE_TOASTS: usize = 5;
+const FADE_IN: f32 = 0.25;
+const FADE_OUT: f32 = 0.5;
+
+// Any system can send one of these to show a short message on screen
+#[derive(Event, Clone)]
+pub struct ToastEvent {
+    pub message: String,
+    pub duration: f32,
+}
+
+impl ToastEvent {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            duration: 2.5,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast {
+    age: f32,
+    duration: f32,
+}
+
+impl Toast {
+    fn alpha(&self) -> f32 {
+        let fade_in = (self.age / FADE_IN).min(1.0);
+        let fade_out = ((self.duration - self.age) / FADE_OUT).min(1.0);
+        fade_in.min(fade_out).max(0.0)
+    }
+}
+
+// Messages waiting for a free slot while the screen is full
+#[derive(Resource, Default)]
+struct ToastQueue(Vec<ToastEvent>);
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ToastEvent>()
+            .init_resource::<ToastQueue>()
+            .add_systems(Startup, spawn_toast_container)
+            .add_systems(
+                Update,
+                (queue_toasts, show_toasts, fade_toasts)
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            ..default()
+        },
+        ToastContainer,
+        SafeAreaAnchor::default(),
+    ));
+}
+
+fn queue_toasts(mut toasts: EventReader<ToastEvent>, mut queue: ResMut<ToastQueue>) {
+    queue.0.extend(toasts.read().cloned());
+}
+
+fn show_toasts(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    container: Query<Entity, With<ToastContainer>>,
+    toasts: Query<(), With<Toast>>,
+) {
+    let Ok(container) = container.get_single() else {
+        return;
+    };
+    let free = MAX_VISIBLE_TOASTS.saturating_sub(toasts.iter().count());
+    let count = free.min(queue.0.len());
+    for toast in queue.0.drain(..count) {
+        let text = commands
+            .spawn((
+                TextBundle::from_section(
+                    toast.message,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE.with_alpha(0.0),
+                        ..default()
+                    },
+                ),
+                Toast {
+                    age: 0.0,
+                    duration: toast.duration.max(FADE_IN + FADE_OUT),
+                },
+            ))
+            .id();
+        commands.entity(container).add_child(text);
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut Toast, &mut Text)>,
+) {
+    for (entity, mut toast, mut text) in toasts.iter_mut() {
+        toast.age += time.delta_seconds();
+        if toast.age >= toast.duration {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = toast.alpha();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
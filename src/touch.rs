@@ -0,0 +1,285 @@
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::touch::TouchInput;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::hud_layout::SafeAreaAnchor;
+use crate::schedule::GameSet;
+use crate::skills::{SkillBindings, SkillDefinition};
+
+const STICK_SIZE: f32 = 140.0;
+const KNOB_SIZE: f32 = 56.0;
+// Touches this far outside the stick still grab it, thumbs are imprecise
+const STICK_GRAB_MARGIN: f32 = 40.0;
+const BUTTON_SIZE: f32 = 64.0;
+const BUTTONS_PER_ROW: usize = 3;
+
+// On-screen controls for touch devices, shown once the screen is touched and
+// hidden again when a keyboard or mouse is used. They don't have an input
+// path of their own: the stick adds to the movement keys and each cast button
+// holds down the key its skill is bound to, so channels, toggles and
+// buffering behave exactly as with the keyboard.
+#[derive(Resource, Default)]
+pub struct TouchControls {
+    pub active: bool,
+    // How far the stick is pushed, up to length 1, y pointing down the screen
+    pub stick: Vec2,
+    stick_touch: Option<u64>,
+    // Cast buttons being held, and the touch holding each
+    held: Vec<(u64, KeyCode)>,
+}
+
+#[derive(Component)]
+struct TouchControlsRoot;
+
+#[derive(Component)]
+struct JoystickBase;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+// Holds down its key while touched
+#[derive(Component)]
+struct TouchCastButton(KeyCode);
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            // Before gameplay reads the keys, so a tap counts on the same frame
+            .add_systems(
+                PreUpdate,
+                (detect_touch_device, read_touch_controls)
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                (spawn_touch_controls, show_touch_controls)
+                    .chain()
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+fn detect_touch_device(
+    mut controls: ResMut<TouchControls>,
+    mut touches: EventReader<TouchInput>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if touches.read().count() > 0 {
+        if !controls.active {
+            controls.active = true;
+        }
+    } else if (mouse_buttons.read().count() > 0
+        || keyboard_input.get_just_pressed().next().is_some())
+        && controls.active
+    {
+        controls.active = false;
+    }
+}
+
+fn read_touch_controls(
+    mut controls: ResMut<TouchControls>,
+    touches: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    stick: Query<(&Node, &GlobalTransform), With<JoystickBase>>,
+    buttons: Query<(&Node, &GlobalTransform, &TouchCastButton)>,
+) {
+    if !controls.active {
+        // Lets go of anything still held when switching back to the keyboard
+        for (_, key) in controls.held.drain(..) {
+            keyboard_input.release(key);
+        }
+        controls.stick_touch = None;
+        controls.stick = Vec2::ZERO;
+        return;
+    }
+    let Ok((stick_node, stick_transform)) = stick.get_single() else {
+        return;
+    };
+    let stick_rect = stick_node.logical_rect(stick_transform);
+
+    for touch in touches.iter_just_pressed() {
+        // Touches are in window pixels, nodes in scaled UI pixels
+        let position = touch.position() / ui_scale.0;
+        if controls.stick_touch.is_none()
+            && stick_rect.inflate(STICK_GRAB_MARGIN).contains(position)
+        {
+            controls.stick_touch = Some(touch.id());
+            continue;
+        }
+        let pressed = buttons
+            .iter()
+            .find(|(node, transform, _)| node.logical_rect(transform).contains(position));
+        if let Some((_, _, button)) = pressed {
+            keyboard_input.press(button.0);
+            controls.held.push((touch.id(), button.0));
+        }
+    }
+
+    for touch in touches
+        .iter_just_released()
+        .chain(touches.iter_just_canceled())
+    {
+        if controls.stick_touch == Some(touch.id()) {
+            controls.stick_touch = None;
+        }
+        let id = touch.id();
+        controls.held.retain(|(touch, key)| {
+            if *touch == id {
+                keyboard_input.release(*key);
+            }
+            *touch != id
+        });
+    }
+
+    controls.stick = match controls.stick_touch.and_then(|id| touches.get_pressed(id)) {
+        Some(touch) => {
+            let offset = touch.position() / ui_scale.0 - stick_rect.center();
+            (offset / (STICK_SIZE / 2.0)).clamp_length_max(1.0)
+        }
+        None => Vec2::ZERO,
+    };
+}
+
+fn spawn_touch_controls(
+    mut commands: Commands,
+    controls: Res<TouchControls>,
+    bindings: Res<SkillBindings>,
+    definitions: Res<Assets<SkillDefinition>>,
+    roots: Query<(), With<TouchControlsRoot>>,
+) {
+    if !controls.active || !roots.is_empty() {
+        return;
+    }
+    let circle = |size: f32| Style {
+        position_type: PositionType::Absolute,
+        width: Val::Px(size),
+        height: Val::Px(size),
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            },
+            TouchControlsRoot,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                NodeBundle {
+                    style: Style {
+                        left: Val::Px(40.0),
+                        bottom: Val::Px(40.0),
+                        ..circle(STICK_SIZE)
+                    },
+                    background_color: Color::srgba(1.0, 1.0, 1.0, 0.15).into(),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                },
+                JoystickBase,
+                SafeAreaAnchor::default(),
+            ))
+            .with_children(|base| {
+                base.spawn((
+                    NodeBundle {
+                        style: circle(KNOB_SIZE),
+                        background_color: Color::srgba(1.0, 1.0, 1.0, 0.5).into(),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    JoystickKnob,
+                ));
+            });
+
+            root.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        right: Val::Px(40.0),
+                        bottom: Val::Px(40.0),
+                        width: Val::Px((BUTTON_SIZE + 10.0) * BUTTONS_PER_ROW as f32),
+                        flex_wrap: FlexWrap::WrapReverse,
+                        justify_content: JustifyContent::FlexEnd,
+                        row_gap: Val::Px(10.0),
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                SafeAreaAnchor::default(),
+            ))
+            .with_children(|panel| {
+                for (key, skill) in bindings.0.iter() {
+                    // Named after the skill once it has loaded
+                    let label = definitions.get(skill).map_or_else(
+                        || format!("{:?}", key),
+                        |definition| definition.name.clone(),
+                    );
+                    panel
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(BUTTON_SIZE),
+                                    height: Val::Px(BUTTON_SIZE),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: Color::srgba(0.2, 0.25, 0.3, 0.7).into(),
+                                border_radius: BorderRadius::MAX,
+                                ..default()
+                            },
+                            TouchCastButton(*key),
+                        ))
+                        .with_children(|button| {
+                            button.spawn(
+                                TextBundle::from_section(
+                                    label,
+                                    TextStyle {
+                                        font_size: 12.0,
+                                        ..default()
+                                    },
+                                )
+                                .with_text_justify(JustifyText::Center),
+                            );
+                        });
+                }
+            });
+        });
+}
+
+fn show_touch_controls(
+    controls: Res<TouchControls>,
+    mut roots: Query<&mut Visibility, With<TouchControlsRoot>>,
+    mut knobs: Query<&mut Style, With<JoystickKnob>>,
+    mut buttons: Query<(&TouchCastButton, &mut BackgroundColor)>,
+) {
+    for mut visibility in roots.iter_mut() {
+        *visibility = if controls.active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    // Centered in the base, pushed out with the stick
+    let center = (STICK_SIZE - KNOB_SIZE) / 2.0;
+    for mut style in knobs.iter_mut() {
+        style.left = Val::Px(center + controls.stick.x * STICK_SIZE / 2.0);
+        style.top = Val::Px(center + controls.stick.y * STICK_SIZE / 2.0);
+    }
+    for (button, mut color) in buttons.iter_mut() {
+        let held = controls.held.iter().any(|(_, key)| *key == button.0);
+        *color = Color::srgba(0.2, 0.25, 0.3, if held { 0.95 } else { 0.7 }).into();
+    }
+}
@@ -0,0 +1,270 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::{Hitbox, Hurtbox};
+use crate::controller::{CharacterController, MovementIntent};
+use crate::ground::Grounded;
+use crate::schedule::GameSet;
+use crate::skills::{SkillDefinition, SkillSpawner};
+use crate::threat::ThreatTable;
+use crate::Enemy;
+
+// How an enemy arrives, listed as `Enter(...)` in its prefab. It can't be hit
+// or move until it has fully arrived.
+#[derive(Debug, Clone, Deserialize, Reflect)]
+pub enum EnterEffect {
+    // Climbs up out of the ground from `depth` below where it stands
+    Rise {
+        depth: f32,
+        duration: f32,
+    },
+    // Grows out of a skill effect cast where it appears
+    Portal {
+        skill: String,
+        #[serde(skip)]
+        handle: Handle<SkillDefinition>,
+        duration: f32,
+    },
+}
+
+impl EnterEffect {
+    pub fn duration(&self) -> f32 {
+        match self {
+            EnterEffect::Rise { duration, .. } | EnterEffect::Portal { duration, .. } => *duration,
+        }
+    }
+}
+
+// How an enemy leaves once killed, listed as `DeathAnimation(...)` in its
+// prefab. Enemies without one disappear right away.
+#[derive(Component, Debug, Clone, Copy, Deserialize, Reflect)]
+#[reflect(Component)]
+pub enum DeathAnimation {
+    // Tips over backwards
+    FallOver { duration: f32 },
+    // Fades out of sight
+    Dissolve { duration: f32 },
+}
+
+impl DeathAnimation {
+    pub fn duration(&self) -> f32 {
+        match self {
+            DeathAnimation::FallOver { duration } | DeathAnimation::Dissolve { duration } => {
+                *duration
+            }
+        }
+    }
+}
+
+// Playing its enter effect. What it was started with is kept so the effect
+// can be undone exactly.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Entering {
+    pub effect: EnterEffect,
+    pub timer: Timer,
+    pub hurtbox: Option<f32>,
+    pub ground_height: Option<f32>,
+    pub scale: Vec3,
+    pub had_controller: bool,
+}
+
+impl Entering {
+    pub fn new(effect: EnterEffect) -> Self {
+        Self {
+            timer: Timer::from_seconds(effect.duration(), TimerMode::Once),
+            effect,
+            hurtbox: None,
+            ground_height: None,
+            scale: Vec3::ONE,
+            had_controller: false,
+        }
+    }
+}
+
+// Killed and playing its death animation, no longer an `Enemy`
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Dying {
+    pub animation: DeathAnimation,
+    pub timer: Timer,
+    pub rotation: Quat,
+    pub started: bool,
+}
+
+// Turns a killed enemy into a `Dying` one: everything that lets it be hit,
+// targeted, counted or moved is taken away while the animation plays
+pub fn start_dying(entity: &mut EntityCommands, animation: DeathAnimation) {
+    entity
+        .remove::<(
+            Enemy,
+            Hurtbox,
+            CharacterController,
+            MovementIntent,
+            ThreatTable,
+            Entering,
+        )>()
+        .insert(Dying {
+            animation,
+            timer: Timer::from_seconds(animation.duration(), TimerMode::Once),
+            rotation: Quat::IDENTITY,
+            started: false,
+        });
+}
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Entering>()
+            .register_type::<Dying>()
+            .register_type::<DeathAnimation>()
+            .add_systems(
+                Update,
+                (
+                    (begin_entering, finish_entering)
+                        .chain()
+                        .in_set(GameSet::Simulation),
+                    (animate_entering, animate_dying).in_set(GameSet::Animation),
+                ),
+            );
+    }
+}
+
+fn begin_entering(
+    mut entering: Query<
+        (
+            Entity,
+            &mut Entering,
+            &mut Transform,
+            Option<&Hurtbox>,
+            Option<&mut Grounded>,
+            Has<CharacterController>,
+        ),
+        Added<Entering>,
+    >,
+    definitions: Res<Assets<SkillDefinition>>,
+    mut spawner: SkillSpawner,
+) {
+    for (entity, mut entering, mut transform, hurtbox, grounded, had_controller) in
+        entering.iter_mut()
+    {
+        entering.had_controller = had_controller;
+        entering.hurtbox = hurtbox.map(|hurtbox| hurtbox.radius);
+        entering.ground_height = grounded.as_ref().map(|grounded| grounded.height);
+        entering.scale = transform.scale;
+        spawner
+            .commands
+            .entity(entity)
+            .remove::<(Hurtbox, CharacterController)>();
+        match &entering.effect {
+            EnterEffect::Rise { depth, .. } => {
+                if let Some(mut grounded) = grounded {
+                    grounded.height -= depth;
+                }
+            }
+            EnterEffect::Portal { handle, .. } => {
+                transform.scale = Vec3::splat(0.01);
+                if let Some(definition) = definitions.get(handle) {
+                    match spawner.spawn(handle, definition, entity, transform.translation, None) {
+                        Ok(portal) => {
+                            spawner.commands.entity(portal).remove::<Hitbox>();
+                        }
+                        Err(error) => spawner.report(&error),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn animate_entering(
+    time: Res<Time>,
+    mut entering: Query<(&mut Entering, &mut Transform, Option<&mut Grounded>)>,
+) {
+    for (mut entering, mut transform, grounded) in entering.iter_mut() {
+        entering.timer.tick(time.delta());
+        let t = entering.timer.fraction();
+        // Eases out so it settles into place rather than stopping dead
+        let eased = 1.0 - (1.0 - t) * (1.0 - t);
+        match entering.effect {
+            EnterEffect::Rise { depth, .. } => {
+                if let (Some(mut grounded), Some(height)) = (grounded, entering.ground_height) {
+                    grounded.height = height - depth * (1.0 - eased);
+                }
+            }
+            EnterEffect::Portal { .. } => {
+                transform.scale = entering.scale * eased.max(0.01);
+            }
+        }
+    }
+}
+
+fn finish_entering(
+    mut commands: Commands,
+    mut entering: Query<(Entity, &Entering, &mut Transform, Option<&mut Grounded>)>,
+) {
+    for (entity, entering, mut transform, grounded) in entering.iter_mut() {
+        if !entering.timer.finished() {
+            continue;
+        }
+        transform.scale = entering.scale;
+        if let (Some(mut grounded), Some(height)) = (grounded, entering.ground_height) {
+            grounded.height = height;
+        }
+        let mut entity = commands.entity(entity);
+        entity.remove::<Entering>();
+        if entering.had_controller {
+            entity.insert(CharacterController::default());
+        }
+        if let Some(radius) = entering.hurtbox {
+            entity.insert(Hurtbox { radius });
+        }
+    }
+}
+
+pub fn animate_dying(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut dying: Query<(
+        Entity,
+        &mut Dying,
+        &mut Transform,
+        Option<&Handle<StandardMaterial>>,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, mut dying, mut transform, material) in dying.iter_mut() {
+        if !dying.started {
+            dying.started = true;
+            dying.rotation = transform.rotation;
+            // Prefab instances share a material, fading it would fade them all
+            if let (DeathAnimation::Dissolve { .. }, Some(material)) = (dying.animation, material) {
+                if let Some(mut own) = materials.get(material).cloned() {
+                    own.alpha_mode = AlphaMode::Blend;
+                    commands.entity(entity).insert(materials.add(own));
+                    // Fades from next frame, once its own material is on
+                    continue;
+                }
+            }
+        }
+        dying.timer.tick(time.delta());
+        let t = dying.timer.fraction();
+        match dying.animation {
+            DeathAnimation::FallOver { .. } => {
+                // Speeds up as it topples, like something actually falling
+                let angle = std::f32::consts::FRAC_PI_2 * t * t;
+                transform.rotation = dying.rotation * Quat::from_rotation_x(angle);
+            }
+            DeathAnimation::Dissolve { .. } => {
+                if let Some(material) = material.and_then(|material| materials.get_mut(material)) {
+                    material.base_color.set_alpha(1.0 - t);
+                }
+            }
+        }
+        if dying.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::HashMap;
+
+use crate::billboard::Billboard;
+use crate::interaction::InteractEvent;
+use crate::locale::Locale;
+use crate::schedule::GameSet;
+use crate::screen_space::{to_ui, ScreenProjection};
+use crate::skills::SkillCastEvent;
+use crate::targeting::TARGET_LOCK_KEY;
+use crate::toast::ToastEvent;
+use crate::{players_center, MainCamera, Player};
+
+pub const SKIP_TUTORIAL_KEY: KeyCode = KeyCode::F10;
+// Where the key prompt floats, relative to the player
+const PROMPT_OFFSET: Vec3 = Vec3::new(0.0, 1.4, 0.0);
+// How far the player has to walk to finish the first step
+const MOVE_DISTANCE: f32 = 2.0;
+
+// Each step shows one prompt and advances once the player has done it
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TutorialStep {
+    #[default]
+    Move,
+    Cast,
+    Target,
+    Interact,
+    Done,
+}
+
+impl TutorialStep {
+    // The key on the prompt and the locale key of what it does
+    fn prompt(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            TutorialStep::Move => Some(("I J K L", "tutorial.move")),
+            TutorialStep::Cast => Some(("Space", "tutorial.cast")),
+            TutorialStep::Target => Some(("Tab", "tutorial.target")),
+            TutorialStep::Interact => Some(("E", "tutorial.interact")),
+            TutorialStep::Done => None,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            TutorialStep::Move => TutorialStep::Cast,
+            TutorialStep::Cast => TutorialStep::Target,
+            TutorialStep::Target => TutorialStep::Interact,
+            TutorialStep::Interact | TutorialStep::Done => TutorialStep::Done,
+        }
+    }
+}
+
+// Key cap sprite floating over the player
+#[derive(Component)]
+struct TutorialPrompt;
+
+#[derive(Component)]
+struct TutorialLabel;
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<TutorialStep>()
+            .add_systems(Startup, spawn_tutorial_prompt)
+            .add_systems(
+                Update,
+                (
+                    skip_tutorial.in_set(GameSet::Input),
+                    (
+                        finish_move_step.run_if(in_state(TutorialStep::Move)),
+                        finish_cast_step.run_if(in_state(TutorialStep::Cast)),
+                        finish_target_step.run_if(in_state(TutorialStep::Target)),
+                        finish_interact_step.run_if(in_state(TutorialStep::Interact)),
+                    )
+                        .in_set(GameSet::Simulation),
+                    (
+                        update_prompt_text.run_if(
+                            state_changed::<TutorialStep>.or_else(resource_changed::<Locale>),
+                        ),
+                        place_tutorial_prompt,
+                    )
+                        .in_set(GameSet::Presentation),
+                ),
+            )
+            .add_systems(OnEnter(TutorialStep::Done), end_tutorial);
+    }
+}
+
+fn skip_tutorial(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    step: Res<State<TutorialStep>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if keyboard_input.just_pressed(SKIP_TUTORIAL_KEY) && *step.get() != TutorialStep::Done {
+        next_step.set(TutorialStep::Done);
+    }
+}
+
+// Done once any player has walked far enough from where they started
+fn finish_move_step(
+    mut starts: Local<HashMap<Entity, Vec3>>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    for (player, transform) in players.iter() {
+        let position = transform.translation().with_y(0.0);
+        let start = *starts.entry(player).or_insert(position);
+        if position.distance(start) >= MOVE_DISTANCE {
+            next_step.set(TutorialStep::Move.next());
+        }
+    }
+}
+
+fn finish_cast_step(
+    mut casts: EventReader<SkillCastEvent>,
+    players: Query<Entity, With<Player>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if casts.read().any(|cast| players.contains(cast.caster)) {
+        next_step.set(TutorialStep::Cast.next());
+    }
+}
+
+fn finish_target_step(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if keyboard_input.just_pressed(TARGET_LOCK_KEY) {
+        next_step.set(TutorialStep::Target.next());
+    }
+}
+
+fn finish_interact_step(
+    mut interactions: EventReader<InteractEvent>,
+    players: Query<(), With<Player>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if interactions
+        .read()
+        .any(|interaction| players.contains(interaction.interactor))
+    {
+        next_step.set(TutorialStep::Interact.next());
+    }
+}
+
+fn end_tutorial(
+    mut commands: Commands,
+    prompts: Query<Entity, Or<(With<TutorialPrompt>, With<TutorialLabel>)>>,
+    locale: Res<Locale>,
+    mut toasts: EventWriter<ToastEvent>,
+) {
+    for entity in prompts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    toasts.send(ToastEvent::new(locale.get("tutorial.complete")));
+}
+
+fn spawn_tutorial_prompt(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color_texture: Some(images.add(key_cap_image())),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Rectangle::new(1.2, 0.4))),
+            material,
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Billboard,
+        NotShadowCaster,
+        TutorialPrompt,
+    ));
+    // The key name is drawn over the cap and the action under it
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "",
+                TextStyle {
+                    font_size: 22.0,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                "",
+                TextStyle {
+                    font_size: 16.0,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                "",
+                TextStyle {
+                    font_size: 12.0,
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.6),
+                    ..default()
+                },
+            ),
+        ])
+        .with_text_justify(JustifyText::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            width: Val::Px(200.0),
+            display: Display::None,
+            ..default()
+        }),
+        TutorialLabel,
+    ));
+}
+
+fn update_prompt_text(
+    step: Res<State<TutorialStep>>,
+    locale: Res<Locale>,
+    mut labels: Query<&mut Text, With<TutorialLabel>>,
+) {
+    let Some((key, action)) = step.get().prompt() else {
+        return;
+    };
+    for mut text in labels.iter_mut() {
+        text.sections[0].value = key.to_string();
+        text.sections[1].value = format!("\n{}", locale.get(action));
+        text.sections[2].value = format!(
+            "\n{}",
+            locale.format(
+                "tutorial.skip",
+                &[("key", &format!("{:?}", SKIP_TUTORIAL_KEY))]
+            )
+        );
+    }
+}
+
+// Floats over the players' midpoint, hidden while that is off screen
+fn place_tutorial_prompt(
+    players: Query<&GlobalTransform, With<Player>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut prompts: Query<(&mut Transform, &mut Visibility), With<TutorialPrompt>>,
+    mut labels: Query<&mut Style, With<TutorialLabel>>,
+    ui_scale: Res<UiScale>,
+) {
+    let center = players_center(players.iter().map(GlobalTransform::translation));
+    let (Some(center), Ok((camera, camera_transform))) = (center, cameras.get_single()) else {
+        return;
+    };
+    let anchor = center + PROMPT_OFFSET;
+    let position = ScreenProjection::new(camera, camera_transform)
+        .and_then(|projection| projection.world_to_screen(anchor));
+    for (mut transform, mut visibility) in prompts.iter_mut() {
+        transform.translation = anchor;
+        *visibility = if position.is_some() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut style in labels.iter_mut() {
+        let Some(position) = position else {
+            style.display = Display::None;
+            continue;
+        };
+        style.display = Display::Flex;
+        let position = to_ui(position, &ui_scale);
+        style.left = Val::Px(position.x - 100.0);
+        style.top = Val::Px(position.y - 14.0);
+    }
+}
+
+// Blank key cap, three times as wide as it is tall, drawn in code so no asset is
+// needed; the key name is the label on top of it
+fn key_cap_image() -> Image {
+    const WIDTH: usize = 48;
+    const HEIGHT: usize = 16;
+    let mut data = vec![0u8; WIDTH * HEIGHT * 4];
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let border = x == 0 || y == 0 || x == WIDTH - 1 || y == HEIGHT - 1;
+            let pixel = if border {
+                [255, 255, 255, 255]
+            } else {
+                [30, 30, 30, 200]
+            };
+            let index = (y * WIDTH + x) * 4;
+            data[index..index + 4].copy_from_slice(&pixel);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: WIDTH as u32,
+            height: HEIGHT as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
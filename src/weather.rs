@@ -0,0 +1,221 @@
+use bevy::pbr::{FogFalloff, FogSettings, NotShadowCaster};
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::billboard::Billboard;
+use crate::schedule::GameSet;
+use crate::{players_center, MainCamera, Player};
+
+pub const RAIN_KEY: KeyCode = KeyCode::F5;
+pub const FOG_KEY: KeyCode = KeyCode::F6;
+pub const PUDDLES_KEY: KeyCode = KeyCode::F7;
+
+const RAIN_DROPS: usize = 400;
+const RAIN_RADIUS: f32 = 12.0;
+const RAIN_HEIGHT: f32 = 10.0;
+const PUDDLES: usize = 8;
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Weather {
+    pub rain: bool,
+    pub fog: bool,
+    pub puddles: bool,
+}
+
+#[derive(Component)]
+struct RainDrop {
+    speed: f32,
+}
+
+#[derive(Component)]
+struct Puddle;
+
+// Shared by every drop so they are drawn in one instanced batch
+#[derive(Resource)]
+struct RainAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .add_systems(Startup, load_rain_assets)
+            .add_systems(
+                Update,
+                (
+                    toggle_weather.in_set(GameSet::Input),
+                    ((update_rain, fall_rain).chain(), update_fog, update_puddles)
+                        .chain()
+                        .in_set(GameSet::Presentation),
+                ),
+            );
+    }
+}
+
+fn load_rain_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(RainAssets {
+        mesh: meshes.add(Mesh::from(Rectangle::new(0.02, 0.4))),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.7, 0.8, 1.0, 0.45),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+fn toggle_weather(keyboard_input: Res<ButtonInput<KeyCode>>, mut weather: ResMut<Weather>) {
+    if keyboard_input.just_pressed(RAIN_KEY) {
+        weather.rain = !weather.rain;
+    }
+    if keyboard_input.just_pressed(FOG_KEY) {
+        weather.fog = !weather.fog;
+    }
+    if keyboard_input.just_pressed(PUDDLES_KEY) {
+        weather.puddles = !weather.puddles;
+    }
+}
+
+fn random_drop_position(rng: &mut impl Rng, center: Vec3, height: f32) -> Vec3 {
+    let offset = Vec2::new(
+        rng.gen_range(-RAIN_RADIUS..RAIN_RADIUS),
+        rng.gen_range(-RAIN_RADIUS..RAIN_RADIUS),
+    );
+    Vec3::new(center.x + offset.x, height, center.z + offset.y)
+}
+
+fn update_rain(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    assets: Option<Res<RainAssets>>,
+    players: Query<&Transform, With<Player>>,
+    drops: Query<Entity, With<RainDrop>>,
+) {
+    if !weather.is_changed() {
+        return;
+    }
+    let has_rain = !drops.is_empty();
+    if weather.rain && !has_rain {
+        let Some(assets) = assets else {
+            return;
+        };
+        let center =
+            players_center(players.iter().map(|player| player.translation)).unwrap_or_default();
+        let mut rng = rand::thread_rng();
+        for _ in 0..RAIN_DROPS {
+            let height = rng.gen_range(0.0..RAIN_HEIGHT);
+            commands.spawn((
+                PbrBundle {
+                    mesh: assets.mesh.clone(),
+                    material: assets.material.clone(),
+                    transform: Transform::from_translation(random_drop_position(
+                        &mut rng, center, height,
+                    )),
+                    ..default()
+                },
+                RainDrop {
+                    speed: rng.gen_range(8.0..12.0),
+                },
+                Billboard,
+                NotShadowCaster,
+            ));
+        }
+    } else if !weather.rain && has_rain {
+        for drop in drops.iter() {
+            commands.entity(drop).despawn();
+        }
+    }
+}
+
+// Drops that reach the ground respawn at the top, around the players
+fn fall_rain(
+    time: Res<Time>,
+    players: Query<&Transform, (With<Player>, Without<RainDrop>)>,
+    mut drops: Query<(&mut Transform, &RainDrop)>,
+) {
+    let center =
+        players_center(players.iter().map(|player| player.translation)).unwrap_or_default();
+    let mut rng = rand::thread_rng();
+    for (mut transform, drop) in drops.iter_mut() {
+        transform.translation.y -= drop.speed * time.delta_seconds();
+        if transform.translation.y < 0.0 {
+            transform.translation = random_drop_position(&mut rng, center, RAIN_HEIGHT);
+        }
+    }
+}
+
+fn update_fog(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    cameras: Query<Entity, With<MainCamera>>,
+) {
+    if !weather.is_changed() {
+        return;
+    }
+    for camera in cameras.iter() {
+        if weather.fog {
+            commands.entity(camera).insert(FogSettings {
+                color: Color::srgba(0.6, 0.65, 0.7, 1.0),
+                falloff: FogFalloff::Linear {
+                    start: 5.0,
+                    end: 30.0,
+                },
+                ..default()
+            });
+        } else {
+            commands.entity(camera).remove::<FogSettings>();
+        }
+    }
+}
+
+// Flat, glossy decals just above the ground plane
+fn update_puddles(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    puddles: Query<Entity, With<Puddle>>,
+) {
+    if !weather.is_changed() {
+        return;
+    }
+    let has_puddles = !puddles.is_empty();
+    if weather.puddles && !has_puddles {
+        let mesh = meshes.add(Mesh::from(Circle::new(1.0)));
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgba(0.15, 0.2, 0.3, 0.45),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.05,
+            reflectance: 0.8,
+            ..default()
+        });
+        let mut rng = rand::thread_rng();
+        for _ in 0..PUDDLES {
+            let position = Vec3::new(rng.gen_range(-8.0..8.0), 0.01, rng.gen_range(-8.0..8.0));
+            let size = Vec3::new(rng.gen_range(0.5..1.5), rng.gen_range(0.3..1.0), 1.0);
+            commands.spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform: Transform::from_translation(position)
+                        .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+                        .with_scale(size),
+                    ..default()
+                },
+                Puddle,
+                NotShadowCaster,
+            ));
+        }
+    } else if !weather.puddles && has_puddles {
+        for puddle in puddles.iter() {
+            commands.entity(puddle).despawn();
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::camera::CameraMode;
+use crate::cursor::CursorGrab;
+use crate::schedule::GameSet;
+use crate::{MainCamera, Player};
+
+// Most systems look up the player and camera with `get_single` and quietly
+// do nothing when that fails, and a few read resources from optional
+// plugins the same way. That keeps scenes without them working, but a
+// missing player otherwise just looks like skills never casting. These
+// checks warn once when something is off, and again only if it comes back
+// after being fixed.
+pub struct WorldCheckPlugin;
+
+impl Plugin for WorldCheckPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, check_world.in_set(GameSet::Debug));
+    }
+}
+
+// Warns the first frame `problem` holds, keyed by `key`
+fn report(reported: &mut HashSet<&'static str>, key: &'static str, problem: bool, message: &str) {
+    if !problem {
+        reported.remove(key);
+    } else if reported.insert(key) {
+        warn!("{}", message);
+    }
+}
+
+fn check_world(
+    mut reported: Local<HashSet<&'static str>>,
+    players: Query<(), With<Player>>,
+    cameras: Query<(), With<MainCamera>>,
+    camera_mode: Option<Res<CameraMode>>,
+    cursor_grab: Option<Res<CursorGrab>>,
+) {
+    let players = players.iter().count();
+    report(
+        &mut reported,
+        "no player",
+        players == 0,
+        "No entity has `Player`: nothing will read input, cast skills or be chased",
+    );
+    let cameras = cameras.iter().count();
+    report(
+        &mut reported,
+        "no camera",
+        cameras == 0,
+        "No entity has `MainCamera`: movement won't follow the view and targeting won't work",
+    );
+    report(
+        &mut reported,
+        "several cameras",
+        cameras > 1,
+        "More than one entity has `MainCamera`: camera-relative systems expect exactly one",
+    );
+    report(
+        &mut reported,
+        "no camera mode",
+        camera_mode.is_none(),
+        "`CameraMode` is missing, add `CameraModePlugin` for first-person movement and billboards",
+    );
+    report(
+        &mut reported,
+        "no cursor grab",
+        cursor_grab.is_none(),
+        "`CursorGrab` is missing, add `CursorPlugin` for mouse look and cursor targeting",
+    );
+}